@@ -0,0 +1,337 @@
+//! `cfg` predicate parsing and evaluation against a build configuration.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Lit, Meta, Token};
+
+use crate::graph::{ActiveBuildConfigurationDto, BuildConfigurationDto};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum CfgPredicate {
+    Name(String),
+    KeyValue(String, String),
+    All(Vec<CfgPredicate>),
+    Any(Vec<CfgPredicate>),
+    Not(Box<CfgPredicate>),
+}
+
+impl CfgPredicate {
+    fn from_meta(meta: &Meta) -> Option<Self> {
+        match meta {
+            Meta::Path(path) => Some(Self::Name(path.get_ident()?.to_string())),
+            Meta::NameValue(name_value) => {
+                let Expr::Lit(expr) = &name_value.value else {
+                    return None;
+                };
+                let Lit::Str(value) = &expr.lit else {
+                    return None;
+                };
+                Some(Self::KeyValue(
+                    name_value.path.get_ident()?.to_string(),
+                    value.value(),
+                ))
+            }
+            Meta::List(list) => {
+                let nested = list
+                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    .ok()?
+                    .iter()
+                    .map(Self::from_meta)
+                    .collect::<Option<Vec<_>>>()?;
+                match list.path.get_ident()?.to_string().as_str() {
+                    "all" => Some(Self::All(nested)),
+                    "any" => Some(Self::Any(nested)),
+                    "not" if nested.len() == 1 => {
+                        Some(Self::Not(Box::new(nested.into_iter().next()?)))
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Joins predicates the way nested `cfg` scopes combine.
+    pub(super) fn all(predicates: &[CfgPredicate]) -> Option<Self> {
+        match predicates {
+            [] => None,
+            [predicate] => Some(predicate.clone()),
+            predicates => Some(Self::All(predicates.to_vec())),
+        }
+    }
+
+    pub(super) fn evaluate(&self, environment: &CfgEnvironment<'_>) -> bool {
+        match self {
+            Self::Name(name) => environment.name_is_set(name),
+            Self::KeyValue(key, value) => environment.key_value_is_set(key, value),
+            Self::All(predicates) => predicates
+                .iter()
+                .all(|predicate| predicate.evaluate(environment)),
+            Self::Any(predicates) => predicates
+                .iter()
+                .any(|predicate| predicate.evaluate(environment)),
+            Self::Not(predicate) => !predicate.evaluate(environment),
+        }
+    }
+}
+
+impl fmt::Display for CfgPredicate {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |formatter: &mut fmt::Formatter<'_>, name: &str, predicates: &[Self]| {
+            write!(formatter, "{name}(")?;
+            for (index, predicate) in predicates.iter().enumerate() {
+                if index > 0 {
+                    formatter.write_str(", ")?;
+                }
+                write!(formatter, "{predicate}")?;
+            }
+            formatter.write_str(")")
+        };
+
+        match self {
+            Self::Name(name) => formatter.write_str(name),
+            Self::KeyValue(key, value) => write!(formatter, "{key} = {value:?}"),
+            Self::All(predicates) => list(formatter, "all", predicates),
+            Self::Any(predicates) => list(formatter, "any", predicates),
+            Self::Not(predicate) => write!(formatter, "not({predicate})"),
+        }
+    }
+}
+
+/// Predicates of every `#[cfg(...)]` attribute. Unparseable predicates are
+/// skipped so the item stays visible.
+pub(super) fn cfg_predicates(attributes: &[Attribute]) -> Vec<CfgPredicate> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("cfg"))
+        .filter_map(|attribute| {
+            attribute
+                .parse_args::<Meta>()
+                .ok()
+                .and_then(|meta| CfgPredicate::from_meta(&meta))
+        })
+        .collect()
+}
+
+/// Cfg values for one crate: the shared target configuration plus that
+/// crate's enabled features.
+pub(super) struct CfgEnvironment<'a> {
+    pub(super) configuration: &'a ActiveBuildConfigurationDto,
+    pub(super) features: &'a BTreeSet<String>,
+}
+
+impl CfgEnvironment<'_> {
+    fn name_is_set(&self, name: &str) -> bool {
+        let configuration = self.configuration;
+        match name {
+            "test" => configuration.test,
+            "debug_assertions" => configuration.debug_assertions,
+            "unix" | "windows" => configuration.target_family == name,
+            _ => configuration.cfgs.iter().any(|cfg| cfg == name),
+        }
+    }
+
+    fn key_value_is_set(&self, key: &str, value: &str) -> bool {
+        let configuration = self.configuration;
+        match key {
+            "feature" => self.features.contains(value),
+            "target_os" => configuration.target_os == value,
+            "target_family" => configuration.target_family == value,
+            "target_arch" => configuration.target_arch == value,
+            "target_pointer_width" => configuration.target_pointer_width == value,
+            "target_endian" => configuration.target_endian == value,
+            "target_env" => configuration.target_env == value,
+            _ => configuration
+                .cfgs
+                .iter()
+                .any(|cfg| cfg == &format!("{key}={value:?}")),
+        }
+    }
+}
+
+/// Resolves requested flags against the host target and each workspace
+/// crate's declared `[features]` table, keyed by crate name.
+pub(super) fn resolve_build_configuration(
+    requested: &BuildConfigurationDto,
+    declared_features_by_crate: &BTreeMap<String, BTreeMap<String, Vec<String>>>,
+) -> ActiveBuildConfigurationDto {
+    let features_by_crate = declared_features_by_crate
+        .iter()
+        .map(|(crate_name, declared)| {
+            let features = enabled_features(requested, crate_name, declared);
+            (crate_name.clone(), features.into_iter().collect())
+        })
+        .collect();
+
+    ActiveBuildConfigurationDto {
+        features_by_crate,
+        target_os: requested
+            .target_os
+            .clone()
+            .unwrap_or_else(|| std::env::consts::OS.to_string()),
+        target_family: requested
+            .target_family
+            .clone()
+            .unwrap_or_else(|| std::env::consts::FAMILY.to_string()),
+        target_arch: requested
+            .target_arch
+            .clone()
+            .unwrap_or_else(|| std::env::consts::ARCH.to_string()),
+        target_pointer_width: requested
+            .target_pointer_width
+            .clone()
+            .unwrap_or_else(|| usize::BITS.to_string()),
+        target_endian: requested.target_endian.clone().unwrap_or_else(|| {
+            if cfg!(target_endian = "big") {
+                "big"
+            } else {
+                "little"
+            }
+            .to_string()
+        }),
+        target_env: requested
+            .target_env
+            .clone()
+            .unwrap_or_else(|| host_target_env().to_string()),
+        test: requested.test,
+        debug_assertions: !requested.release,
+        cfgs: requested.cfgs.clone(),
+        prune_inactive: requested.prune_inactive,
+    }
+}
+
+/// Expands default and requested features through the `[features]` table.
+/// `dep:` entries and `dependency/feature` entries only affect dependencies
+/// and are not crate features.
+fn enabled_features(
+    requested: &BuildConfigurationDto,
+    crate_name: &str,
+    declared: &BTreeMap<String, Vec<String>>,
+) -> BTreeSet<String> {
+    let mut pending = Vec::new();
+    if requested.all_features {
+        pending.extend(declared.keys().cloned());
+    } else if !requested.no_default_features && declared.contains_key("default") {
+        pending.push("default".to_string());
+    }
+    for feature in &requested.features {
+        match feature.split_once('/') {
+            Some((target_crate, feature)) if target_crate == crate_name => {
+                pending.push(feature.to_string());
+            }
+            Some(_) => {}
+            None if declared.contains_key(feature) => pending.push(feature.clone()),
+            None => {}
+        }
+    }
+
+    let mut enabled = BTreeSet::new();
+    while let Some(feature) = pending.pop() {
+        if !enabled.insert(feature.clone()) {
+            continue;
+        }
+        for entry in declared.get(&feature).into_iter().flatten() {
+            if !entry.starts_with("dep:") && !entry.contains('/') {
+                pending.push(entry.clone());
+            }
+        }
+    }
+    enabled
+}
+
+fn host_target_env() -> &'static str {
+    if cfg!(target_env = "gnu") {
+        "gnu"
+    } else if cfg!(target_env = "musl") {
+        "musl"
+    } else if cfg!(target_env = "msvc") {
+        "msvc"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::{cfg_predicates, resolve_build_configuration, CfgEnvironment, CfgPredicate};
+    use crate::graph::{ActiveBuildConfigurationDto, BuildConfigurationDto};
+
+    fn predicate(source: &str) -> CfgPredicate {
+        let item: syn::ItemFn =
+            syn::parse_str(&format!("{source} fn item() {{}}")).expect("parse cfg fixture");
+        cfg_predicates(&item.attrs)
+            .into_iter()
+            .next()
+            .expect("cfg predicate")
+    }
+
+    #[test]
+    fn parses_and_prints_nested_predicates() {
+        let parsed = predicate(r#"#[cfg(all(feature = "cli", not(any(test, windows))))]"#);
+
+        assert_eq!(
+            parsed.to_string(),
+            r#"all(feature = "cli", not(any(test, windows)))"#
+        );
+        assert_eq!(
+            CfgPredicate::all(&[predicate("#[cfg(unix)]"), predicate("#[cfg(test)]")])
+                .map(|predicate| predicate.to_string()),
+            Some("all(unix, test)".to_string())
+        );
+    }
+
+    #[test]
+    fn evaluates_against_default_and_requested_features() {
+        let declared = BTreeMap::from([(
+            "app".to_string(),
+            BTreeMap::from([
+                ("default".to_string(), vec!["cli".to_string()]),
+                (
+                    "cli".to_string(),
+                    vec!["color".to_string(), "dep:clap".to_string()],
+                ),
+                ("color".to_string(), Vec::new()),
+                ("server".to_string(), Vec::new()),
+            ]),
+        )]);
+        let defaults = resolve_build_configuration(&BuildConfigurationDto::default(), &declared);
+        let requested = resolve_build_configuration(
+            &BuildConfigurationDto {
+                features: vec!["app/server".to_string()],
+                no_default_features: true,
+                test: true,
+                target_os: Some("windows".to_string()),
+                target_family: Some("windows".to_string()),
+                ..BuildConfigurationDto::default()
+            },
+            &declared,
+        );
+
+        assert_eq!(
+            defaults.features_by_crate["app"],
+            vec!["cli", "color", "default"]
+        );
+        assert_eq!(requested.features_by_crate["app"], vec!["server"]);
+
+        let cli_only = predicate(r#"#[cfg(all(feature = "color", not(test)))]"#);
+        let windows_tests = predicate(r#"#[cfg(all(windows, test, feature = "server"))]"#);
+        let evaluate = |predicate: &CfgPredicate, configuration: &ActiveBuildConfigurationDto| {
+            let features = configuration.features_by_crate["app"]
+                .iter()
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            predicate.evaluate(&CfgEnvironment {
+                configuration,
+                features: &features,
+            })
+        };
+
+        assert!(evaluate(&cli_only, &defaults));
+        assert!(!evaluate(&cli_only, &requested));
+        assert!(evaluate(&windows_tests, &requested));
+        assert!(!evaluate(&windows_tests, &defaults));
+    }
+}
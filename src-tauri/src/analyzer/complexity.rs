@@ -0,0 +1,186 @@
+//! Syntax-level size and complexity measurement for function bodies.
+
+use syn::visit::{self, Visit};
+use syn::{BinOp, Block, Signature};
+
+use crate::graph::metrics::FunctionComplexityDto;
+
+/// Measures one function from its signature and body. Nested item
+/// definitions are measured separately and do not count toward the outer
+/// function; closures do.
+pub(super) fn function_complexity(
+    signature: &Signature,
+    block: &Block,
+    source: &str,
+) -> FunctionComplexityDto {
+    let start_line = signature.fn_token.span.start().line;
+    let end_line = block.brace_token.span.close().end().line;
+    let lines_of_code = source
+        .lines()
+        .skip(start_line.saturating_sub(1))
+        .take(end_line.saturating_sub(start_line) + 1)
+        .filter(|line| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with("//")
+        })
+        .count();
+
+    let mut visitor = ComplexityVisitor {
+        complexity: FunctionComplexityDto {
+            lines_of_code: lines_of_code as u32,
+            cyclomatic_complexity: 1,
+            parameter_count: signature.inputs.len() as u32,
+            ..FunctionComplexityDto::default()
+        },
+        depth: 0,
+    };
+    visitor.visit_block(block);
+    visitor.complexity
+}
+
+struct ComplexityVisitor {
+    complexity: FunctionComplexityDto,
+    depth: u32,
+}
+
+impl ComplexityVisitor {
+    fn nested(&mut self, visit_children: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        self.complexity.max_nesting_depth = self.complexity.max_nesting_depth.max(self.depth);
+        visit_children(self);
+        self.depth -= 1;
+    }
+}
+
+impl<'ast> Visit<'ast> for ComplexityVisitor {
+    fn visit_item(&mut self, _node: &'ast syn::Item) {}
+
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        self.complexity.cyclomatic_complexity += 1;
+        self.visit_expr(&node.cond);
+        self.nested(|visitor| visitor.visit_block(&node.then_branch));
+        if let Some((_, else_branch)) = &node.else_branch {
+            // `else if` chains stay at the depth of the first `if`.
+            if let syn::Expr::If(_) = else_branch.as_ref() {
+                self.visit_expr(else_branch);
+            } else {
+                self.nested(|visitor| visitor.visit_expr(else_branch));
+            }
+        }
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        self.complexity.cyclomatic_complexity += node.arms.len().saturating_sub(1) as u32;
+        self.complexity.cyclomatic_complexity +=
+            node.arms.iter().filter(|arm| arm.guard.is_some()).count() as u32;
+        self.visit_expr(&node.expr);
+        self.nested(|visitor| {
+            for arm in &node.arms {
+                visitor.visit_arm(arm);
+            }
+        });
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast syn::ExprWhile) {
+        self.complexity.cyclomatic_complexity += 1;
+        self.nested(|visitor| visit::visit_expr_while(visitor, node));
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        self.complexity.cyclomatic_complexity += 1;
+        self.nested(|visitor| visit::visit_expr_for_loop(visitor, node));
+    }
+
+    fn visit_expr_loop(&mut self, node: &'ast syn::ExprLoop) {
+        self.complexity.cyclomatic_complexity += 1;
+        self.nested(|visitor| visit::visit_expr_loop(visitor, node));
+    }
+
+    fn visit_expr_closure(&mut self, node: &'ast syn::ExprClosure) {
+        self.nested(|visitor| visit::visit_expr_closure(visitor, node));
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, BinOp::And(_) | BinOp::Or(_)) {
+            self.complexity.cyclomatic_complexity += 1;
+        }
+        visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast syn::ExprTry) {
+        self.complexity.question_mark_count += 1;
+        visit::visit_expr_try(self, node);
+    }
+
+    fn visit_expr_await(&mut self, node: &'ast syn::ExprAwait) {
+        self.complexity.await_count += 1;
+        visit::visit_expr_await(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        match node.method.to_string().as_str() {
+            "unwrap" => self.complexity.unwrap_count += 1,
+            "expect" => self.complexity.expect_count += 1,
+            _ => {}
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::function_complexity;
+
+    fn measure(source: &str) -> crate::graph::metrics::FunctionComplexityDto {
+        let item: syn::ItemFn = syn::parse_str(source).expect("parse function");
+        function_complexity(&item.sig, &item.block, source)
+    }
+
+    #[test]
+    fn counts_branches_nesting_and_error_sites() {
+        let complexity = measure(
+            "async fn load(path: &str, retries: u32) -> Result<(), Error> {
+    // read the file
+    for attempt in 0..retries {
+        if attempt > 0 && path.is_empty() {
+            continue;
+        } else if attempt > 2 {
+            let value = fetch().await?;
+            value.unwrap();
+        }
+    }
+
+    match open(path).expect(\"open\") {
+        Some(file) if file.ok() => file.close()?,
+        Some(_) => {}
+        None => {}
+    }
+    Ok(())
+}",
+        );
+
+        assert_eq!(complexity.parameter_count, 2);
+        assert_eq!(complexity.lines_of_code, 16);
+        // 1 + for + if + && + else if + 2 extra arms + 1 guard
+        assert_eq!(complexity.cyclomatic_complexity, 8);
+        assert_eq!(complexity.max_nesting_depth, 2);
+        assert_eq!(complexity.question_mark_count, 2);
+        assert_eq!(complexity.await_count, 1);
+        assert_eq!(complexity.unwrap_count, 1);
+        assert_eq!(complexity.expect_count, 1);
+    }
+
+    #[test]
+    fn nested_items_are_not_measured_with_their_parent() {
+        let complexity = measure(
+            "fn outer() {
+    fn inner(flag: bool) { if flag { helper().unwrap(); } }
+    let closure = |value: Option<u8>| value.unwrap();
+}",
+        );
+
+        assert_eq!(complexity.cyclomatic_complexity, 1);
+        assert_eq!(complexity.unwrap_count, 1);
+        assert_eq!(complexity.max_nesting_depth, 1);
+    }
+}
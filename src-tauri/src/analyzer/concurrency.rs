@@ -0,0 +1,378 @@
+//! Syntax-level concurrency facts: spawned tasks, channels, sync primitive
+//! fields, and the sites that send, receive, lock, or touch atomics.
+
+use std::collections::BTreeMap;
+
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Block, Expr, ExprCall, ExprMethodCall, GenericArgument, Local, Pat, PathArguments, Type,
+};
+
+const SPAWN_FUNCTIONS: &[&str] = &["spawn", "spawn_blocking", "spawn_local"];
+const CHANNEL_CONSTRUCTORS: &[&str] = &[
+    "channel",
+    "sync_channel",
+    "unbounded_channel",
+    "unbounded",
+    "bounded",
+];
+/// Wrappers looked through when classifying a field type.
+const SHARED_WRAPPERS: &[&str] = &["Arc", "Rc", "Box", "Option"];
+
+/// What a sync primitive field or binding can be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PrimitiveClass {
+    Lock,
+    Atomic,
+    Sender,
+    Receiver,
+    /// `Condvar`, `Notify`, `Semaphore`, or `Barrier`.
+    Signal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SyncOperation {
+    Send,
+    Receive,
+    Lock,
+    Atomic,
+}
+
+impl SyncOperation {
+    fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "send" | "try_send" | "blocking_send" | "send_timeout" => Some(Self::Send),
+            "recv" | "try_recv" | "blocking_recv" | "recv_timeout" => Some(Self::Receive),
+            "lock" | "read" | "write" | "try_lock" | "try_read" | "try_write" | "blocking_lock"
+            | "blocking_read" | "blocking_write" => Some(Self::Lock),
+            "load"
+            | "store"
+            | "swap"
+            | "compare_exchange"
+            | "compare_exchange_weak"
+            | "fetch_add"
+            | "fetch_sub"
+            | "fetch_and"
+            | "fetch_or"
+            | "fetch_xor"
+            | "fetch_max"
+            | "fetch_min"
+            | "fetch_update" => Some(Self::Atomic),
+            _ => None,
+        }
+    }
+
+    /// Whether the operation can target a primitive of `class`, so
+    /// `file.read()` on a non-lock field never links.
+    pub(super) fn applies_to(self, class: PrimitiveClass) -> bool {
+        matches!(
+            (self, class),
+            (Self::Send, PrimitiveClass::Sender)
+                | (Self::Receive, PrimitiveClass::Receiver)
+                | (Self::Lock, PrimitiveClass::Lock)
+                | (Self::Atomic, PrimitiveClass::Atomic)
+        )
+    }
+}
+
+/// Classifies a field type such as `RwLock<Config>`, `Arc<AtomicBool>`, or
+/// `mpsc::Sender<Job>`, looking through `Arc`, `Rc`, `Box`, and `Option`.
+pub(super) fn primitive_class(ty: &Type) -> Option<PrimitiveClass> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let name = segment.ident.to_string();
+    if SHARED_WRAPPERS.contains(&name.as_str()) {
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+        return arguments.args.iter().find_map(|argument| match argument {
+            GenericArgument::Type(inner) => primitive_class(inner),
+            _ => None,
+        });
+    }
+    match name.as_str() {
+        "Mutex" | "RwLock" | "ReentrantMutex" => Some(PrimitiveClass::Lock),
+        "Sender" | "SyncSender" | "UnboundedSender" => Some(PrimitiveClass::Sender),
+        "Receiver" | "UnboundedReceiver" => Some(PrimitiveClass::Receiver),
+        "Condvar" | "Notify" | "Semaphore" | "Barrier" => Some(PrimitiveClass::Signal),
+        atomic if atomic.starts_with("Atomic") => Some(PrimitiveClass::Atomic),
+        _ => None,
+    }
+}
+
+/// A `spawn`, `spawn_blocking`, or `spawn_local` call through a qualified
+/// path such as `tokio::spawn` or `std::thread::spawn`.
+#[derive(Debug, Clone)]
+pub(super) struct TaskFact {
+    pub(super) spawner: String,
+    /// Enclosing spawned task, as an index into [`BodyConcurrency::tasks`];
+    /// `None` when the function itself spawns it.
+    pub(super) parent: Option<usize>,
+    pub(super) span: Span,
+}
+
+/// A channel created by `let (tx, rx) = mpsc::channel(..)` or a similar
+/// constructor, with every local name bound to one of its endpoints.
+#[derive(Debug, Clone)]
+pub(super) struct ChannelFact {
+    pub(super) constructor: String,
+    pub(super) endpoints: Vec<String>,
+    pub(super) span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum SyncTarget {
+    /// `self.field` or `value.field`, resolved later against struct fields.
+    Field(String),
+    /// Index into [`BodyConcurrency::channels`].
+    Channel(usize),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SyncAccessFact {
+    pub(super) operation: SyncOperation,
+    pub(super) target: SyncTarget,
+    /// Innermost spawned task containing the site, as an index into
+    /// [`BodyConcurrency::tasks`]; `None` for the function itself.
+    pub(super) task: Option<usize>,
+    pub(super) span: Span,
+}
+
+/// Concurrency facts of one function body, including closures and async
+/// blocks. Calls inside macro input are not parsed.
+#[derive(Debug, Default)]
+pub(super) struct BodyConcurrency {
+    pub(super) tasks: Vec<TaskFact>,
+    pub(super) channels: Vec<ChannelFact>,
+    pub(super) accesses: Vec<SyncAccessFact>,
+}
+
+pub(super) fn body_concurrency(block: &Block) -> BodyConcurrency {
+    let mut visitor = ConcurrencyVisitor::default();
+    visitor.visit_block(block);
+    visitor.facts
+}
+
+#[derive(Default)]
+struct ConcurrencyVisitor {
+    facts: BodyConcurrency,
+    /// Channel index by local endpoint name. Shadowing is not tracked.
+    bindings: BTreeMap<String, usize>,
+    task_stack: Vec<usize>,
+}
+
+impl<'ast> Visit<'ast> for ConcurrencyVisitor {
+    fn visit_local(&mut self, node: &'ast Local) {
+        if let Some(init) = &node.init {
+            let names = pattern_names(&node.pat);
+            if let Some(constructor) = channel_constructor(&init.expr) {
+                self.facts.channels.push(ChannelFact {
+                    constructor,
+                    endpoints: names.clone(),
+                    span: node.span(),
+                });
+                let channel = self.facts.channels.len() - 1;
+                for name in names {
+                    self.bindings.insert(name, channel);
+                }
+            } else if let (Some(channel), [name]) = (
+                cloned_endpoint(&init.expr, &self.bindings),
+                names.as_slice(),
+            ) {
+                self.bindings.insert(name.clone(), channel);
+                self.facts.channels[channel].endpoints.push(name.clone());
+            }
+        }
+        visit::visit_local(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        let Some(spawner) = qualified_call(&node.func, SPAWN_FUNCTIONS) else {
+            visit::visit_expr_call(self, node);
+            return;
+        };
+        self.facts.tasks.push(TaskFact {
+            spawner,
+            parent: self.task_stack.last().copied(),
+            span: node.span(),
+        });
+        self.task_stack.push(self.facts.tasks.len() - 1);
+        visit::visit_expr_call(self, node);
+        self.task_stack.pop();
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        if let Some(operation) = SyncOperation::from_method(&node.method.to_string()) {
+            let target = match node.receiver.as_ref() {
+                Expr::Field(field) => match &field.member {
+                    syn::Member::Named(name) => Some(SyncTarget::Field(name.to_string())),
+                    syn::Member::Unnamed(_) => None,
+                },
+                receiver if matches!(operation, SyncOperation::Send | SyncOperation::Receive) => {
+                    local_name(receiver)
+                        .and_then(|name| self.bindings.get(&name))
+                        .map(|channel| SyncTarget::Channel(*channel))
+                }
+                _ => None,
+            };
+            if let Some(target) = target {
+                self.facts.accesses.push(SyncAccessFact {
+                    operation,
+                    target,
+                    task: self.task_stack.last().copied(),
+                    span: node.method.span(),
+                });
+            }
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+}
+
+/// Path text of a call like `tokio::task::spawn_blocking(..)` whose last
+/// segment is in `names` and that has at least one qualifying segment.
+fn qualified_call(func: &Expr, names: &[&str]) -> Option<String> {
+    let Expr::Path(path) = func else {
+        return None;
+    };
+    let segments = &path.path.segments;
+    let last = segments.last()?.ident.to_string();
+    if segments.len() < 2 || !names.contains(&last.as_str()) {
+        return None;
+    }
+    Some(
+        segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::"),
+    )
+}
+
+fn channel_constructor(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Call(call) => qualified_call(&call.func, CHANNEL_CONSTRUCTORS),
+        _ => None,
+    }
+}
+
+/// Channel of `tx.clone()` when `tx` is a known endpoint.
+fn cloned_endpoint(expr: &Expr, bindings: &BTreeMap<String, usize>) -> Option<usize> {
+    let Expr::MethodCall(call) = expr else {
+        return None;
+    };
+    if call.method != "clone" {
+        return None;
+    }
+    bindings.get(&local_name(&call.receiver)?).copied()
+}
+
+fn local_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(ToString::to_string),
+        Expr::Reference(reference) => local_name(&reference.expr),
+        _ => None,
+    }
+}
+
+/// Identifiers bound by `name`, `mut name`, `(tx, rx)`, or `name: Type`.
+fn pattern_names(pattern: &Pat) -> Vec<String> {
+    match pattern {
+        Pat::Ident(ident) => vec![ident.ident.to_string()],
+        Pat::Tuple(tuple) => tuple.elems.iter().flat_map(pattern_names).collect(),
+        Pat::Type(typed) => pattern_names(&typed.pat),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{body_concurrency, primitive_class, PrimitiveClass, SyncOperation, SyncTarget};
+
+    #[test]
+    fn classifies_sync_field_types() {
+        let class = |source: &str| primitive_class(&syn::parse_str(source).expect("parse type"));
+
+        assert_eq!(class("RwLock<AppConfigDto>"), Some(PrimitiveClass::Lock));
+        assert_eq!(
+            class("std::sync::Arc<AtomicBool>"),
+            Some(PrimitiveClass::Atomic)
+        );
+        assert_eq!(
+            class("mpsc::UnboundedSender<Job>"),
+            Some(PrimitiveClass::Sender)
+        );
+        assert_eq!(
+            class("Option<Receiver<u8>>"),
+            Some(PrimitiveClass::Receiver)
+        );
+        assert_eq!(class("Arc<Notify>"), Some(PrimitiveClass::Signal));
+        assert_eq!(class("Vec<Mutex<u8>>"), None);
+        assert_eq!(class("String"), None);
+    }
+
+    #[test]
+    fn records_spawns_channels_and_sync_sites_inside_tasks() {
+        let block: syn::Block = syn::parse_str(
+            r#"{
+                let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+                let worker_tx = tx.clone();
+                tokio::spawn(async move {
+                    worker_tx.send(1).await.ok();
+                    self.running.store(true, Ordering::SeqCst);
+                });
+                let guard = self.config.read().await;
+                let value = rx.recv().await;
+                std::thread::spawn(|| {
+                    tokio::task::spawn_blocking(|| {});
+                });
+                spawn(|| {});
+                file.read(&mut buffer);
+            }"#,
+        )
+        .expect("parse block");
+
+        let facts = body_concurrency(&block);
+
+        assert_eq!(
+            facts
+                .tasks
+                .iter()
+                .map(|task| (task.spawner.as_str(), task.parent))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tokio::spawn", None),
+                ("std::thread::spawn", None),
+                ("tokio::task::spawn_blocking", Some(1)),
+            ]
+        );
+        assert_eq!(facts.channels.len(), 1);
+        assert_eq!(facts.channels[0].constructor, "tokio::sync::mpsc::channel");
+        assert_eq!(facts.channels[0].endpoints, vec!["tx", "rx", "worker_tx"]);
+        let accesses = facts
+            .accesses
+            .iter()
+            .map(|access| (access.operation, access.target.clone(), access.task))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            accesses,
+            vec![
+                (SyncOperation::Send, SyncTarget::Channel(0), Some(0)),
+                (
+                    SyncOperation::Atomic,
+                    SyncTarget::Field("running".to_string()),
+                    Some(0)
+                ),
+                (
+                    SyncOperation::Lock,
+                    SyncTarget::Field("config".to_string()),
+                    None
+                ),
+                (SyncOperation::Receive, SyncTarget::Channel(0), None),
+            ]
+        );
+    }
+}
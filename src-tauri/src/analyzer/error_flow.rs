@@ -0,0 +1,535 @@
+//! Syntax-level error facts: `Result` error types, `?` sites, `map_err`
+//! conversions, `From` impls, and thiserror `#[from]` fields.
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Block, Expr, ExprMethodCall, FnArg, GenericArgument, ImplItem, ItemEnum, ItemImpl, Pat,
+    PathArguments, ReturnType, Signature, Stmt, Type,
+};
+
+use super::extraction::span_range;
+use super::signature::type_text;
+use crate::graph::error_flow::{
+    ErrorConversionDto, ErrorConversionKindDto, ErrorDetailDto, ErrorFlowFactsDto, TrySiteDto,
+};
+
+/// Macros whose output is text built from their arguments.
+const FORMATTING_MACROS: &[&str] = &[
+    "format",
+    "format_args",
+    "anyhow",
+    "eyre",
+    "bail",
+    "panic",
+    "write",
+    "writeln",
+];
+
+/// Error facts of a function or method body, or `None` when it neither
+/// returns a `Result` nor propagates or converts errors. `self_type` names
+/// the impl type that `Self::Variant(..)` in a `map_err` closure produces.
+pub(super) fn function_error_flow(
+    relative_path: &str,
+    signature: &Signature,
+    block: &Block,
+    self_type: Option<&str>,
+) -> Option<ErrorFlowFactsDto> {
+    let mut visitor = ErrorFlowVisitor {
+        relative_path,
+        self_type,
+        closure_depth: 0,
+        facts: ErrorFlowFactsDto {
+            error_type: result_error_type(&signature.output),
+            ..ErrorFlowFactsDto::default()
+        },
+    };
+    visitor.visit_block(block);
+    let facts = visitor.facts;
+    (facts.error_type.is_some() || !facts.try_sites.is_empty() || !facts.conversions.is_empty())
+        .then_some(facts)
+}
+
+/// The conversion declared by `impl From<Source> for Target`, with the
+/// detail its `from` body keeps of the source value.
+pub(super) fn from_impl_conversion(
+    relative_path: &str,
+    item: &ItemImpl,
+) -> Option<ErrorConversionDto> {
+    let (_, trait_path, _) = item.trait_.as_ref()?;
+    let segment = trait_path.segments.last()?;
+    if segment.ident != "From" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    let source_type = arguments.args.iter().find_map(|argument| match argument {
+        GenericArgument::Type(ty) => Some(error_type_text(ty)),
+        _ => None,
+    })?;
+    let detail = item
+        .items
+        .iter()
+        .find_map(|impl_item| match impl_item {
+            ImplItem::Fn(method) if method.sig.ident == "from" => {
+                let parameter = method.sig.inputs.first().and_then(|input| match input {
+                    FnArg::Typed(typed) => Some(typed.pat.as_ref()),
+                    FnArg::Receiver(_) => None,
+                })?;
+                Some(detail_kept(parameter, |visitor| {
+                    visitor.visit_block(&method.block)
+                }))
+            }
+            _ => None,
+        })
+        .unwrap_or(ErrorDetailDto::Preserved);
+
+    Some(ErrorConversionDto {
+        kind: ErrorConversionKindDto::FromImpl,
+        source_type: Some(source_type),
+        target_type: Some(error_type_text(&item.self_ty)),
+        callee: None,
+        detail,
+        range: span_range(relative_path, trait_path.span()),
+    })
+}
+
+/// `From` conversions thiserror generates for `#[from]` variant fields.
+pub(super) fn from_attribute_conversions(
+    relative_path: &str,
+    item: &ItemEnum,
+) -> Vec<ErrorConversionDto> {
+    item.variants
+        .iter()
+        .flat_map(|variant| &variant.fields)
+        .filter(|field| {
+            field
+                .attrs
+                .iter()
+                .any(|attribute| attribute.path().is_ident("from"))
+        })
+        .map(|field| ErrorConversionDto {
+            kind: ErrorConversionKindDto::FromAttribute,
+            source_type: Some(error_type_text(&field.ty)),
+            target_type: Some(item.ident.to_string()),
+            callee: None,
+            detail: ErrorDetailDto::Preserved,
+            range: span_range(relative_path, field.span()),
+        })
+        .collect()
+}
+
+/// `E` of `Result<T, E>`, or `module::Error` for a one-argument
+/// `module::Result<T>` alias such as `io::Result<T>` or `anyhow::Result<T>`.
+fn result_error_type(output: &ReturnType) -> Option<String> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::Path(path) = ty.as_ref() else {
+        return None;
+    };
+    let segments = &path.path.segments;
+    let last = segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &last.arguments else {
+        return None;
+    };
+    let types = arguments
+        .args
+        .iter()
+        .filter_map(|argument| match argument {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    match types.as_slice() {
+        [_, error] => Some(error_type_text(error)),
+        [_] if segments.len() > 1 => {
+            let module = segments
+                .iter()
+                .take(segments.len() - 1)
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            Some(format!("{module}::Error"))
+        }
+        _ => None,
+    }
+}
+
+/// Type text without a leading `crate::`, `self::`, or `super::`, so one
+/// workspace error type is named the same from every module.
+fn error_type_text(ty: &Type) -> String {
+    let mut text = type_text(ty);
+    while let Some(rest) = ["crate::", "self::", "super::"]
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+    {
+        text = rest.to_string();
+    }
+    text
+}
+
+struct ErrorFlowVisitor<'a> {
+    relative_path: &'a str,
+    self_type: Option<&'a str>,
+    /// `?` inside a closure or async block returns from that closure.
+    closure_depth: usize,
+    facts: ErrorFlowFactsDto,
+}
+
+impl<'ast> Visit<'ast> for ErrorFlowVisitor<'_> {
+    fn visit_item(&mut self, _node: &'ast syn::Item) {}
+
+    fn visit_expr_closure(&mut self, node: &'ast syn::ExprClosure) {
+        self.closure_depth += 1;
+        visit::visit_expr_closure(self, node);
+        self.closure_depth -= 1;
+    }
+
+    fn visit_expr_async(&mut self, node: &'ast syn::ExprAsync) {
+        self.closure_depth += 1;
+        visit::visit_expr_async(self, node);
+        self.closure_depth -= 1;
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast syn::ExprTry) {
+        if self.closure_depth == 0 {
+            let callee = match unwrap_await(&node.expr) {
+                Expr::MethodCall(call) if call.method == "map_err" => None,
+                expr => callee_name(expr),
+            };
+            self.facts.try_sites.push(TrySiteDto {
+                range: span_range(self.relative_path, node.question_token.span),
+                callee,
+            });
+        }
+        visit::visit_expr_try(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        if node.method == "map_err" && node.args.len() == 1 {
+            self.facts.conversions.push(self.map_err_conversion(node));
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+}
+
+impl ErrorFlowVisitor<'_> {
+    fn map_err_conversion(&self, node: &ExprMethodCall) -> ErrorConversionDto {
+        let (target_type, detail) = match &node.args[0] {
+            Expr::Closure(closure) => {
+                let detail = match closure.inputs.first() {
+                    Some(parameter) => {
+                        detail_kept(parameter, |visitor| visitor.visit_expr(&closure.body))
+                    }
+                    None => ErrorDetailDto::Discarded,
+                };
+                (produced_type(&closure.body, self.self_type), detail)
+            }
+            Expr::Path(path) => (
+                constructed_type(&path.path, self.self_type, false),
+                ErrorDetailDto::Preserved,
+            ),
+            _ => (None, ErrorDetailDto::Preserved),
+        };
+        ErrorConversionDto {
+            kind: ErrorConversionKindDto::MapErr,
+            source_type: None,
+            target_type,
+            callee: callee_name(unwrap_await(&node.receiver)),
+            detail,
+            range: span_range(self.relative_path, node.method.span()),
+        }
+    }
+}
+
+fn unwrap_await(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Await(awaited) => unwrap_await(&awaited.base),
+        Expr::Paren(paren) => unwrap_await(&paren.expr),
+        expr => expr,
+    }
+}
+
+fn callee_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Call(call) => match call.func.as_ref() {
+            Expr::Path(path) => path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        },
+        Expr::MethodCall(call) => Some(call.method.to_string()),
+        _ => None,
+    }
+}
+
+/// Type a `map_err` closure body evaluates to, when the body names it:
+/// `Error::Variant(..)`, `Type::constructor(..)`, `Type { .. }`,
+/// `TupleStruct(..)`, or text from `to_string()` and `format!`.
+fn produced_type(expr: &Expr, self_type: Option<&str>) -> Option<String> {
+    match expr {
+        Expr::Block(block) => match block.block.stmts.last()? {
+            Stmt::Expr(expr, None) => produced_type(expr, self_type),
+            _ => None,
+        },
+        Expr::Paren(paren) => produced_type(&paren.expr, self_type),
+        Expr::Call(call) => match call.func.as_ref() {
+            Expr::Path(path) => constructed_type(&path.path, self_type, true),
+            _ => None,
+        },
+        Expr::Struct(structure) => constructed_type(&structure.path, self_type, true),
+        Expr::Path(path) => constructed_type(&path.path, self_type, false),
+        Expr::MethodCall(call) if call.method == "to_string" => Some("String".to_string()),
+        Expr::Macro(invocation) if invocation.mac.path.is_ident("format") => {
+            Some("String".to_string())
+        }
+        Expr::Macro(invocation) if invocation.mac.path.is_ident("anyhow") => {
+            Some("anyhow::Error".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// The type part of a constructor path: everything before the last segment,
+/// with `Self` replaced by the impl type. A single capitalized segment is
+/// the type itself when `single_segment_is_type`, as for a tuple struct
+/// call; a bare unit variant or `Into::into` yields nothing.
+fn constructed_type(
+    path: &syn::Path,
+    self_type: Option<&str>,
+    single_segment_is_type: bool,
+) -> Option<String> {
+    let names = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    let type_names = match names.as_slice() {
+        [name] if single_segment_is_type && name.starts_with(char::is_uppercase) => {
+            names.as_slice()
+        }
+        [name] if name == "Self" => names.as_slice(),
+        [_] | [] => return None,
+        [.., _] => &names[..names.len() - 1],
+    };
+    if matches!(type_names, [name] if name == "Into" || name == "From") {
+        return None;
+    }
+    let text = type_names
+        .iter()
+        .map(|name| match (name.as_str(), self_type) {
+            ("Self", Some(self_type)) => self_type,
+            (name, _) => name,
+        })
+        .filter(|name| !matches!(*name, "crate" | "self" | "super"))
+        .collect::<Vec<_>>()
+        .join("::");
+    Some(text)
+}
+
+/// Whether the body `visit` walks keeps, stringifies, or ignores the value
+/// bound by `parameter`.
+fn detail_kept(parameter: &Pat, visit: impl FnOnce(&mut DetailVisitor)) -> ErrorDetailDto {
+    let name = match parameter {
+        Pat::Ident(ident) => ident.ident.to_string(),
+        Pat::Type(typed) => return detail_kept(&typed.pat, visit),
+        _ => return ErrorDetailDto::Discarded,
+    };
+    let mut visitor = DetailVisitor {
+        name,
+        used: false,
+        stringified: false,
+    };
+    visit(&mut visitor);
+    match (visitor.used, visitor.stringified) {
+        (_, true) => ErrorDetailDto::Stringified,
+        (true, false) => ErrorDetailDto::Preserved,
+        (false, false) => ErrorDetailDto::Discarded,
+    }
+}
+
+struct DetailVisitor {
+    name: String,
+    used: bool,
+    stringified: bool,
+}
+
+impl<'ast> Visit<'ast> for DetailVisitor {
+    fn visit_expr_path(&mut self, node: &'ast syn::ExprPath) {
+        if node.path.is_ident(&self.name) {
+            self.used = true;
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        if node.method == "to_string" && is_named(&node.receiver, &self.name) {
+            self.stringified = true;
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if mentions(node.tokens.clone(), &self.name) {
+            self.used = true;
+            let formats = node.path.segments.last().is_some_and(|segment| {
+                FORMATTING_MACROS.contains(&segment.ident.to_string().as_str())
+            });
+            if formats {
+                self.stringified = true;
+            }
+        }
+    }
+}
+
+fn is_named(expr: &Expr, name: &str) -> bool {
+    match expr {
+        Expr::Path(path) => path.path.is_ident(name),
+        Expr::Reference(reference) => is_named(&reference.expr, name),
+        Expr::Paren(paren) => is_named(&paren.expr, name),
+        _ => false,
+    }
+}
+
+/// Whether macro input names `name` directly or captures it inline, as in
+/// `format!("{error}")` or `format!("{error:?}")`.
+fn mentions(tokens: TokenStream, name: &str) -> bool {
+    let capture = format!("{{{name}");
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == name,
+        TokenTree::Literal(literal) => {
+            let text = literal.to_string();
+            text.match_indices(&capture).any(|(index, _)| {
+                text[index + capture.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|next| next == '}' || next == ':')
+            })
+        }
+        TokenTree::Group(group) => mentions(group.stream(), name),
+        TokenTree::Punct(_) => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_attribute_conversions, from_impl_conversion, function_error_flow};
+    use crate::graph::error_flow::{ErrorConversionKindDto, ErrorDetailDto};
+
+    #[test]
+    fn records_error_type_try_sites_and_map_err_detail() {
+        let function: syn::ItemFn = syn::parse_str(
+            r#"async fn get_config(state: &State) -> Result<Config, crate::commands::CommandErrorDto> {
+                let path = resolve()?;
+                let config = load(&path)
+                    .await
+                    .map_err(|error| CommandErrorDto::internal(error.to_string()))?;
+                let extra = read_extra().map_err(|error| Self::Io(error))?;
+                let _ = parse(&path).map_err(|error| format!("parse failed: {error}"));
+                let _ = spawn().await.map_err(|_| CommandErrorDto::validation("busy"));
+                let _ = run().map_err(Into::into);
+                let _ = || helper()?;
+                Ok(config)
+            }"#,
+        )
+        .expect("parse function");
+
+        let facts = function_error_flow(
+            "src/commands.rs",
+            &function.sig,
+            &function.block,
+            Some("ConfigError"),
+        )
+        .expect("error facts");
+
+        assert_eq!(
+            facts.error_type.as_deref(),
+            Some("commands::CommandErrorDto")
+        );
+        let try_sites = facts
+            .try_sites
+            .iter()
+            .map(|site| (site.range.start_line, site.callee.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(try_sites, vec![(2, Some("resolve")), (5, None), (6, None)]);
+        let conversions = facts
+            .conversions
+            .iter()
+            .map(|conversion| {
+                (
+                    conversion.callee.as_deref(),
+                    conversion.target_type.as_deref(),
+                    conversion.detail,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            conversions,
+            vec![
+                (
+                    Some("load"),
+                    Some("CommandErrorDto"),
+                    ErrorDetailDto::Stringified
+                ),
+                (
+                    Some("read_extra"),
+                    Some("ConfigError"),
+                    ErrorDetailDto::Preserved
+                ),
+                (Some("parse"), Some("String"), ErrorDetailDto::Stringified),
+                (
+                    Some("spawn"),
+                    Some("CommandErrorDto"),
+                    ErrorDetailDto::Discarded
+                ),
+                (Some("run"), None, ErrorDetailDto::Preserved),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_from_impls_and_thiserror_from_fields() {
+        let file = syn::parse_file(
+            r#"
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid config")]
+    Invalid(String),
+}
+impl From<serde_json::Error> for ConfigError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Invalid(error.to_string())
+    }
+}
+"#,
+        )
+        .expect("parse file");
+        let syn::Item::Enum(error_enum) = &file.items[0] else {
+            panic!("expected enum");
+        };
+        let syn::Item::Impl(from_impl) = &file.items[1] else {
+            panic!("expected impl");
+        };
+
+        let fields = from_attribute_conversions("src/error.rs", error_enum);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].kind, ErrorConversionKindDto::FromAttribute);
+        assert_eq!(fields[0].source_type.as_deref(), Some("std::io::Error"));
+        assert_eq!(fields[0].target_type.as_deref(), Some("ConfigError"));
+        assert_eq!(fields[0].range.start_line, 5);
+
+        let conversion = from_impl_conversion("src/error.rs", from_impl).expect("From impl");
+        assert_eq!(conversion.source_type.as_deref(), Some("serde_json::Error"));
+        assert_eq!(conversion.target_type.as_deref(), Some("ConfigError"));
+        assert_eq!(conversion.detail, ErrorDetailDto::Stringified);
+    }
+}
@@ -0,0 +1,360 @@
+//! Effective public API paths of library crates, from `pub` module nesting
+//! and `pub use` re-exports.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::graph::{GraphEdgeDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind, NodeVisibilityDto};
+
+const MAX_EXPORT_DEPTH: usize = 8;
+
+/// Library roots, `pub use` items, and inherent impl targets collected
+/// while extracting items.
+#[derive(Debug, Default)]
+pub(super) struct ExportFacts {
+    /// Crate name used in paths, by library root module ID.
+    pub(super) lib_roots: BTreeMap<String, String>,
+    pub(super) reexports: Vec<ReExportFact>,
+    /// Final path segment of the self type, by inherent impl ID.
+    pub(super) impl_self_types: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ReExportFact {
+    pub(super) module_id: String,
+    /// Use path segments; glob re-exports end in `*`.
+    pub(super) segments: Vec<String>,
+    /// Exported name, or `None` for a glob.
+    pub(super) name: Option<String>,
+}
+
+/// Sets `public_paths` on every item reachable from a library root through
+/// `pub` items and modules or `pub use` re-exports. Public methods follow
+/// their inherent impl's self type, and `#[macro_export]` macros sit at the
+/// crate root.
+pub(super) fn assign_public_paths(
+    nodes: &mut BTreeMap<String, GraphNodeDto>,
+    edges: &BTreeMap<String, GraphEdgeDto>,
+    facts: &ExportFacts,
+) {
+    let paths = {
+        let tree = ModuleTree::new(nodes, edges, facts);
+        let mut paths = BTreeMap::<String, BTreeSet<String>>::new();
+        let mut visited = BTreeSet::new();
+        for (root_id, crate_name) in &facts.lib_roots {
+            if !nodes.contains_key(root_id) {
+                continue;
+            }
+            paths
+                .entry(root_id.clone())
+                .or_default()
+                .insert(crate_name.clone());
+            tree.visit(root_id, crate_name, 0, &mut paths, &mut visited);
+        }
+        tree.add_method_paths(facts, &mut paths);
+        tree.add_macro_paths(facts, &mut paths);
+        paths
+    };
+
+    for (node_id, node_paths) in paths {
+        if let Some(node) = nodes.get_mut(&node_id) {
+            node.public_paths = node_paths.into_iter().collect();
+        }
+    }
+}
+
+struct ModuleTree<'a> {
+    nodes: &'a BTreeMap<String, GraphNodeDto>,
+    children: BTreeMap<&'a str, Vec<&'a str>>,
+    owners: BTreeMap<&'a str, &'a str>,
+    reexports: BTreeMap<&'a str, Vec<&'a ReExportFact>>,
+    lib_roots_by_name: BTreeMap<&'a str, &'a str>,
+}
+
+impl<'a> ModuleTree<'a> {
+    fn new(
+        nodes: &'a BTreeMap<String, GraphNodeDto>,
+        edges: &'a BTreeMap<String, GraphEdgeDto>,
+        facts: &'a ExportFacts,
+    ) -> Self {
+        let mut children = BTreeMap::<&str, Vec<&str>>::new();
+        let mut owners = BTreeMap::new();
+        for edge in edges.values() {
+            if !matches!(
+                edge.kind,
+                GraphEdgeKind::Contains | GraphEdgeKind::Defines | GraphEdgeKind::DefinesMethod
+            ) {
+                continue;
+            }
+            let owner_kind = nodes.get(&edge.source_id).map(|node| &node.kind);
+            if !matches!(
+                owner_kind,
+                Some(GraphNodeKind::Module | GraphNodeKind::Impl)
+            ) || !nodes.contains_key(&edge.target_id)
+            {
+                continue;
+            }
+            children
+                .entry(edge.source_id.as_str())
+                .or_default()
+                .push(edge.target_id.as_str());
+            owners
+                .entry(edge.target_id.as_str())
+                .or_insert(edge.source_id.as_str());
+        }
+
+        let mut reexports = BTreeMap::<&str, Vec<&ReExportFact>>::new();
+        for reexport in &facts.reexports {
+            reexports
+                .entry(reexport.module_id.as_str())
+                .or_default()
+                .push(reexport);
+        }
+
+        Self {
+            nodes,
+            children,
+            owners,
+            reexports,
+            lib_roots_by_name: facts
+                .lib_roots
+                .iter()
+                .map(|(root_id, name)| (name.as_str(), root_id.as_str()))
+                .collect(),
+        }
+    }
+
+    fn node(&self, node_id: &str) -> Option<&'a GraphNodeDto> {
+        self.nodes.get(node_id)
+    }
+
+    fn children(&self, node_id: &str) -> impl Iterator<Item = &'a GraphNodeDto> + '_ {
+        self.children
+            .get(node_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|child_id| self.node(child_id))
+    }
+
+    fn parent_module(&self, module_id: &str) -> Option<&'a str> {
+        self.owners
+            .get(module_id)
+            .copied()
+            .filter(|owner_id| self.is_module(owner_id))
+    }
+
+    fn root_module(&self, module_id: &'a str) -> &'a str {
+        let mut current = module_id;
+        while let Some(parent) = self.parent_module(current) {
+            current = parent;
+        }
+        current
+    }
+
+    fn is_module(&self, node_id: &str) -> bool {
+        self.node(node_id)
+            .is_some_and(|node| node.kind == GraphNodeKind::Module)
+    }
+
+    fn visit(
+        &self,
+        module_id: &str,
+        prefix: &str,
+        depth: usize,
+        paths: &mut BTreeMap<String, BTreeSet<String>>,
+        visited: &mut BTreeSet<(String, String)>,
+    ) {
+        if depth > MAX_EXPORT_DEPTH || !visited.insert((module_id.to_string(), prefix.to_string()))
+        {
+            return;
+        }
+
+        for child in self.children(module_id) {
+            if !is_path_item(&child.kind) || child.visibility != Some(NodeVisibilityDto::Public) {
+                continue;
+            }
+            let path = format!("{prefix}::{}", child.label);
+            paths
+                .entry(child.id.clone())
+                .or_default()
+                .insert(path.clone());
+            if child.kind == GraphNodeKind::Module {
+                self.visit(&child.id, &path, depth + 1, paths, visited);
+            }
+        }
+
+        for reexport in self.reexports.get(module_id).into_iter().flatten() {
+            for target_id in self.resolve(module_id, &reexport.segments, 0) {
+                match &reexport.name {
+                    Some(name) => {
+                        let path = format!("{prefix}::{name}");
+                        paths
+                            .entry(target_id.to_string())
+                            .or_default()
+                            .insert(path.clone());
+                        if self.is_module(target_id) {
+                            self.visit(target_id, &path, depth + 1, paths, visited);
+                        }
+                    }
+                    None if self.is_module(target_id) => {
+                        self.visit(target_id, prefix, depth + 1, paths, visited);
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    /// Resolves a use path from `module_id` to workspace nodes. Paths into
+    /// other crates resolve only through sibling library roots; a glob
+    /// resolves to the module it imports from.
+    fn resolve(&self, module_id: &str, segments: &[String], depth: usize) -> Vec<&'a str> {
+        let Some(first) = segments.first() else {
+            return Vec::new();
+        };
+        let Some(module_id) = self.node(module_id).map(|node| node.id.as_str()) else {
+            return Vec::new();
+        };
+        if depth > MAX_EXPORT_DEPTH {
+            return Vec::new();
+        }
+
+        let (mut current, rest) = match first.as_str() {
+            "crate" => (self.root_module(module_id), &segments[1..]),
+            "self" => (module_id, &segments[1..]),
+            "super" => {
+                let mut current = module_id;
+                let mut rest = segments;
+                while rest.first().is_some_and(|segment| segment == "super") {
+                    let Some(parent) = self.parent_module(current) else {
+                        return Vec::new();
+                    };
+                    current = parent;
+                    rest = &rest[1..];
+                }
+                (current, rest)
+            }
+            name if self.module_named(module_id, name, depth).is_some() => (module_id, segments),
+            name => match self.lib_roots_by_name.get(name) {
+                Some(root_id) => (*root_id, &segments[1..]),
+                None => return Vec::new(),
+            },
+        };
+
+        let Some((last, modules)) = rest.split_last() else {
+            return vec![current];
+        };
+        for segment in modules {
+            match self.module_named(current, segment, depth) {
+                Some(module) => current = module,
+                None => return Vec::new(),
+            }
+        }
+        if last == "*" {
+            return vec![current];
+        }
+
+        let items = self
+            .children(current)
+            .filter(|child| is_path_item(&child.kind) && &child.label == last)
+            .map(|child| child.id.as_str())
+            .collect::<Vec<_>>();
+        if !items.is_empty() {
+            return items;
+        }
+        self.reexports
+            .get(current)
+            .into_iter()
+            .flatten()
+            .filter(|reexport| reexport.name.as_ref() == Some(last))
+            .flat_map(|reexport| self.resolve(current, &reexport.segments, depth + 1))
+            .collect()
+    }
+
+    fn module_named(&self, module_id: &str, name: &str, depth: usize) -> Option<&'a str> {
+        let child = self
+            .children(module_id)
+            .find(|child| child.kind == GraphNodeKind::Module && child.label == name)
+            .map(|child| child.id.as_str());
+        child.or_else(|| {
+            self.reexports
+                .get(module_id)?
+                .iter()
+                .filter(|reexport| reexport.name.as_deref() == Some(name))
+                .flat_map(|reexport| self.resolve(module_id, &reexport.segments, depth + 1))
+                .find(|target_id| self.is_module(target_id))
+        })
+    }
+
+    fn add_method_paths(
+        &self,
+        facts: &ExportFacts,
+        paths: &mut BTreeMap<String, BTreeSet<String>>,
+    ) {
+        for (impl_id, self_type) in &facts.impl_self_types {
+            let Some(module_id) = self.owners.get(impl_id.as_str()) else {
+                continue;
+            };
+            let type_paths = self
+                .children(module_id)
+                .filter(|child| {
+                    matches!(
+                        child.kind,
+                        GraphNodeKind::Struct | GraphNodeKind::Enum | GraphNodeKind::Union
+                    ) && &child.label == self_type
+                })
+                .filter_map(|child| paths.get(&child.id))
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            for method in self.children(impl_id) {
+                if method.kind != GraphNodeKind::Method
+                    || method.visibility != Some(NodeVisibilityDto::Public)
+                {
+                    continue;
+                }
+                for type_path in &type_paths {
+                    paths
+                        .entry(method.id.clone())
+                        .or_default()
+                        .insert(format!("{type_path}::{}", method.label));
+                }
+            }
+        }
+    }
+
+    fn add_macro_paths(&self, facts: &ExportFacts, paths: &mut BTreeMap<String, BTreeSet<String>>) {
+        for node in self.nodes.values() {
+            if node.kind != GraphNodeKind::Macro
+                || node.visibility != Some(NodeVisibilityDto::Public)
+            {
+                continue;
+            }
+            let Some(module_id) = self.owners.get(node.id.as_str()) else {
+                continue;
+            };
+            if let Some(crate_name) = facts.lib_roots.get(self.root_module(module_id)) {
+                paths
+                    .entry(node.id.clone())
+                    .or_default()
+                    .insert(format!("{crate_name}::{}", node.label));
+            }
+        }
+    }
+}
+
+/// Kinds addressed by a path segment. Macros follow `#[macro_export]`
+/// instead, and methods follow their impl.
+fn is_path_item(kind: &GraphNodeKind) -> bool {
+    matches!(
+        kind,
+        GraphNodeKind::Module
+            | GraphNodeKind::Struct
+            | GraphNodeKind::Enum
+            | GraphNodeKind::Union
+            | GraphNodeKind::Trait
+            | GraphNodeKind::TypeAlias
+            | GraphNodeKind::Function
+            | GraphNodeKind::Const
+            | GraphNodeKind::Static
+    )
+}
@@ -1,19 +1,49 @@
+use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use cargo_metadata::MetadataCommand;
+use cargo_metadata::{DependencyKind, Metadata, MetadataCommand};
+use proc_macro2::{Spacing, TokenTree};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::visit::{self, Visit};
-use syn::{Attribute, Expr, File, ImplItem, Item, ItemImpl, ItemMod, ItemUse, Type, UseTree};
+use syn::{
+    Attribute, Block, Expr, ImplItem, Item, ItemImpl, ItemMacro, ItemMod, ItemUse, Lit, Meta, Stmt,
+    Token, Type, UseTree, Visibility,
+};
 use walkdir::WalkDir;
 
+use super::cfg::{cfg_predicates, resolve_build_configuration, CfgEnvironment, CfgPredicate};
+use super::complexity::function_complexity;
+use super::concurrency::{
+    body_concurrency, primitive_class, PrimitiveClass, SyncOperation, SyncTarget,
+};
+use super::error_flow::{from_attribute_conversions, from_impl_conversion, function_error_flow};
+use super::exports::{assign_public_paths, ExportFacts, ReExportFact};
+use super::rust_relations::collect_use_tree_entries;
+use super::signature::{
+    const_signature, enum_signature, field_signature, function_signature, macro_signature,
+    module_signature, static_signature, struct_signature, trait_signature, type_alias_signature,
+    union_signature,
+};
+use super::state_machine::body_state_assignments;
+use super::unsafety::{declaration_site, function_sites, UnsafeSource};
+use super::wire_contract::{enum_wire_contract, struct_wire_contract};
+use crate::graph::error_flow::ErrorFlowFactsDto;
+use crate::graph::metrics::{FunctionComplexityDto, NodeMetricsDto};
+use crate::graph::state_machine::StateAssignmentDto;
+use crate::graph::unsafe_code::{UnsafeSiteDto, UnsafeSiteKindDto};
+use crate::graph::wire_contract::WireContractDto;
 use crate::graph::{
-    stable_edge_id, stable_node_id, AnalyzerDiagnosticDto, EdgeConfidenceDto, EdgeProvenanceDto,
-    GraphEdgeDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
-    GRAPH_SCHEMA_VERSION,
+    stable_edge_id, stable_node_id, ActiveBuildConfigurationDto, AnalyzerDiagnosticDto,
+    BuildConfigurationDto, CrateDependencyDto, DependencyKindDto, EdgeConfidenceDto,
+    EdgeProvenanceDto, GraphEdgeDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto,
+    NodeVisibilityDto, SourceRangeDto, GRAPH_SCHEMA_VERSION,
 };
 use crate::source::ValidatedRepoPath;
 
@@ -23,16 +53,36 @@ const SNAPSHOT_SIZE_WARNING_THRESHOLD: usize = 10_000;
 pub struct RustGraphExtractor;
 
 impl RustGraphExtractor {
+    /// Extracts the graph against a default build of every workspace crate.
     pub fn extract(
         &self,
         source_root: &ValidatedRepoPath,
+    ) -> Result<GraphSnapshotDto, RustGraphExtractionError> {
+        self.extract_with_configuration(source_root, &BuildConfigurationDto::default())
+    }
+
+    /// Extracts every item and annotates nodes and edges with their effective
+    /// `cfg` predicate. The ones the configuration compiles out are kept
+    /// unless [`BuildConfigurationDto::prune_inactive`] is set.
+    pub fn extract_with_configuration(
+        &self,
+        source_root: &ValidatedRepoPath,
+        configuration: &BuildConfigurationDto,
     ) -> Result<GraphSnapshotDto, RustGraphExtractionError> {
         let metadata = MetadataCommand::new()
             .manifest_path(source_root.as_path().join("Cargo.toml"))
             .exec()
             .map_err(|error| RustGraphExtractionError::CargoMetadata(error.to_string()))?;
 
-        let mut graph = GraphAccumulator::new(source_root.display_path());
+        let declared_features_by_crate = metadata
+            .workspace_packages()
+            .into_iter()
+            .map(|package| (package.name.to_string(), package.features.clone()))
+            .collect();
+        let mut graph = GraphAccumulator::new(
+            source_root.display_path(),
+            resolve_build_configuration(configuration, &declared_features_by_crate),
+        );
         let workspace_id = graph.add_node(
             GraphNodeKind::Workspace,
             "workspace".to_string(),
@@ -40,9 +90,10 @@ impl RustGraphExtractor {
             None,
         );
 
-        let mut package_roots = BTreeSet::new();
+        let mut package_roots = BTreeMap::new();
         for package in metadata.workspace_packages() {
             let package_name = package.name.to_string();
+            graph.enter_package(&package_name);
             let crate_id = graph.add_node(
                 GraphNodeKind::Crate,
                 package_name.clone(),
@@ -62,144 +113,295 @@ impl RustGraphExtractor {
                 .parent()
                 .map(PathBuf::from)
                 .unwrap_or_else(|| source_root.as_path().to_path_buf());
-            package_roots.insert(package_root.clone());
+            package_roots
+                .entry(package_root.clone())
+                .or_insert_with(|| package_name.clone());
 
             for target in &package.targets {
                 let source_path = PathBuf::from(target.src_path.as_std_path());
                 if source_path
                     .extension()
                     .and_then(|extension| extension.to_str())
-                    == Some("rs")
+                    != Some("rs")
+                {
+                    continue;
+                }
+                let module = ModuleFile::crate_root(
+                    &package_name,
+                    &package_root,
+                    &target.name,
+                    target.is_lib(),
+                    source_path,
+                );
+                if let Some(module_id) =
+                    extract_module_file(source_root.as_path(), &crate_id, &module, &mut graph)
                 {
-                    self.extract_source_file(
-                        source_root.as_path(),
-                        &package_name,
+                    if target.is_lib() {
+                        graph
+                            .exports
+                            .lib_roots
+                            .insert(module_id.clone(), target.name.replace('-', "_"));
+                    }
+                    graph.add_edge(
+                        GraphEdgeKind::Contains,
                         &crate_id,
-                        &source_path,
-                        &mut graph,
+                        &module_id,
+                        EdgeProvenanceDto::Normalized,
+                        EdgeConfidenceDto::Exact,
                     );
                 }
             }
         }
 
-        for package_root in package_roots {
-            for entry in WalkDir::new(package_root.join("src")) {
-                let entry = match entry {
-                    Ok(entry) => entry,
-                    Err(error) => {
-                        graph.add_diagnostic(
-                            "walkdir_error",
-                            format!("failed to read source entry: {error}"),
-                            None,
-                        );
-                        continue;
-                    }
-                };
-
-                if !entry.file_type().is_file() {
-                    continue;
-                }
-
-                let path = entry.path();
-                if path.extension().and_then(|extension| extension.to_str()) != Some("rs") {
-                    continue;
-                }
-
-                let already_seen = graph
-                    .file_paths
-                    .contains(&relative_path(source_root.as_path(), path));
-                if !already_seen {
-                    self.extract_source_file(
-                        source_root.as_path(),
-                        "workspace",
-                        &workspace_id,
-                        path,
-                        &mut graph,
-                    );
-                }
-            }
+        for package_root in package_roots.keys() {
+            report_orphan_source_files(source_root.as_path(), package_root, &mut graph);
         }
 
+        add_dependency_edges(&metadata, &mut graph);
         graph.link_imports();
         graph.link_calls();
+        graph.link_macro_invocations();
+        graph.link_sync_accesses();
+        graph.link_state_assignments();
+        graph.drop_inactive();
+        graph.assign_public_paths();
         graph.warn_if_large();
         Ok(graph.finish())
     }
+}
 
-    fn extract_source_file(
-        &self,
-        source_root: &Path,
-        package_name: &str,
-        owner_id: &str,
-        source_path: &Path,
-        graph: &mut GraphAccumulator,
-    ) {
-        let relative_path = relative_path(source_root, source_path);
-        graph.file_paths.insert(relative_path.clone());
+/// One file of a crate's module tree, as reached from a target root through
+/// `mod name;` declarations.
+struct ModuleFile<'a> {
+    package_name: &'a str,
+    /// Target name for module trees outside the package's shared namespace,
+    /// such as `src/bin/*.rs`, examples, tests, and benches, so their modules
+    /// don't collide with the library's.
+    namespace: Option<String>,
+    path: PathBuf,
+    /// Crate-relative module path such as `graph::rules`; empty for a root.
+    module_path: String,
+    label: String,
+    /// Crate roots, `mod.rs` files, and `#[path]` files keep their child
+    /// modules beside them; other files keep them in a directory named after
+    /// the module.
+    owns_directory: bool,
+}
 
-        let source = match fs::read_to_string(source_path) {
-            Ok(source) => source,
-            Err(error) => {
-                graph.add_diagnostic(
-                    "read_source_failed",
-                    format!("failed to read source file: {error}"),
-                    Some(relative_path),
-                );
-                return;
-            }
+impl<'a> ModuleFile<'a> {
+    /// The library and a `src/main.rs` binary share the package namespace and
+    /// keep the `lib`/`main` root labels; other targets are named after the
+    /// target.
+    fn crate_root(
+        package_name: &'a str,
+        package_root: &Path,
+        target_name: &str,
+        is_lib: bool,
+        path: PathBuf,
+    ) -> Self {
+        let is_main = path == package_root.join("src/main.rs");
+        let (namespace, label) = if is_lib {
+            (None, "lib".to_string())
+        } else if is_main {
+            (None, "main".to_string())
+        } else {
+            (Some(target_name.to_string()), target_name.to_string())
         };
+        Self {
+            package_name,
+            namespace,
+            path,
+            module_path: String::new(),
+            label,
+            owns_directory: true,
+        }
+    }
+
+    fn child(&self, module_path: String, path: PathBuf, owns_directory: bool) -> Self {
+        Self {
+            package_name: self.package_name,
+            namespace: self.namespace.clone(),
+            path,
+            label: module_path.clone(),
+            module_path,
+            owns_directory,
+        }
+    }
+
+    fn id_parts(&self) -> Vec<&str> {
+        let mut parts = vec![self.package_name];
+        parts.extend(self.namespace.as_deref());
+        parts.push(self.label.as_str());
+        parts
+    }
+
+    fn module_id(&self) -> String {
+        stable_node_id(GraphNodeKind::Module, &self.id_parts())
+    }
+
+    /// Directory that holds this file's out-of-line child modules.
+    fn child_directory(&self) -> PathBuf {
+        let parent = self
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        if self.owns_directory {
+            parent
+        } else {
+            let stem = self
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            parent.join(stem)
+        }
+    }
+}
+
+/// Extracts one module file and, through its `mod name;` declarations, every
+/// file below it. Returns the file's module node ID, or `None` when the file
+/// was already reached from another declaration or cannot be read or parsed.
+fn extract_module_file(
+    source_root: &Path,
+    crate_id: &str,
+    module: &ModuleFile<'_>,
+    graph: &mut GraphAccumulator,
+) -> Option<String> {
+    let relative_path = relative_path(source_root, &module.path);
+    if !graph.file_paths.insert(relative_path.clone()) {
+        return graph.module_ids_by_path.get(&relative_path).cloned();
+    }
+    let module_id = module.module_id();
+
+    let source = match fs::read_to_string(&module.path) {
+        Ok(source) => source,
+        Err(error) => {
+            graph.add_diagnostic(
+                "read_source_failed",
+                format!("failed to read source file: {error}"),
+                Some(relative_path),
+            );
+            return None;
+        }
+    };
+
+    let parsed = match syn::parse_file(&source) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            graph.add_diagnostic(
+                "parse_source_failed",
+                format!("failed to parse Rust source: {error}"),
+                Some(relative_path),
+            );
+            return None;
+        }
+    };
+
+    // File-level `#![cfg]` scopes the file, its module, and every item.
+    let file_scope = graph.push_cfg(&parsed.attrs);
+    let file_id = graph.add_node(
+        GraphNodeKind::File,
+        relative_path.clone(),
+        &[relative_path.as_str()],
+        Some(source_range(&relative_path, 1)),
+    );
+    graph.add_edge(
+        GraphEdgeKind::Contains,
+        crate_id,
+        &file_id,
+        EdgeProvenanceDto::Normalized,
+        EdgeConfidenceDto::Exact,
+    );
+
+    graph.add_node(
+        GraphNodeKind::Module,
+        module.label.clone(),
+        &module.id_parts(),
+        Some(source_range(&relative_path, 1)),
+    );
+    graph.add_edge(
+        GraphEdgeKind::Defines,
+        &file_id,
+        &module_id,
+        EdgeProvenanceDto::Normalized,
+        EdgeConfidenceDto::Exact,
+    );
+    graph
+        .module_ids_by_path
+        .insert(relative_path.clone(), module_id.clone());
+
+    let child_directory = module.child_directory();
+    let context = ItemContext {
+        source_root,
+        crate_id,
+        module_file: module,
+        relative_path: &relative_path,
+        source: &source,
+        file_id: &file_id,
+        module_id: module_id.clone(),
+        inline_path: String::new(),
+        module_directory: child_directory,
+        path_directory: module
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    extract_items(&parsed.items, &context, graph);
+    extract_rust_analyzer_symbols(
+        module.package_name,
+        &relative_path,
+        &source,
+        &file_id,
+        graph,
+    );
+    graph.pop_cfg(file_scope);
+    Some(module_id)
+}
+
+/// Reports `.rs` files under a package's conventional source directories
+/// that no target's module tree reaches. They are not extracted because no
+/// build compiles them.
+fn report_orphan_source_files(
+    source_root: &Path,
+    package_root: &Path,
+    graph: &mut GraphAccumulator,
+) {
+    for directory in ["src", "tests", "examples", "benches"] {
+        for entry in WalkDir::new(package_root.join(directory)) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    if error.io_error().map(std::io::Error::kind)
+                        != Some(std::io::ErrorKind::NotFound)
+                    {
+                        graph.add_diagnostic(
+                            "walkdir_error",
+                            format!("failed to read source entry: {error}"),
+                            None,
+                        );
+                    }
+                    continue;
+                }
+            };
 
-        let parsed = match syn::parse_file(&source) {
-            Ok(parsed) => parsed,
-            Err(error) => {
+            let path = entry.path();
+            if !entry.file_type().is_file()
+                || path.extension().and_then(|extension| extension.to_str()) != Some("rs")
+            {
+                continue;
+            }
+
+            let relative_path = relative_path(source_root, path);
+            if !graph.file_paths.contains(&relative_path) {
                 graph.add_diagnostic(
-                    "parse_source_failed",
-                    format!("failed to parse Rust source: {error}"),
+                    "orphan_source_file",
+                    "source file is not reachable from any target's module tree",
                     Some(relative_path),
                 );
-                return;
             }
-        };
-
-        let file_id = graph.add_node(
-            GraphNodeKind::File,
-            relative_path.clone(),
-            &[relative_path.as_str()],
-            Some(source_range(&relative_path, 1)),
-        );
-        graph.add_edge(
-            GraphEdgeKind::Contains,
-            owner_id,
-            &file_id,
-            EdgeProvenanceDto::Normalized,
-            EdgeConfidenceDto::Exact,
-        );
-
-        let module_label = module_label_from_path(&relative_path);
-        let module_id = graph.add_node(
-            GraphNodeKind::Module,
-            module_label.clone(),
-            &[package_name, module_label.as_str()],
-            Some(source_range(&relative_path, 1)),
-        );
-        graph.add_edge(
-            GraphEdgeKind::Defines,
-            &file_id,
-            &module_id,
-            EdgeProvenanceDto::Normalized,
-            EdgeConfidenceDto::Exact,
-        );
-
-        extract_items(
-            &parsed,
-            package_name,
-            &relative_path,
-            &source,
-            &file_id,
-            &module_id,
-            graph,
-        );
-        extract_rust_analyzer_symbols(package_name, &relative_path, &source, &file_id, graph);
+        }
     }
 }
 
@@ -216,36 +418,261 @@ struct GraphAccumulator {
     edges: BTreeMap<String, GraphEdgeDto>,
     diagnostics: Vec<AnalyzerDiagnosticDto>,
     file_paths: BTreeSet<String>,
+    /// Module ID of each extracted file under the target that reached it
+    /// first, returned when another target reaches the same file.
+    module_ids_by_path: BTreeMap<String, String>,
     functions_by_name: BTreeMap<String, String>,
+    macros_by_name: BTreeMap<String, String>,
     pending_imports: Vec<PendingImport>,
     pending_calls: Vec<PendingCall>,
+    pending_macro_invocations: Vec<PendingCall>,
+    /// Sync primitive struct fields by field name.
+    sync_fields: BTreeMap<String, Vec<SyncField>>,
+    pending_sync_accesses: Vec<PendingSyncAccess>,
+    /// Enum node IDs by name, for state assignment linking.
+    enums_by_name: BTreeMap<String, Vec<String>>,
+    /// `(field, type name)` of every named struct field.
+    field_types: BTreeSet<(String, String)>,
+    pending_state_assignments: Vec<PendingStateAssignment>,
+    configuration: Option<ActiveBuildConfigurationDto>,
+    package_features: BTreeSet<String>,
+    cfg_scope: Vec<CfgPredicate>,
+    /// Nodes and edges whose every occurrence is compiled out. They stay in
+    /// the snapshot unless the configuration prunes inactive items.
+    inactive_ids: BTreeSet<String>,
+    /// Scope of each syn symbol by (path, label), used to place rust-analyzer
+    /// duplicates in the same scope.
+    symbol_scopes: BTreeMap<(String, String), CfgScope>,
+    exports: ExportFacts,
+}
+
+/// Effective cfg predicate text and whether the active configuration
+/// compiles it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CfgScope {
+    cfg: Option<String>,
+    active: bool,
 }
 
 impl GraphAccumulator {
-    fn new(source_root: String) -> Self {
+    fn new(source_root: String, configuration: ActiveBuildConfigurationDto) -> Self {
         Self {
             source_root,
+            configuration: Some(configuration),
             ..Self::default()
         }
     }
 
+    /// Switches feature evaluation to the workspace crate being extracted.
+    fn enter_package(&mut self, package_name: &str) {
+        self.package_features = self
+            .configuration
+            .as_ref()
+            .and_then(|configuration| configuration.features_by_crate.get(package_name))
+            .map(|features| features.iter().cloned().collect())
+            .unwrap_or_default();
+    }
+
+    /// Pushes the `#[cfg]` predicates of an item or statement and returns how
+    /// many to pop when leaving it.
+    fn push_cfg(&mut self, attributes: &[Attribute]) -> usize {
+        let predicates = cfg_predicates(attributes);
+        let count = predicates.len();
+        self.cfg_scope.extend(predicates);
+        count
+    }
+
+    fn pop_cfg(&mut self, count: usize) {
+        self.cfg_scope
+            .truncate(self.cfg_scope.len().saturating_sub(count));
+    }
+
+    fn current_scope(&self) -> CfgScope {
+        let active = match &self.configuration {
+            Some(configuration) => {
+                let environment = CfgEnvironment {
+                    configuration,
+                    features: &self.package_features,
+                };
+                self.cfg_scope
+                    .iter()
+                    .all(|predicate| predicate.evaluate(&environment))
+            }
+            None => true,
+        };
+        CfgScope {
+            cfg: CfgPredicate::all(&self.cfg_scope).map(|predicate| predicate.to_string()),
+            active,
+        }
+    }
+
     fn add_node(
         &mut self,
         kind: GraphNodeKind,
         label: String,
         id_parts: &[&str],
         source_range: Option<SourceRangeDto>,
+    ) -> String {
+        let scope = self.current_scope();
+        self.add_node_in_scope(kind, label, id_parts, source_range, scope)
+    }
+
+    /// Adds a node, or revives a compiled-out node when the same item is
+    /// declared again under an active predicate, such as per-platform
+    /// `#[cfg(unix)]` and `#[cfg(windows)]` variants of one function.
+    fn add_node_in_scope(
+        &mut self,
+        kind: GraphNodeKind,
+        label: String,
+        id_parts: &[&str],
+        source_range: Option<SourceRangeDto>,
+        scope: CfgScope,
     ) -> String {
         let id = stable_node_id(kind.clone(), id_parts);
-        self.nodes.entry(id.clone()).or_insert(GraphNodeDto {
-            id: id.clone(),
-            kind,
-            label,
-            source_range,
-        });
+        if let Some(path) = source_range.as_ref().map(|range| range.path.clone()) {
+            let symbol_scope = self
+                .symbol_scopes
+                .entry((path, label.clone()))
+                .or_insert_with(|| scope.clone());
+            if !symbol_scope.active && scope.active {
+                *symbol_scope = scope.clone();
+            }
+        }
+
+        match self.nodes.entry(id.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(GraphNodeDto {
+                    id: id.clone(),
+                    kind,
+                    label,
+                    source_range,
+                    metrics: None,
+                    visibility: None,
+                    attributes: Vec::new(),
+                    derives: Vec::new(),
+                    signature: None,
+                    public_paths: Vec::new(),
+                    unsafe_sites: Vec::new(),
+                    error_flow: None,
+                    wire_contract: None,
+                    state_assignments: Vec::new(),
+                    cfg: scope.cfg,
+                });
+                if !scope.active {
+                    self.inactive_ids.insert(id.clone());
+                }
+            }
+            Entry::Occupied(mut entry) => {
+                if scope.active && self.inactive_ids.remove(&id) {
+                    entry.get_mut().cfg = scope.cfg;
+                    entry.get_mut().source_range = source_range;
+                }
+            }
+        }
         id
     }
 
+    /// Whether a node or edge is compiled out and will be pruned.
+    fn is_pruned(&self, id: &str) -> bool {
+        self.configuration
+            .as_ref()
+            .is_some_and(|configuration| configuration.prune_inactive)
+            && self.inactive_ids.contains(id)
+    }
+
+    /// Records a resolvable function name unless the definition is pruned,
+    /// so calls never resolve to code the configuration excludes.
+    fn register_function(&mut self, name: String, node_id: String) {
+        if !self.is_pruned(&node_id) {
+            self.functions_by_name.insert(name, node_id);
+        }
+    }
+
+    /// Records a lock, atomic, channel, or signal field for access linking
+    /// unless the field is pruned.
+    fn register_sync_field(&mut self, field_name: String, field: SyncField) {
+        if !self.is_pruned(&field.node_id) {
+            self.sync_fields.entry(field_name).or_default().push(field);
+        }
+    }
+
+    /// Records an enum for state assignment linking unless the definition is
+    /// pruned.
+    fn register_enum(&mut self, name: String, node_id: String) {
+        if !self.is_pruned(&node_id) {
+            self.enums_by_name.entry(name).or_default().push(node_id);
+        }
+    }
+
+    /// Records a `macro_rules!` name for invocation linking unless the
+    /// definition is pruned.
+    fn register_macro(&mut self, name: String, node_id: String) {
+        if !self.is_pruned(&node_id) {
+            self.macros_by_name.insert(name, node_id);
+        }
+    }
+
+    fn set_item_facts(
+        &mut self,
+        node_id: &str,
+        visibility: Option<&Visibility>,
+        attributes: &[Attribute],
+    ) {
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.visibility = visibility.map(visibility_dto);
+            node.attributes = attribute_paths(attributes);
+            node.derives = derive_paths(attributes);
+        }
+    }
+
+    fn set_signature(&mut self, node_id: &str, signature: Option<String>) {
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.signature = signature;
+        }
+    }
+
+    /// Records the entries of an active `pub use` for public path resolution.
+    fn record_reexports(&mut self, module_id: &str, tree: &UseTree) {
+        if !self.current_scope().active {
+            return;
+        }
+        let mut entries = Vec::new();
+        collect_use_tree_entries(tree, String::new(), &mut entries);
+        for (import_path, name) in entries {
+            self.exports.reexports.push(ReExportFact {
+                module_id: module_id.to_string(),
+                segments: import_path.split("::").map(str::to_string).collect(),
+                name,
+            });
+        }
+    }
+
+    fn set_unsafe_sites(&mut self, node_id: &str, sites: Vec<UnsafeSiteDto>) {
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.unsafe_sites = sites;
+        }
+    }
+
+    fn set_error_flow(&mut self, node_id: &str, error_flow: Option<ErrorFlowFactsDto>) {
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.error_flow = error_flow;
+        }
+    }
+
+    fn set_wire_contract(&mut self, node_id: &str, wire_contract: Option<WireContractDto>) {
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.wire_contract = wire_contract;
+        }
+    }
+
+    fn set_function_complexity(&mut self, node_id: &str, complexity: FunctionComplexityDto) {
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.metrics
+                .get_or_insert_with(NodeMetricsDto::default)
+                .complexity = Some(complexity);
+        }
+    }
+
     fn add_edge(
         &mut self,
         kind: GraphEdgeKind,
@@ -253,15 +680,80 @@ impl GraphAccumulator {
         target_id: &str,
         provenance: EdgeProvenanceDto,
         confidence: EdgeConfidenceDto,
+    ) {
+        self.add_edge_with_evidence(kind, source_id, target_id, provenance, confidence, None);
+    }
+
+    fn add_edge_with_evidence(
+        &mut self,
+        kind: GraphEdgeKind,
+        source_id: &str,
+        target_id: &str,
+        provenance: EdgeProvenanceDto,
+        confidence: EdgeConfidenceDto,
+        evidence: Option<SourceRangeDto>,
+    ) {
+        let scope = self.current_scope();
+        self.add_edge_in_scope(
+            kind, source_id, target_id, provenance, confidence, evidence, scope,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_edge_in_scope(
+        &mut self,
+        kind: GraphEdgeKind,
+        source_id: &str,
+        target_id: &str,
+        provenance: EdgeProvenanceDto,
+        confidence: EdgeConfidenceDto,
+        evidence: Option<SourceRangeDto>,
+        scope: CfgScope,
     ) {
         let id = stable_edge_id(kind.clone(), source_id, target_id);
+        match self.edges.entry(id.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(GraphEdgeDto {
+                    id: id.clone(),
+                    kind,
+                    source_id: source_id.to_string(),
+                    target_id: target_id.to_string(),
+                    provenance,
+                    confidence,
+                    evidence,
+                    dependency: None,
+                    cfg: scope.cfg,
+                });
+                if !scope.active {
+                    self.inactive_ids.insert(id);
+                }
+            }
+            Entry::Occupied(mut entry) => {
+                if scope.active && self.inactive_ids.remove(&id) {
+                    entry.get_mut().cfg = scope.cfg;
+                    entry.get_mut().evidence = evidence;
+                }
+            }
+        }
+    }
+
+    fn add_dependency_edge(
+        &mut self,
+        source_id: &str,
+        target_id: &str,
+        dependency: CrateDependencyDto,
+    ) {
+        let id = stable_edge_id(GraphEdgeKind::DependsOn, source_id, target_id);
         self.edges.entry(id.clone()).or_insert(GraphEdgeDto {
             id,
-            kind,
+            kind: GraphEdgeKind::DependsOn,
             source_id: source_id.to_string(),
             target_id: target_id.to_string(),
-            provenance,
-            confidence,
+            provenance: EdgeProvenanceDto::Normalized,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: Some(dependency),
+            cfg: None,
         });
     }
 
@@ -270,11 +762,22 @@ impl GraphAccumulator {
         code: impl Into<String>,
         message: impl Into<String>,
         source_path: Option<String>,
+    ) {
+        self.add_diagnostic_with_evidence(code, message, source_path, None);
+    }
+
+    fn add_diagnostic_with_evidence(
+        &mut self,
+        code: impl Into<String>,
+        message: impl Into<String>,
+        source_path: Option<String>,
+        evidence: Option<SourceRangeDto>,
     ) {
         self.diagnostics.push(AnalyzerDiagnosticDto {
             code: code.into(),
             message: message.into(),
             source_path,
+            evidence,
         });
     }
 
@@ -285,48 +788,202 @@ impl GraphAccumulator {
                 .get(&pending_call.target_name)
                 .cloned()
             {
-                self.add_edge(
+                self.add_edge_in_scope(
                     GraphEdgeKind::Calls,
                     &pending_call.source_id,
                     &target_id,
                     EdgeProvenanceDto::Syn,
                     EdgeConfidenceDto::Partial,
+                    Some(pending_call.evidence),
+                    pending_call.scope,
                 );
-            } else {
-                self.add_diagnostic(
+            } else if pending_call.scope.active {
+                self.add_diagnostic_with_evidence(
                     "unresolved_call",
                     format!("unresolved call target '{}'", pending_call.target_name),
                     Some(pending_call.source_path),
+                    Some(pending_call.evidence),
                 );
             }
         }
     }
 
-    fn link_imports(&mut self) {
-        for pending_import in std::mem::take(&mut self.pending_imports) {
-            let Some(target_path) = resolve_import_path(
-                &pending_import.source_path,
-                &pending_import.import,
-                &self.file_paths,
-            ) else {
-                continue;
-            };
-
-            if target_path == pending_import.source_path {
-                continue;
+    /// Links invocations to workspace `macro_rules!` definitions by name.
+    /// Standard library and external macros have no definition node and are
+    /// skipped without a diagnostic.
+    fn link_macro_invocations(&mut self) {
+        for invocation in std::mem::take(&mut self.pending_macro_invocations) {
+            if let Some(target_id) = self.macros_by_name.get(&invocation.target_name).cloned() {
+                self.add_edge_in_scope(
+                    GraphEdgeKind::InvokesMacro,
+                    &invocation.source_id,
+                    &target_id,
+                    EdgeProvenanceDto::Syn,
+                    EdgeConfidenceDto::Partial,
+                    Some(invocation.evidence),
+                    invocation.scope,
+                );
             }
-
+        }
+    }
+
+    /// Links `.lock()`, `.send()`, `.load()` and similar calls on a field to
+    /// the sync primitive field they target. A field of the method's own
+    /// `Self` type is exact; otherwise the field name must match exactly one
+    /// primitive of a compatible kind, and ambiguous sites are skipped.
+    fn link_sync_accesses(&mut self) {
+        for access in std::mem::take(&mut self.pending_sync_accesses) {
+            let candidates = self
+                .sync_fields
+                .get(&access.field)
+                .into_iter()
+                .flatten()
+                .filter(|field| access.operation.applies_to(field.class))
+                .collect::<Vec<_>>();
+            let own_field = candidates
+                .iter()
+                .find(|field| Some(&field.owner_type) == access.self_type.as_ref());
+            let (target_id, confidence) = match (own_field, candidates.as_slice()) {
+                (Some(field), _) => (field.node_id.clone(), EdgeConfidenceDto::Exact),
+                (None, [field]) => (field.node_id.clone(), EdgeConfidenceDto::Inferred),
+                _ => continue,
+            };
+            self.add_edge_in_scope(
+                sync_edge_kind(access.operation),
+                &access.source_id,
+                &target_id,
+                EdgeProvenanceDto::Syn,
+                confidence,
+                Some(access.evidence),
+                access.scope,
+            );
+        }
+    }
+
+    /// Records state assignments on the functions and methods performing
+    /// them. The enum name must match exactly one enum, a struct field of
+    /// that name must have the enum's type, and the enum must be assigned
+    /// outside a struct literal at least once: enums that are only ever
+    /// constructed, such as node kinds, are not state machines.
+    fn link_state_assignments(&mut self) {
+        let resolved = std::mem::take(&mut self.pending_state_assignments)
+            .into_iter()
+            .filter_map(|assignment| {
+                let [enum_id] = self.enums_by_name.get(&assignment.enum_name)?.as_slice() else {
+                    return None;
+                };
+                self.field_types
+                    .contains(&(assignment.field.clone(), assignment.enum_name.clone()))
+                    .then(|| (enum_id.clone(), assignment))
+            })
+            .collect::<Vec<_>>();
+        let reassigned = resolved
+            .iter()
+            .filter(|(_, assignment)| !assignment.construction)
+            .map(|(enum_id, _)| enum_id.clone())
+            .collect::<BTreeSet<_>>();
+        for (enum_id, assignment) in resolved {
+            if !reassigned.contains(&enum_id) {
+                continue;
+            }
+            if let Some(node) = self.nodes.get_mut(&assignment.node_id) {
+                node.state_assignments.push(StateAssignmentDto {
+                    enum_id,
+                    enum_name: assignment.enum_name,
+                    field: assignment.field,
+                    variant: assignment.variant,
+                    guard: assignment.guard,
+                    construction: assignment.construction,
+                    range: assignment.range,
+                });
+            }
+        }
+    }
+
+    fn link_imports(&mut self) {
+        for pending_import in std::mem::take(&mut self.pending_imports) {
+            let Some(target_path) = resolve_import_path(
+                &pending_import.source_path,
+                &pending_import.import,
+                &self.file_paths,
+            ) else {
+                continue;
+            };
+
+            if target_path == pending_import.source_path {
+                continue;
+            }
+
             let target_id = stable_node_id(GraphNodeKind::File, &[target_path.as_str()]);
-            self.add_edge(
+            self.add_edge_in_scope(
                 GraphEdgeKind::Imports,
                 &pending_import.source_id,
                 &target_id,
                 EdgeProvenanceDto::Syn,
                 EdgeConfidenceDto::Inferred,
+                Some(pending_import.evidence),
+                pending_import.scope,
+            );
+        }
+    }
+
+    /// Reports how much the configuration compiles out and, when pruning,
+    /// removes those nodes and edges plus edges left without an endpoint.
+    fn drop_inactive(&mut self) {
+        let inactive_ids = std::mem::take(&mut self.inactive_ids);
+        let prune_inactive = self
+            .configuration
+            .as_ref()
+            .is_some_and(|configuration| configuration.prune_inactive);
+        if !prune_inactive {
+            let inactive_nodes = self
+                .nodes
+                .keys()
+                .filter(|id| inactive_ids.contains(*id))
+                .count();
+            let inactive_edges = self
+                .edges
+                .keys()
+                .filter(|id| inactive_ids.contains(*id))
+                .count();
+            if inactive_nodes + inactive_edges > 0 {
+                self.add_diagnostic(
+                    "cfg_inactive_items",
+                    format!(
+                        "{inactive_nodes} nodes and {inactive_edges} edges are compiled out by the active build configuration and kept with their cfg predicate"
+                    ),
+                    None,
+                );
+            }
+            return;
+        }
+
+        let node_count = self.nodes.len();
+        let edge_count = self.edges.len();
+        self.nodes.retain(|id, _| !inactive_ids.contains(id));
+        self.edges.retain(|id, edge| {
+            !inactive_ids.contains(id)
+                && !inactive_ids.contains(&edge.source_id)
+                && !inactive_ids.contains(&edge.target_id)
+        });
+
+        let dropped_nodes = node_count - self.nodes.len();
+        let dropped_edges = edge_count - self.edges.len();
+        if dropped_nodes + dropped_edges > 0 {
+            self.add_diagnostic(
+                "cfg_inactive_items",
+                format!(
+                    "{dropped_nodes} nodes and {dropped_edges} edges are compiled out by the active build configuration"
+                ),
+                None,
             );
         }
     }
 
+    fn assign_public_paths(&mut self) {
+        assign_public_paths(&mut self.nodes, &self.edges, &self.exports);
+    }
+
     fn warn_if_large(&mut self) {
         let snapshot_size = self.nodes.len() + self.edges.len();
         if snapshot_size > SNAPSHOT_SIZE_WARNING_THRESHOLD {
@@ -346,15 +1003,173 @@ impl GraphAccumulator {
             nodes: self.nodes.into_values().collect(),
             edges: self.edges.into_values().collect(),
             diagnostics: self.diagnostics,
+            configuration: self.configuration,
+        }
+    }
+}
+
+/// Adds `DependsOn` edges for the direct dependencies of every workspace
+/// crate in the Cargo resolve graph. Workspace dependencies point at their
+/// crate node; everything else becomes one external crate node per package
+/// version.
+fn add_dependency_edges(metadata: &Metadata, graph: &mut GraphAccumulator) {
+    let Some(resolve) = &metadata.resolve else {
+        graph.add_diagnostic(
+            "cargo_resolve_unavailable",
+            "Cargo metadata did not include a dependency resolve graph",
+            None,
+        );
+        return;
+    };
+    let packages_by_id = metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect::<BTreeMap<_, _>>();
+    let resolve_nodes_by_id = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, node))
+        .collect::<BTreeMap<_, _>>();
+    let workspace_ids = metadata.workspace_members.iter().collect::<BTreeSet<_>>();
+
+    for node in &resolve.nodes {
+        let Some(package) = workspace_ids
+            .contains(&node.id)
+            .then(|| packages_by_id.get(&node.id))
+            .flatten()
+        else {
+            continue;
+        };
+        let source_id = stable_node_id(GraphNodeKind::Crate, &[package.name.as_str()]);
+
+        for node_dep in &node.deps {
+            let Some(dependency_package) = packages_by_id.get(&node_dep.pkg) else {
+                continue;
+            };
+            let dependency_name = dependency_package.name.to_string();
+            let resolved_version = dependency_package.version.to_string();
+            let target_id = if workspace_ids.contains(&node_dep.pkg) {
+                stable_node_id(GraphNodeKind::Crate, &[dependency_name.as_str()])
+            } else {
+                graph.add_node(
+                    GraphNodeKind::ExternalCrate,
+                    dependency_name.clone(),
+                    &[dependency_name.as_str(), resolved_version.as_str()],
+                    None,
+                )
+            };
+
+            let declarations = package
+                .dependencies
+                .iter()
+                .filter(|declared| {
+                    declared.name == dependency_name
+                        && declared.req.matches(&dependency_package.version)
+                        && node_dep
+                            .dep_kinds
+                            .iter()
+                            .any(|dep_kind| dep_kind.kind == declared.kind)
+                })
+                .collect::<Vec<_>>();
+            let kinds = node_dep
+                .dep_kinds
+                .iter()
+                .map(|dep_kind| dependency_kind_dto(&dep_kind.kind))
+                .collect::<BTreeSet<_>>();
+            let targets = node_dep
+                .dep_kinds
+                .iter()
+                .filter_map(|dep_kind| dep_kind.target.as_ref().map(ToString::to_string))
+                .collect::<BTreeSet<_>>();
+            let requested_features = declarations
+                .iter()
+                .flat_map(|declared| declared.features.iter().cloned())
+                .collect::<BTreeSet<_>>();
+            let enabled_features = resolve_nodes_by_id
+                .get(&node_dep.pkg)
+                .map(|dependency_node| {
+                    dependency_node
+                        .features
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<BTreeSet<_>>()
+                })
+                .unwrap_or_default();
+
+            graph.add_dependency_edge(
+                &source_id,
+                &target_id,
+                CrateDependencyDto {
+                    kinds: kinds.into_iter().collect(),
+                    version_req: declarations
+                        .first()
+                        .map(|declared| declared.req.to_string())
+                        .unwrap_or_else(|| "*".to_string()),
+                    resolved_version,
+                    optional: declarations.iter().any(|declared| declared.optional),
+                    default_features: declarations.is_empty()
+                        || declarations
+                            .iter()
+                            .any(|declared| declared.uses_default_features),
+                    requested_features: requested_features.into_iter().collect(),
+                    enabled_features: enabled_features.into_iter().collect(),
+                    rename: declarations
+                        .iter()
+                        .find_map(|declared| declared.rename.clone()),
+                    targets: targets.into_iter().collect(),
+                },
+            );
         }
     }
 }
 
+fn dependency_kind_dto(kind: &DependencyKind) -> DependencyKindDto {
+    match kind {
+        DependencyKind::Development => DependencyKindDto::Dev,
+        DependencyKind::Build => DependencyKindDto::Build,
+        _ => DependencyKindDto::Normal,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PendingCall {
     source_id: String,
     target_name: String,
     source_path: String,
+    evidence: SourceRangeDto,
+    scope: CfgScope,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SyncField {
+    owner_type: String,
+    node_id: String,
+    class: PrimitiveClass,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingSyncAccess {
+    /// Function, method, or task performing the access.
+    source_id: String,
+    /// Impl self type of the enclosing method.
+    self_type: Option<String>,
+    field: String,
+    operation: SyncOperation,
+    evidence: SourceRangeDto,
+    scope: CfgScope,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingStateAssignment {
+    /// Function or method performing the assignment.
+    node_id: String,
+    enum_name: String,
+    field: String,
+    variant: String,
+    guard: Vec<String>,
+    construction: bool,
+    range: SourceRangeDto,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -362,112 +1177,326 @@ struct PendingImport {
     source_id: String,
     source_path: String,
     import: String,
+    evidence: SourceRangeDto,
+    scope: CfgScope,
 }
 
-fn extract_items(
-    parsed: &File,
-    package_name: &str,
-    relative_path: &str,
-    source: &str,
-    file_id: &str,
-    module_id: &str,
-    graph: &mut GraphAccumulator,
-) {
-    for item in &parsed.items {
+/// Location of the items being extracted: the source file, the module node
+/// that owns them, and the inline `mod` path inside the file. The inline path
+/// is part of every node ID so same-named items in sibling inline modules,
+/// such as two `mod tests` helpers, stay distinct.
+struct ItemContext<'a> {
+    source_root: &'a Path,
+    crate_id: &'a str,
+    module_file: &'a ModuleFile<'a>,
+    relative_path: &'a str,
+    source: &'a str,
+    file_id: &'a str,
+    module_id: String,
+    inline_path: String,
+    /// Where `mod name;` looks for `name.rs` or `name/mod.rs`.
+    module_directory: PathBuf,
+    /// Base directory for `#[path]` on module declarations: the file's own
+    /// directory at the top level, the module directory inside inline blocks.
+    path_directory: PathBuf,
+}
+
+impl<'a> ItemContext<'a> {
+    fn id_parts<'b>(&'b self, names: &[&'b str]) -> Vec<&'b str> {
+        let mut parts = vec![self.module_file.package_name, self.relative_path];
+        if !self.inline_path.is_empty() {
+            parts.push(self.inline_path.as_str());
+        }
+        parts.extend_from_slice(names);
+        parts
+    }
+
+    fn inline_module(&self, module_id: String, label: &str) -> Self {
+        let module_directory = self.module_directory.join(label);
+        Self {
+            source_root: self.source_root,
+            crate_id: self.crate_id,
+            module_file: self.module_file,
+            relative_path: self.relative_path,
+            source: self.source,
+            file_id: self.file_id,
+            module_id,
+            inline_path: join_path(self.inline_path.clone(), label),
+            path_directory: module_directory.clone(),
+            module_directory,
+        }
+    }
+
+    fn unsafe_source(&self) -> UnsafeSource<'a> {
+        UnsafeSource {
+            relative_path: self.relative_path,
+            source: self.source,
+        }
+    }
+
+    /// Crate-relative path of the module the items belong to.
+    fn module_path(&self) -> String {
+        match (
+            self.module_file.module_path.is_empty(),
+            self.inline_path.is_empty(),
+        ) {
+            (_, true) => self.module_file.module_path.clone(),
+            (true, false) => self.inline_path.clone(),
+            (false, false) => format!("{}::{}", self.module_file.module_path, self.inline_path),
+        }
+    }
+}
+
+fn extract_items(items: &[Item], context: &ItemContext<'_>, graph: &mut GraphAccumulator) {
+    for item in items {
+        let scope = graph.push_cfg(item_attributes(item));
         match item {
             Item::Struct(item) => {
-                add_definition(
+                let node_id = add_definition(
                     graph,
+                    context,
                     GraphNodeKind::Struct,
                     GraphEdgeKind::Defines,
                     item.ident.to_string(),
-                    &[package_name, relative_path, item.ident.to_string().as_str()],
-                    relative_path,
-                    source,
-                    module_id,
+                    item_range(context.relative_path, item.struct_token.span, item.span()),
+                    &context.module_id,
+                );
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(struct_signature(item)));
+                graph.set_wire_contract(
+                    &node_id,
+                    struct_wire_contract(item, &derive_paths(&item.attrs)),
                 );
+                extract_sync_fields(context, graph, item, &node_id);
+                for field in &item.fields {
+                    if let (Some(ident), Some(type_name)) =
+                        (&field.ident, last_type_segment(&field.ty))
+                    {
+                        graph.field_types.insert((ident.to_string(), type_name));
+                    }
+                }
             }
             Item::Enum(item) => {
-                add_definition(
+                let node_id = add_definition(
                     graph,
+                    context,
                     GraphNodeKind::Enum,
                     GraphEdgeKind::Defines,
                     item.ident.to_string(),
-                    &[package_name, relative_path, item.ident.to_string().as_str()],
-                    relative_path,
-                    source,
-                    module_id,
+                    item_range(context.relative_path, item.enum_token.span, item.span()),
+                    &context.module_id,
                 );
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(enum_signature(item)));
+                graph.register_enum(item.ident.to_string(), node_id.clone());
+                graph.set_wire_contract(
+                    &node_id,
+                    enum_wire_contract(item, &derive_paths(&item.attrs)),
+                );
+                let conversions = from_attribute_conversions(context.relative_path, item);
+                if !conversions.is_empty() {
+                    graph.set_error_flow(
+                        &node_id,
+                        Some(ErrorFlowFactsDto {
+                            conversions,
+                            ..ErrorFlowFactsDto::default()
+                        }),
+                    );
+                }
             }
             Item::Trait(item) => {
-                add_definition(
+                let node_id = add_definition(
                     graph,
+                    context,
                     GraphNodeKind::Trait,
                     GraphEdgeKind::Defines,
                     item.ident.to_string(),
-                    &[package_name, relative_path, item.ident.to_string().as_str()],
-                    relative_path,
-                    source,
-                    module_id,
+                    item_range(context.relative_path, item.trait_token.span, item.span()),
+                    &context.module_id,
                 );
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(trait_signature(item)));
+                if let Some(unsafe_token) = &item.unsafety {
+                    graph.set_unsafe_sites(
+                        &node_id,
+                        vec![declaration_site(
+                            context.unsafe_source(),
+                            UnsafeSiteKindDto::Trait,
+                            unsafe_token,
+                            item.span(),
+                        )],
+                    );
+                }
             }
             Item::Fn(item) => {
                 let function_name = item.sig.ident.to_string();
                 let function_id = add_definition(
                     graph,
+                    context,
                     GraphNodeKind::Function,
                     GraphEdgeKind::Defines,
                     function_name.clone(),
-                    &[package_name, relative_path, function_name.as_str()],
-                    relative_path,
-                    source,
-                    module_id,
+                    item_range(context.relative_path, item.sig.span(), item.block.span()),
+                    &context.module_id,
                 );
-                graph
-                    .functions_by_name
-                    .insert(function_name, function_id.clone());
+                graph.register_function(function_name.clone(), function_id.clone());
                 add_tauri_command_if_needed(
                     graph,
+                    context,
                     &item.attrs,
-                    package_name,
-                    relative_path,
-                    source,
-                    file_id,
+                    context.file_id,
+                    &function_id,
+                    &function_name,
+                );
+                graph.set_item_facts(&function_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&function_id, Some(function_signature(&item.vis, &item.sig)));
+                graph.set_function_complexity(
+                    &function_id,
+                    function_complexity(&item.sig, &item.block, context.source),
+                );
+                graph.set_unsafe_sites(
+                    &function_id,
+                    function_sites(context.unsafe_source(), &item.sig, &item.block),
+                );
+                graph.set_error_flow(
                     &function_id,
-                    &item.sig.ident.to_string(),
+                    function_error_flow(context.relative_path, &item.sig, &item.block, None),
+                );
+                collect_calls(&function_id, context.relative_path, &item.block, graph);
+                collect_state_assignments(context, graph, &function_id, None, &item.block);
+                collect_concurrency(
+                    context,
+                    graph,
+                    FunctionOwner {
+                        node_id: &function_id,
+                        label: &function_name,
+                        id_names: &[function_name.as_str()],
+                        self_type: None,
+                    },
+                    &item.block,
+                );
+            }
+            Item::Union(item) => {
+                let node_id = add_definition(
+                    graph,
+                    context,
+                    GraphNodeKind::Union,
+                    GraphEdgeKind::Defines,
+                    item.ident.to_string(),
+                    item_range(context.relative_path, item.union_token.span, item.span()),
+                    &context.module_id,
+                );
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(union_signature(item)));
+            }
+            Item::Type(item) => {
+                let node_id = add_definition(
+                    graph,
+                    context,
+                    GraphNodeKind::TypeAlias,
+                    GraphEdgeKind::Defines,
+                    item.ident.to_string(),
+                    item_range(context.relative_path, item.type_token.span, item.span()),
+                    &context.module_id,
+                );
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(type_alias_signature(item)));
+            }
+            Item::Const(item) => {
+                let node_id = add_definition(
+                    graph,
+                    context,
+                    GraphNodeKind::Const,
+                    GraphEdgeKind::Defines,
+                    item.ident.to_string(),
+                    item_range(context.relative_path, item.const_token.span, item.span()),
+                    &context.module_id,
                 );
-                collect_calls(&function_id, relative_path, &item.block, graph);
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(const_signature(item)));
             }
-            Item::Impl(item) => {
-                extract_impl(package_name, relative_path, source, module_id, graph, item)
+            Item::Static(item) => {
+                let node_id = add_definition(
+                    graph,
+                    context,
+                    GraphNodeKind::Static,
+                    GraphEdgeKind::Defines,
+                    item.ident.to_string(),
+                    item_range(context.relative_path, item.static_token.span, item.span()),
+                    &context.module_id,
+                );
+                graph.set_item_facts(&node_id, Some(&item.vis), &item.attrs);
+                graph.set_signature(&node_id, Some(static_signature(item)));
             }
-            Item::Use(item) => extract_use(relative_path, file_id, graph, item),
-            Item::Mod(item) => {
-                extract_mod(package_name, relative_path, source, module_id, graph, item)
+            Item::Impl(item) => extract_impl(context, graph, item),
+            Item::Use(item) => {
+                extract_use(context.relative_path, context.file_id, graph, item);
+                if matches!(item.vis, Visibility::Public(_)) {
+                    graph.record_reexports(&context.module_id, &item.tree);
+                }
             }
+            Item::Mod(item) => extract_mod(context, graph, item),
+            Item::Macro(item) => extract_macro(context, graph, item),
             _ => {}
         }
+        graph.pop_cfg(scope);
+    }
+}
+
+fn item_attributes(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Const(item) => &item.attrs,
+        Item::Enum(item) => &item.attrs,
+        Item::ExternCrate(item) => &item.attrs,
+        Item::Fn(item) => &item.attrs,
+        Item::ForeignMod(item) => &item.attrs,
+        Item::Impl(item) => &item.attrs,
+        Item::Macro(item) => &item.attrs,
+        Item::Mod(item) => &item.attrs,
+        Item::Static(item) => &item.attrs,
+        Item::Struct(item) => &item.attrs,
+        Item::Trait(item) => &item.attrs,
+        Item::TraitAlias(item) => &item.attrs,
+        Item::Type(item) => &item.attrs,
+        Item::Union(item) => &item.attrs,
+        Item::Use(item) => &item.attrs,
+        _ => &[],
     }
 }
 
-#[allow(clippy::too_many_arguments)]
 fn add_definition(
     graph: &mut GraphAccumulator,
+    context: &ItemContext<'_>,
     kind: GraphNodeKind,
     edge_kind: GraphEdgeKind,
     label: String,
-    id_parts: &[&str],
-    relative_path: &str,
-    source: &str,
+    range: SourceRangeDto,
     owner_id: &str,
 ) -> String {
-    let line = find_line(source, &label);
-    let node_id = graph.add_node(
+    add_definition_with_id(
+        graph,
+        context,
         kind,
-        label,
-        id_parts,
-        Some(source_range(relative_path, line)),
-    );
+        edge_kind,
+        label.clone(),
+        &[&label],
+        range,
+        owner_id,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_definition_with_id(
+    graph: &mut GraphAccumulator,
+    context: &ItemContext<'_>,
+    kind: GraphNodeKind,
+    edge_kind: GraphEdgeKind,
+    label: String,
+    id_names: &[&str],
+    range: SourceRangeDto,
+    owner_id: &str,
+) -> String {
+    let node_id = graph.add_node(kind, label, &context.id_parts(id_names), Some(range));
     graph.add_edge(
         edge_kind,
         owner_id,
@@ -478,14 +1507,7 @@ fn add_definition(
     node_id
 }
 
-fn extract_impl(
-    package_name: &str,
-    relative_path: &str,
-    source: &str,
-    module_id: &str,
-    graph: &mut GraphAccumulator,
-    item: &ItemImpl,
-) {
+fn extract_impl(context: &ItemContext<'_>, graph: &mut GraphAccumulator, item: &ItemImpl) {
     let self_ty = type_label(&item.self_ty);
     let label = match &item.trait_ {
         Some((_, trait_path, _)) => format!("impl {} for {self_ty}", path_label(trait_path)),
@@ -493,20 +1515,47 @@ fn extract_impl(
     };
     let impl_id = add_definition(
         graph,
+        context,
         GraphNodeKind::Impl,
         GraphEdgeKind::Defines,
-        label.clone(),
-        &[package_name, relative_path, label.as_str()],
-        relative_path,
-        source,
-        module_id,
+        label,
+        item_range(context.relative_path, item.impl_token.span, item.span()),
+        &context.module_id,
     );
+    graph.set_item_facts(&impl_id, None, &item.attrs);
+    if let Some(unsafe_token) = &item.unsafety {
+        graph.set_unsafe_sites(
+            &impl_id,
+            vec![declaration_site(
+                context.unsafe_source(),
+                UnsafeSiteKindDto::Impl,
+                unsafe_token,
+                item.span(),
+            )],
+        );
+    }
+    if let Some(conversion) = from_impl_conversion(context.relative_path, item) {
+        graph.set_error_flow(
+            &impl_id,
+            Some(ErrorFlowFactsDto {
+                conversions: vec![conversion],
+                ..ErrorFlowFactsDto::default()
+            }),
+        );
+    }
+    let self_type = self_ty.rsplit("::").next().unwrap_or(&self_ty);
+    if item.trait_.is_none() {
+        graph
+            .exports
+            .impl_self_types
+            .insert(impl_id.clone(), self_type.to_string());
+    }
 
     if item.trait_.is_some() {
         graph.add_edge(
             GraphEdgeKind::Implements,
             &impl_id,
-            module_id,
+            &context.module_id,
             EdgeProvenanceDto::Syn,
             EdgeConfidenceDto::Partial,
         );
@@ -514,41 +1563,262 @@ fn extract_impl(
 
     for impl_item in &item.items {
         if let ImplItem::Fn(method) = impl_item {
+            let scope = graph.push_cfg(&method.attrs);
             let method_name = method.sig.ident.to_string();
-            let method_id = add_definition(
+            let method_id = add_definition_with_id(
                 graph,
+                context,
                 GraphNodeKind::Method,
                 GraphEdgeKind::DefinesMethod,
                 method_name.clone(),
-                &[
-                    package_name,
-                    relative_path,
-                    self_ty.as_str(),
-                    method_name.as_str(),
-                ],
-                relative_path,
-                source,
+                &[self_ty.as_str(), method_name.as_str()],
+                item_range(
+                    context.relative_path,
+                    method.sig.span(),
+                    method.block.span(),
+                ),
                 &impl_id,
             );
-            graph
-                .functions_by_name
-                .insert(method_name.clone(), method_id.clone());
+            graph.register_function(method_name.clone(), method_id.clone());
             add_tauri_command_if_needed(
                 graph,
+                context,
                 &method.attrs,
-                package_name,
-                relative_path,
-                source,
-                module_id,
+                &context.module_id,
                 &method_id,
                 method_name.as_str(),
             );
-            collect_calls(&method_id, relative_path, &method.block, graph);
+            graph.set_item_facts(&method_id, Some(&method.vis), &method.attrs);
+            graph.set_signature(
+                &method_id,
+                Some(function_signature(&method.vis, &method.sig)),
+            );
+            graph.set_function_complexity(
+                &method_id,
+                function_complexity(&method.sig, &method.block, context.source),
+            );
+            graph.set_unsafe_sites(
+                &method_id,
+                function_sites(context.unsafe_source(), &method.sig, &method.block),
+            );
+            graph.set_error_flow(
+                &method_id,
+                function_error_flow(
+                    context.relative_path,
+                    &method.sig,
+                    &method.block,
+                    Some(self_type),
+                ),
+            );
+            collect_calls(&method_id, context.relative_path, &method.block, graph);
+            collect_state_assignments(context, graph, &method_id, Some(self_type), &method.block);
+            collect_concurrency(
+                context,
+                graph,
+                FunctionOwner {
+                    node_id: &method_id,
+                    label: &format!("{self_type}::{method_name}"),
+                    id_names: &[self_ty.as_str(), method_name.as_str()],
+                    self_type: Some(self_type),
+                },
+                &method.block,
+            );
+            graph.pop_cfg(scope);
+        }
+    }
+}
+
+/// Adds a sync primitive node under the struct for each named field whose
+/// type is a lock, atomic, channel endpoint, or signal.
+fn extract_sync_fields(
+    context: &ItemContext<'_>,
+    graph: &mut GraphAccumulator,
+    item: &syn::ItemStruct,
+    struct_id: &str,
+) {
+    let struct_name = item.ident.to_string();
+    for field in &item.fields {
+        let (Some(ident), Some(class)) = (&field.ident, primitive_class(&field.ty)) else {
+            continue;
+        };
+        let scope = graph.push_cfg(&field.attrs);
+        let field_name = ident.to_string();
+        let node_id = graph.add_node(
+            GraphNodeKind::SyncPrimitive,
+            format!("{struct_name}.{field_name}"),
+            &context.id_parts(&[struct_name.as_str(), field_name.as_str()]),
+            Some(span_range(context.relative_path, field.span())),
+        );
+        graph.set_item_facts(&node_id, Some(&field.vis), &field.attrs);
+        graph.set_signature(&node_id, Some(field_signature(field)));
+        graph.add_edge(
+            GraphEdgeKind::Defines,
+            struct_id,
+            &node_id,
+            EdgeProvenanceDto::Syn,
+            EdgeConfidenceDto::Exact,
+        );
+        graph.register_sync_field(
+            field_name,
+            SyncField {
+                owner_type: struct_name.clone(),
+                node_id,
+                class,
+            },
+        );
+        graph.pop_cfg(scope);
+    }
+}
+
+/// Function or method whose body is scanned for concurrency facts.
+struct FunctionOwner<'a> {
+    node_id: &'a str,
+    /// `name` for functions, `Type::name` for methods.
+    label: &'a str,
+    id_names: &'a [&'a str],
+    self_type: Option<&'a str>,
+}
+
+/// Adds task nodes for spawn calls and sync primitive nodes for channels
+/// created in the body, with `Spawns`, `Sends`, and `Receives` edges from the
+/// function or innermost task. Field accesses are linked after extraction.
+fn collect_concurrency(
+    context: &ItemContext<'_>,
+    graph: &mut GraphAccumulator,
+    owner: FunctionOwner<'_>,
+    block: &Block,
+) {
+    let facts = body_concurrency(block);
+    let mut task_ids: Vec<String> = Vec::with_capacity(facts.tasks.len());
+    for (index, task) in facts.tasks.iter().enumerate() {
+        let ordinal = format!("task{index}");
+        let mut id_names = owner.id_names.to_vec();
+        id_names.push(&ordinal);
+        let task_id = graph.add_node(
+            GraphNodeKind::Task,
+            format!("{} in {}", task.spawner, owner.label),
+            &context.id_parts(&id_names),
+            Some(span_range(context.relative_path, task.span)),
+        );
+        let spawner_id = task
+            .parent
+            .map_or(owner.node_id, |parent| task_ids[parent].as_str());
+        graph.add_edge_with_evidence(
+            GraphEdgeKind::Spawns,
+            spawner_id,
+            &task_id,
+            EdgeProvenanceDto::Syn,
+            EdgeConfidenceDto::Exact,
+            Some(span_range(context.relative_path, task.span)),
+        );
+        task_ids.push(task_id);
+    }
+
+    let mut channel_ids = Vec::with_capacity(facts.channels.len());
+    for (index, channel) in facts.channels.iter().enumerate() {
+        let ordinal = format!("channel{index}");
+        let mut id_names = owner.id_names.to_vec();
+        id_names.push(&ordinal);
+        let channel_id = graph.add_node(
+            GraphNodeKind::SyncPrimitive,
+            format!("{} in {}", channel.constructor, owner.label),
+            &context.id_parts(&id_names),
+            Some(span_range(context.relative_path, channel.span)),
+        );
+        graph.set_signature(
+            &channel_id,
+            Some(format!(
+                "({}) = {}(..)",
+                channel.endpoints.join(", "),
+                channel.constructor
+            )),
+        );
+        graph.add_edge(
+            GraphEdgeKind::Defines,
+            owner.node_id,
+            &channel_id,
+            EdgeProvenanceDto::Syn,
+            EdgeConfidenceDto::Exact,
+        );
+        channel_ids.push(channel_id);
+    }
+
+    for access in facts.accesses {
+        let source_id = access
+            .task
+            .map_or(owner.node_id, |task| task_ids[task].as_str());
+        let evidence = span_range(context.relative_path, access.span);
+        match access.target {
+            SyncTarget::Channel(channel) => graph.add_edge_with_evidence(
+                sync_edge_kind(access.operation),
+                source_id,
+                &channel_ids[channel],
+                EdgeProvenanceDto::Syn,
+                EdgeConfidenceDto::Exact,
+                Some(evidence),
+            ),
+            SyncTarget::Field(field) => {
+                let scope = graph.current_scope();
+                graph.pending_sync_accesses.push(PendingSyncAccess {
+                    source_id: source_id.to_string(),
+                    self_type: owner.self_type.map(str::to_string),
+                    field,
+                    operation: access.operation,
+                    evidence,
+                    scope,
+                });
+            }
         }
     }
 }
 
+/// Queues the body's state enum assignments for linking once every enum
+/// and struct field is known.
+fn collect_state_assignments(
+    context: &ItemContext<'_>,
+    graph: &mut GraphAccumulator,
+    node_id: &str,
+    self_type: Option<&str>,
+    block: &Block,
+) {
+    for fact in body_state_assignments(block, self_type) {
+        graph
+            .pending_state_assignments
+            .push(PendingStateAssignment {
+                node_id: node_id.to_string(),
+                enum_name: fact.enum_name,
+                field: fact.field,
+                variant: fact.variant,
+                guard: fact.guard,
+                construction: fact.construction,
+                range: span_range(context.relative_path, fact.span),
+            });
+    }
+}
+
+/// Last path segment of a field type, such as `Phase` in `status::Phase`.
+fn last_type_segment(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn sync_edge_kind(operation: SyncOperation) -> GraphEdgeKind {
+    match operation {
+        SyncOperation::Send => GraphEdgeKind::Sends,
+        SyncOperation::Receive => GraphEdgeKind::Receives,
+        SyncOperation::Lock => GraphEdgeKind::Locks,
+        SyncOperation::Atomic => GraphEdgeKind::References,
+    }
+}
+
 fn extract_use(relative_path: &str, file_id: &str, graph: &mut GraphAccumulator, item: &ItemUse) {
+    let evidence = span_range(relative_path, item.span());
     for import in use_tree_labels(&item.tree) {
         let import_id = graph.add_node(
             GraphNodeKind::Module,
@@ -556,74 +1826,199 @@ fn extract_use(relative_path: &str, file_id: &str, graph: &mut GraphAccumulator,
             &["import", import.as_str()],
             Some(source_range(relative_path, 1)),
         );
-        graph.add_edge(
+        graph.add_edge_with_evidence(
             GraphEdgeKind::Imports,
             file_id,
             &import_id,
             EdgeProvenanceDto::Syn,
             EdgeConfidenceDto::Partial,
+            Some(evidence.clone()),
         );
         graph.pending_imports.push(PendingImport {
             source_id: file_id.to_string(),
             source_path: relative_path.to_string(),
             import,
+            evidence: evidence.clone(),
+            scope: graph.current_scope(),
         });
     }
 }
 
-fn extract_mod(
-    package_name: &str,
-    relative_path: &str,
-    source: &str,
-    module_id: &str,
-    graph: &mut GraphAccumulator,
-    item: &ItemMod,
-) {
+/// Adds the module node and extracts inline `mod { .. }` contents with the
+/// same item handling as file-level items, at any depth. Out-of-line
+/// `mod name;` declarations only get the module node here.
+fn extract_mod(context: &ItemContext<'_>, graph: &mut GraphAccumulator, item: &ItemMod) {
     let label = item.ident.to_string();
+    if item.content.is_none() {
+        extract_out_of_line_mod(context, graph, item, &label);
+        return;
+    }
+
     let child_id = add_definition(
         graph,
+        context,
         GraphNodeKind::Module,
         GraphEdgeKind::Contains,
         label.clone(),
-        &[package_name, relative_path, label.as_str()],
-        relative_path,
-        source,
-        module_id,
+        item_range(context.relative_path, item.mod_token.span, item.span()),
+        &context.module_id,
     );
+    graph.set_item_facts(&child_id, Some(&item.vis), &item.attrs);
+    graph.set_signature(&child_id, Some(module_signature(item)));
 
     if let Some((_, items)) = &item.content {
-        for item in items {
-            if let Item::Fn(function) = item {
-                let function_name = function.sig.ident.to_string();
-                let function_id = add_definition(
-                    graph,
-                    GraphNodeKind::Function,
-                    GraphEdgeKind::Defines,
-                    function_name.clone(),
-                    &[
-                        package_name,
-                        relative_path,
-                        label.as_str(),
-                        function_name.as_str(),
-                    ],
-                    relative_path,
-                    source,
-                    &child_id,
-                );
-                graph.functions_by_name.insert(function_name, function_id);
+        extract_items(items, &context.inline_module(child_id, &label), graph);
+    }
+}
+
+/// Follows `mod name;` to `name.rs`, `name/mod.rs`, or the `#[path]` file and
+/// extracts it as a child module. A missing file still gets a module node at
+/// the declaration so the tree stays connected, plus a diagnostic.
+fn extract_out_of_line_mod(
+    context: &ItemContext<'_>,
+    graph: &mut GraphAccumulator,
+    item: &ItemMod,
+    label: &str,
+) {
+    let module_path = join_path(context.module_path(), label);
+    let candidates = match path_attribute(&item.attrs) {
+        Some(path) => vec![(
+            lexically_normalized(&context.path_directory.join(path)),
+            true,
+        )],
+        None => vec![
+            (context.module_directory.join(format!("{label}.rs")), false),
+            (context.module_directory.join(label).join("mod.rs"), true),
+        ],
+    };
+    let resolved = candidates.iter().find(|(path, _)| path.is_file());
+
+    let child_id = match resolved {
+        Some((path, owns_directory)) => {
+            let child = context
+                .module_file
+                .child(module_path, path.clone(), *owns_directory);
+            match extract_module_file(context.source_root, context.crate_id, &child, graph) {
+                Some(child_id) => child_id,
+                None => return,
             }
         }
+        None => {
+            let expected = candidates
+                .iter()
+                .map(|(path, _)| relative_path(context.source_root, path))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            graph.add_diagnostic_with_evidence(
+                "module_file_missing",
+                format!("module '{module_path}' has no source file at {expected}"),
+                Some(context.relative_path.to_string()),
+                Some(span_range(context.relative_path, item.ident.span())),
+            );
+            let missing = context
+                .module_file
+                .child(module_path, PathBuf::new(), false);
+            graph.add_node(
+                GraphNodeKind::Module,
+                missing.label.clone(),
+                &missing.id_parts(),
+                Some(span_range(context.relative_path, item.ident.span())),
+            )
+        }
+    };
+
+    graph.add_edge_with_evidence(
+        GraphEdgeKind::Contains,
+        &context.module_id,
+        &child_id,
+        EdgeProvenanceDto::Syn,
+        EdgeConfidenceDto::Exact,
+        Some(span_range(context.relative_path, item.span())),
+    );
+    graph.set_item_facts(&child_id, Some(&item.vis), &item.attrs);
+    graph.set_signature(&child_id, Some(module_signature(item)));
+}
+
+fn path_attribute(attributes: &[Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attribute| {
+        let Meta::NameValue(name_value) = &attribute.meta else {
+            return None;
+        };
+        if !name_value.path.is_ident("path") {
+            return None;
+        }
+        let Expr::Lit(expr) = &name_value.value else {
+            return None;
+        };
+        let Lit::Str(path) = &expr.lit else {
+            return None;
+        };
+        Some(path.value())
+    })
+}
+
+/// Adds a node for a `macro_rules!` definition, or records an item-position
+/// invocation such as `thread_local! { .. }` against the enclosing module.
+fn extract_macro(context: &ItemContext<'_>, graph: &mut GraphAccumulator, item: &ItemMacro) {
+    let Some(ident) = item
+        .ident
+        .as_ref()
+        .filter(|_| item.mac.path.is_ident("macro_rules"))
+    else {
+        push_macro_invocation(graph, &context.module_id, context.relative_path, &item.mac);
+        return;
+    };
+
+    let macro_name = ident.to_string();
+    let macro_id = add_definition(
+        graph,
+        context,
+        GraphNodeKind::Macro,
+        GraphEdgeKind::Defines,
+        macro_name.clone(),
+        item_range(context.relative_path, item.mac.path.span(), item.span()),
+        &context.module_id,
+    );
+    graph.set_item_facts(&macro_id, None, &item.attrs);
+    graph.set_signature(&macro_id, macro_signature(item));
+    if let Some(node) = graph.nodes.get_mut(&macro_id) {
+        let exported = item
+            .attrs
+            .iter()
+            .any(|attribute| attribute.path().is_ident("macro_export"));
+        node.visibility = Some(if exported {
+            NodeVisibilityDto::Public
+        } else {
+            NodeVisibilityDto::Private
+        });
     }
+    graph.register_macro(macro_name, macro_id);
+}
+
+fn push_macro_invocation(
+    graph: &mut GraphAccumulator,
+    source_id: &str,
+    relative_path: &str,
+    mac: &syn::Macro,
+) {
+    let Some(segment) = mac.path.segments.last() else {
+        return;
+    };
+    let scope = graph.current_scope();
+    graph.pending_macro_invocations.push(PendingCall {
+        source_id: source_id.to_string(),
+        target_name: segment.ident.to_string(),
+        source_path: relative_path.to_string(),
+        evidence: span_range(relative_path, mac.path.span()),
+        scope,
+    });
 }
 
-#[allow(clippy::too_many_arguments)]
 fn add_tauri_command_if_needed(
     graph: &mut GraphAccumulator,
+    context: &ItemContext<'_>,
     attributes: &[Attribute],
-    package_name: &str,
-    relative_path: &str,
-    source: &str,
-    file_id: &str,
+    owner_id: &str,
     function_id: &str,
     function_name: &str,
 ) {
@@ -640,15 +2035,15 @@ fn add_tauri_command_if_needed(
     let command_id = graph.add_node(
         GraphNodeKind::TauriCommand,
         function_name.to_string(),
-        &[package_name, relative_path, "tauri_command", function_name],
-        Some(source_range(
-            relative_path,
-            find_line(source, function_name),
-        )),
+        &context.id_parts(&["tauri_command", function_name]),
+        graph
+            .nodes
+            .get(function_id)
+            .and_then(|node| node.source_range.clone()),
     );
     graph.add_edge(
         GraphEdgeKind::ExposesCommand,
-        file_id,
+        owner_id,
         &command_id,
         EdgeProvenanceDto::Syn,
         EdgeConfidenceDto::Exact,
@@ -730,21 +2125,33 @@ fn extract_rust_analyzer_symbols(
             continue;
         };
 
-        let node_id = graph.add_node(
+        // rust-analyzer symbols carry no attributes, so they take the scope
+        // of the syn item with the same label in this file.
+        let scope = graph
+            .symbol_scopes
+            .get(&(relative_path.to_string(), label.clone()))
+            .cloned()
+            .unwrap_or_else(|| graph.current_scope());
+        let node_id = graph.add_node_in_scope(
             node_kind.clone(),
             label.clone(),
             &[package_name, relative_path, "rust-analyzer", label.as_str()],
-            Some(source_range(relative_path, find_line(source, &label))),
+            navigation_range(line, source, relative_path),
+            scope.clone(),
         );
-        graph.add_edge(
+        graph.add_edge_in_scope(
             GraphEdgeKind::Defines,
             file_id,
             &node_id,
             EdgeProvenanceDto::RustAnalyzer,
             EdgeConfidenceDto::Exact,
+            None,
+            scope,
         );
 
-        if matches!(node_kind, GraphNodeKind::Function | GraphNodeKind::Method) {
+        if matches!(node_kind, GraphNodeKind::Function | GraphNodeKind::Method)
+            && !graph.is_pruned(&node_id)
+        {
             graph.functions_by_name.entry(label).or_insert(node_id);
         }
     }
@@ -758,6 +2165,35 @@ fn extract_quoted_field(line: &str, field: &str) -> Option<String> {
     Some(rest[..end].to_string())
 }
 
+/// Converts the `navigation_range: start..end` byte offsets of a
+/// rust-analyzer structure node into a source range.
+fn navigation_range(line: &str, source: &str, relative_path: &str) -> Option<SourceRangeDto> {
+    let start_marker = "navigation_range: ";
+    let start = line.find(start_marker)? + start_marker.len();
+    let rest = &line[start..];
+    let end = rest.find(',')?;
+    let (start_offset, end_offset) = rest[..end].split_once("..")?;
+    let (start_line, start_column) = line_column(source, start_offset.parse().ok()?)?;
+    let (end_line, end_column) = line_column(source, end_offset.parse().ok()?)?;
+    Some(SourceRangeDto {
+        path: relative_path.to_string(),
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    })
+}
+
+/// 1-based line and character column of a byte offset.
+fn line_column(source: &str, offset: usize) -> Option<(u32, u32)> {
+    let prefix = source.get(..offset)?;
+    let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+    Some((
+        prefix.matches('\n').count() as u32 + 1,
+        prefix[line_start..].chars().count() as u32 + 1,
+    ))
+}
+
 fn extract_symbol_kind(line: &str) -> Option<String> {
     let start_marker = "kind: SymbolKind(";
     let start = line.find(start_marker)? + start_marker.len();
@@ -774,6 +2210,10 @@ fn graph_node_kind_from_rust_analyzer(kind: &str) -> Option<GraphNodeKind> {
         "Function" => Some(GraphNodeKind::Function),
         "Method" => Some(GraphNodeKind::Method),
         "Module" => Some(GraphNodeKind::Module),
+        "Const" => Some(GraphNodeKind::Const),
+        "Static" => Some(GraphNodeKind::Static),
+        "TypeAlias" => Some(GraphNodeKind::TypeAlias),
+        "Union" => Some(GraphNodeKind::Union),
         _ => None,
     }
 }
@@ -802,10 +2242,13 @@ impl<'ast> Visit<'ast> for CallVisitor<'_> {
     fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
         if let Expr::Path(path) = node.func.as_ref() {
             if let Some(segment) = path.path.segments.last() {
+                let scope = self.graph.current_scope();
                 self.graph.pending_calls.push(PendingCall {
                     source_id: self.source_id.to_string(),
                     target_name: segment.ident.to_string(),
                     source_path: self.relative_path.to_string(),
+                    evidence: span_range(self.relative_path, node.span()),
+                    scope,
                 });
             }
         }
@@ -813,13 +2256,115 @@ impl<'ast> Visit<'ast> for CallVisitor<'_> {
     }
 
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let scope = self.graph.current_scope();
         self.graph.pending_calls.push(PendingCall {
             source_id: self.source_id.to_string(),
             target_name: node.method.to_string(),
             source_path: self.relative_path.to_string(),
+            evidence: span_range(self.relative_path, node.method.span()),
+            scope,
         });
         visit::visit_expr_method_call(self, node);
     }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        let scope = self.graph.push_cfg(&node.attrs);
+        visit::visit_local(self, node);
+        self.graph.pop_cfg(scope);
+    }
+
+    fn visit_stmt_macro(&mut self, node: &'ast syn::StmtMacro) {
+        let scope = self.graph.push_cfg(&node.attrs);
+        visit::visit_stmt_macro(self, node);
+        self.graph.pop_cfg(scope);
+    }
+
+    fn visit_expr_block(&mut self, node: &'ast syn::ExprBlock) {
+        let scope = self.graph.push_cfg(&node.attrs);
+        visit::visit_expr_block(self, node);
+        self.graph.pop_cfg(scope);
+    }
+
+    fn visit_arm(&mut self, node: &'ast syn::Arm) {
+        let scope = self.graph.push_cfg(&node.attrs);
+        visit::visit_arm(self, node);
+        self.graph.pop_cfg(scope);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        push_macro_invocation(self.graph, self.source_id, self.relative_path, node);
+        for statement in macro_statements(node.tokens.clone()) {
+            self.visit_stmt(&statement);
+        }
+    }
+}
+
+/// Best-effort parse of macro input so calls inside `vec![]`, `format!()`,
+/// `tokio::select!` and workspace macros are still attributed to the caller.
+/// Input is tried as comma-separated expressions, then as a statement block;
+/// anything else is split at top-level `,`, `;`, `=` and `=>` and each piece
+/// is parsed as an expression or, failing that, searched for delimited groups.
+/// Parsed tokens keep their original spans, so evidence points at the call.
+fn macro_statements(tokens: proc_macro2::TokenStream) -> Vec<Stmt> {
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+    if let Ok(expressions) = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(tokens.clone())
+    {
+        return expressions
+            .into_iter()
+            .map(|expression| Stmt::Expr(expression, None))
+            .collect();
+    }
+    if let Ok(statements) = Block::parse_within.parse2(tokens.clone()) {
+        return statements;
+    }
+
+    let mut statements = Vec::new();
+    for segment in macro_segments(tokens) {
+        if let Ok(expression) = syn::parse2::<Expr>(segment.iter().cloned().collect()) {
+            statements.push(Stmt::Expr(expression, None));
+            continue;
+        }
+        for token in segment {
+            if let TokenTree::Group(group) = token {
+                statements.extend(macro_statements(group.stream()));
+            }
+        }
+    }
+    statements
+}
+
+fn macro_segments(tokens: proc_macro2::TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut segments = vec![Vec::new()];
+    let mut previous_is_punct = false;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let next_is_arrow_head = matches!(
+            tokens.peek(),
+            Some(TokenTree::Punct(next)) if next.as_char() == '>'
+        );
+        let is_boundary = match &token {
+            TokenTree::Punct(punct) => match punct.as_char() {
+                ',' | ';' => true,
+                '=' if punct.spacing() == Spacing::Joint && next_is_arrow_head => {
+                    tokens.next();
+                    true
+                }
+                '=' => punct.spacing() == Spacing::Alone && !previous_is_punct,
+                _ => false,
+            },
+            _ => false,
+        };
+        previous_is_punct = !is_boundary && matches!(token, TokenTree::Punct(_));
+        if is_boundary {
+            segments.push(Vec::new());
+        } else if let Some(segment) = segments.last_mut() {
+            segment.push(token);
+        }
+    }
+    segments.retain(|segment| !segment.is_empty());
+    segments
 }
 
 fn use_tree_labels(tree: &UseTree) -> Vec<String> {
@@ -868,16 +2413,59 @@ fn path_label(path: &syn::Path) -> String {
         .join("::")
 }
 
-fn module_label_from_path(relative_path: &str) -> String {
-    let label = relative_path
-        .trim_start_matches("src/")
-        .trim_end_matches(".rs")
-        .replace('/', "::");
+fn visibility_dto(visibility: &Visibility) -> NodeVisibilityDto {
+    match visibility {
+        Visibility::Public(_) => NodeVisibilityDto::Public,
+        Visibility::Restricted(restricted) if restricted.path.is_ident("crate") => {
+            NodeVisibilityDto::Crate
+        }
+        Visibility::Restricted(restricted) if restricted.path.is_ident("super") => {
+            NodeVisibilityDto::Super
+        }
+        Visibility::Restricted(restricted) if restricted.path.is_ident("self") => {
+            NodeVisibilityDto::Private
+        }
+        Visibility::Restricted(_) => NodeVisibilityDto::Restricted,
+        Visibility::Inherited => NodeVisibilityDto::Private,
+    }
+}
 
-    label
-        .strip_suffix("::mod")
-        .unwrap_or(label.as_str())
-        .to_string()
+fn derive_paths(attributes: &[Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("derive"))
+        .filter_map(|attribute| {
+            attribute
+                .parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .map(|path| path_label(&path))
+        .collect()
+}
+
+fn attribute_paths(attributes: &[Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attribute| !attribute.path().is_ident("doc"))
+        .map(|attribute| path_label(attribute.path()))
+        .collect()
+}
+
+/// Resolves `.` and `..` components without touching the filesystem, so a
+/// `#[path = "../x.rs"]` file gets the same relative path from every target.
+fn lexically_normalized(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
 }
 
 fn relative_path(root: &Path, path: &Path) -> String {
@@ -897,6 +2485,37 @@ fn source_range(relative_path: &str, line: u32) -> SourceRangeDto {
     }
 }
 
+pub(super) fn span_range(relative_path: &str, span: proc_macro2::Span) -> SourceRangeDto {
+    let start = span.start();
+    let end = span.end();
+    SourceRangeDto {
+        path: relative_path.to_string(),
+        start_line: start.line as u32,
+        start_column: start.column as u32 + 1,
+        end_line: end.line as u32,
+        end_column: end.column as u32 + 1,
+    }
+}
+
+/// Range from the start of `start` to the end of `end`, such as an item's
+/// keyword or signature through its closing brace, leaving outer attributes
+/// and visibility out.
+fn item_range(
+    relative_path: &str,
+    start: proc_macro2::Span,
+    end: proc_macro2::Span,
+) -> SourceRangeDto {
+    let start = start.start();
+    let end = end.end();
+    SourceRangeDto {
+        path: relative_path.to_string(),
+        start_line: start.line as u32,
+        start_column: start.column as u32 + 1,
+        end_line: end.line as u32,
+        end_column: end.column as u32 + 1,
+    }
+}
+
 fn resolve_import_path(
     source_path: &str,
     import: &str,
@@ -944,14 +2563,6 @@ fn crate_source_prefix(source_path: &str) -> String {
     "src".to_string()
 }
 
-fn find_line(source: &str, needle: &str) -> u32 {
-    source
-        .lines()
-        .position(|line| line.contains(needle))
-        .map(|index| index as u32 + 1)
-        .unwrap_or(1)
-}
-
 fn generated_at_string() -> String {
     let seconds = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -962,8 +2573,12 @@ fn generated_at_string() -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{module_label_from_path, RustGraphExtractor};
-    use crate::graph::{EdgeProvenanceDto, GraphEdgeKind, GraphNodeKind};
+    use super::{navigation_range, RustGraphExtractor};
+    use crate::graph::unsafe_code::UnsafeSiteKindDto;
+    use crate::graph::{
+        BuildConfigurationDto, DependencyKindDto, EdgeConfidenceDto, EdgeProvenanceDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, NodeVisibilityDto,
+    };
     use crate::source::ValidatedRepoPath;
     use std::fs;
     use std::path::PathBuf;
@@ -994,12 +2609,163 @@ mod tests {
     }
 
     #[test]
-    fn module_label_strips_src_and_extension() {
-        assert_eq!(module_label_from_path("src/lib.rs"), "lib");
+    fn builds_module_tree_from_declarations_and_reports_orphans() {
+        let (repo, validated) = fixture_repo(
+            "module-tree",
+            r#"
+mod graph;
+#[path = "platform/unix_impl.rs"]
+mod sys;
+#[cfg(test)]
+mod tests;
+mod absent;
+"#,
+        );
+        let write = |path: &str, contents: &str| {
+            let path = repo.join(path);
+            fs::create_dir_all(path.parent().expect("fixture parent")).expect("create dir");
+            fs::write(path, contents).expect("write fixture file");
+        };
+        write(
+            "src/graph/mod.rs",
+            "pub mod rules;
+",
+        );
+        write(
+            "src/graph/rules.rs",
+            "pub fn check() {}
+",
+        );
+        write(
+            "src/platform/unix_impl.rs",
+            "mod detail;
+",
+        );
+        write(
+            "src/platform/detail.rs",
+            "pub fn probe() {}
+",
+        );
+        write(
+            "src/tests.rs",
+            "fn fixture() {}
+",
+        );
+        write(
+            "src/orphan.rs",
+            "pub fn unused() {}
+",
+        );
+        write(
+            "src/bin/tool/main.rs",
+            "mod helpers;
+fn main() {}
+",
+        );
+        write(
+            "src/bin/tool/helpers.rs",
+            "pub fn assist() {}
+",
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let has_edge = |kind: GraphEdgeKind, source_id: &str, target_id: &str| {
+            snapshot.edges.iter().any(|edge| {
+                edge.kind == kind && edge.source_id == source_id && edge.target_id == target_id
+            })
+        };
+        let diagnostic_paths = |code: &str| {
+            snapshot
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.code == code)
+                .filter_map(|diagnostic| diagnostic.source_path.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(has_edge(
+            GraphEdgeKind::Contains,
+            "module:fixture:lib",
+            "module:fixture:graph"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Contains,
+            "module:fixture:graph",
+            "module:fixture:graph::rules"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Defines,
+            "file:src/graph/rules.rs",
+            "module:fixture:graph::rules"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Defines,
+            "file:src/platform/unix_impl.rs",
+            "module:fixture:sys"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Defines,
+            "file:src/platform/detail.rs",
+            "module:fixture:sys::detail"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Contains,
+            "module:fixture:tool:tool",
+            "module:fixture:tool:helpers"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Contains,
+            "crate:fixture",
+            "file:src/bin/tool/helpers.rs"
+        ));
+        assert!(has_edge(
+            GraphEdgeKind::Defines,
+            "file:src/tests.rs",
+            "module:fixture:tests"
+        ));
         assert_eq!(
-            module_label_from_path("src/domain/model.rs"),
-            "domain::model"
+            diagnostic_paths("orphan_source_file"),
+            vec!["src/orphan.rs"]
         );
+        assert_eq!(diagnostic_paths("module_file_missing"), vec!["src/lib.rs"]);
+        assert!(has_edge(
+            GraphEdgeKind::Contains,
+            "module:fixture:lib",
+            "module:fixture:absent"
+        ));
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn files_shared_between_targets_keep_their_first_module() {
+        let (repo, validated) = fixture_repo("shared-module-file", "pub mod shared;\n");
+        fs::write(repo.join("src/shared.rs"), "pub fn common() {}\n").expect("write shared");
+        fs::create_dir_all(repo.join("src/bin")).expect("create bin dir");
+        fs::write(
+            repo.join("src/bin/tool.rs"),
+            "#[path = \"../shared.rs\"]\nmod shared;\nfn main() {}\n",
+        )
+        .expect("write bin");
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+
+        assert!(snapshot.edges.iter().any(|edge| {
+            edge.kind == GraphEdgeKind::Contains
+                && edge.source_id == "module:fixture:tool:tool"
+                && edge.target_id == "module:fixture:shared"
+        }));
+        assert!(snapshot
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == GraphEdgeKind::Contains)
+            .all(|edge| snapshot.nodes.iter().any(|node| node.id == edge.target_id)));
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
     }
 
     #[test]
@@ -1039,6 +2805,14 @@ pub fn entry() { helper(); }
             edge.kind == GraphEdgeKind::Defines
                 && edge.provenance == EdgeProvenanceDto::RustAnalyzer
         }));
+        let entry_complexity = snapshot
+            .nodes
+            .iter()
+            .find(|node| node.kind == GraphNodeKind::Function && node.label == "entry")
+            .and_then(|node| node.metrics.as_ref()?.complexity.as_ref())
+            .expect("entry complexity metrics");
+        assert_eq!(entry_complexity.lines_of_code, 1);
+        assert_eq!(entry_complexity.cyclomatic_complexity, 1);
 
         fs::remove_dir_all(repo).expect("cleanup fixture repo");
     }
@@ -1089,7 +2863,8 @@ pub fn open_project() {}
             r#"
 use std::{fs, path::PathBuf};
 use crate::nested::child;
-mod nested {
+mod nested;
+mod inline {
     pub fn child() {}
 }
 "#,
@@ -1110,6 +2885,10 @@ pub fn child() {}
             .nodes
             .iter()
             .any(|node| node.kind == GraphNodeKind::Module && node.label == "nested"));
+        assert!(snapshot
+            .nodes
+            .iter()
+            .any(|node| node.kind == GraphNodeKind::Module && node.label == "inline"));
         assert!(snapshot
             .edges
             .iter()
@@ -1122,4 +2901,743 @@ pub fn child() {}
 
         fs::remove_dir_all(repo).expect("cleanup fixture repo");
     }
+
+    #[test]
+    fn extracts_nested_inline_modules_with_module_path_ids() {
+        let (repo, validated) = fixture_repo(
+            "inline-modules",
+            r#"
+pub mod outer {
+    pub struct Config;
+    impl Config { pub fn load() {} }
+    pub mod inner {
+        pub enum Mode { Fast }
+        pub fn helper() {}
+    }
+}
+pub mod sibling {
+    pub struct Config;
+    pub fn helper() {}
+}
+"#,
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let syn_ids = |kind: GraphNodeKind, label: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .filter(|node| {
+                    node.kind == kind && node.label == label && !node.id.contains("rust-analyzer")
+                })
+                .map(|node| node.id.as_str())
+                .collect::<Vec<_>>()
+        };
+        let contains = |source_id: &str, target_id: &str| {
+            snapshot.edges.iter().any(|edge| {
+                matches!(
+                    edge.kind,
+                    GraphEdgeKind::Contains | GraphEdgeKind::Defines | GraphEdgeKind::DefinesMethod
+                ) && edge.source_id == source_id
+                    && edge.target_id == target_id
+            })
+        };
+
+        assert_eq!(
+            syn_ids(GraphNodeKind::Struct, "Config"),
+            vec![
+                "struct:fixture:src/lib.rs:outer:Config",
+                "struct:fixture:src/lib.rs:sibling:Config",
+            ]
+        );
+        assert_eq!(
+            syn_ids(GraphNodeKind::Function, "helper"),
+            vec![
+                "function:fixture:src/lib.rs:outer::inner:helper",
+                "function:fixture:src/lib.rs:sibling:helper",
+            ]
+        );
+        assert!(contains(
+            "module:fixture:src/lib.rs:outer",
+            "module:fixture:src/lib.rs:outer:inner"
+        ));
+        assert!(contains(
+            "module:fixture:src/lib.rs:outer:inner",
+            "enum:fixture:src/lib.rs:outer::inner:Mode"
+        ));
+        assert!(contains(
+            "impl:fixture:src/lib.rs:outer:impl_Config",
+            "method:fixture:src/lib.rs:outer:Config:load"
+        ));
+        let range = |id: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .and_then(|node| node.source_range.as_ref())
+                .map(|range| {
+                    (
+                        range.start_line,
+                        range.start_column,
+                        range.end_line,
+                        range.end_column,
+                    )
+                })
+        };
+        assert_eq!(
+            range("struct:fixture:src/lib.rs:outer:Config"),
+            Some((3, 9, 3, 23))
+        );
+        assert_eq!(
+            range("struct:fixture:src/lib.rs:sibling:Config"),
+            Some((11, 9, 11, 23))
+        );
+        assert_eq!(
+            range("impl:fixture:src/lib.rs:outer:impl_Config"),
+            Some((4, 5, 4, 37))
+        );
+        assert_eq!(
+            range("module:fixture:src/lib.rs:outer:inner"),
+            Some((5, 9, 8, 6))
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn converts_rust_analyzer_navigation_offsets_to_ranges() {
+        let source = "fn a() {}\nstruct Ünit;\n";
+        let line = r#"StructureNode { parent: None, label: "Ünit", navigation_range: 17..22, node_range: 10..23, kind: SymbolKind(Struct), detail: None, deprecated: false }"#;
+
+        let range = navigation_range(line, source, "src/lib.rs").expect("navigation range");
+
+        assert_eq!(
+            (
+                range.start_line,
+                range.start_column,
+                range.end_line,
+                range.end_column
+            ),
+            (2, 8, 2, 12)
+        );
+        assert_eq!(navigation_range("label: \"x\"", source, "src/lib.rs"), None);
+    }
+
+    #[test]
+    fn extracts_workspace_and_external_dependency_edges() {
+        let repo = unique_temp_dir("dependencies");
+        let write = |path: &str, contents: &str| {
+            let path = repo.join(path);
+            fs::create_dir_all(path.parent().expect("fixture parent")).expect("create dir");
+            fs::write(path, contents).expect("write fixture file");
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"app\", \"core\"]\nexclude = [\"vendor\"]\nresolver = \"2\"\n",
+        );
+        write(
+            "app/Cargo.toml",
+            r#"[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+core = { path = "../core" }
+fast-json = { path = "../vendor/fast-json", package = "json", optional = true, default-features = false, features = ["simd"] }
+
+[dev-dependencies]
+fast-json = { path = "../vendor/fast-json", package = "json" }
+
+[features]
+default = ["fast-json"]
+"#,
+        );
+        write("app/src/lib.rs", "pub fn app() {}\n");
+        write(
+            "core/Cargo.toml",
+            "[package]\nname = \"core\"\nversion = \"0.2.0\"\nedition = \"2021\"\n",
+        );
+        write("core/src/lib.rs", "pub fn core() {}\n");
+        write(
+            "vendor/fast-json/Cargo.toml",
+            "[package]\nname = \"json\"\nversion = \"1.4.0\"\nedition = \"2021\"\n\n[features]\ndefault = []\nsimd = []\n",
+        );
+        write("vendor/fast-json/src/lib.rs", "");
+        let validated = ValidatedRepoPath::parse_existing_cargo_repo(&repo).expect("valid repo");
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let dependency = |target_id: &str| {
+            snapshot
+                .edges
+                .iter()
+                .find(|edge| {
+                    edge.kind == GraphEdgeKind::DependsOn
+                        && edge.source_id == "crate:app"
+                        && edge.target_id == target_id
+                })
+                .and_then(|edge| edge.dependency.clone())
+                .unwrap_or_else(|| panic!("dependency edge to {target_id}"))
+        };
+
+        let core = dependency("crate:core");
+        assert_eq!(core.kinds, vec![DependencyKindDto::Normal]);
+        assert_eq!(core.resolved_version, "0.2.0");
+
+        assert!(snapshot.nodes.iter().any(|node| {
+            node.id == "external_crate:json:1.4.0" && node.kind == GraphNodeKind::ExternalCrate
+        }));
+        let json = dependency("external_crate:json:1.4.0");
+        assert_eq!(
+            json.kinds,
+            vec![DependencyKindDto::Normal, DependencyKindDto::Dev]
+        );
+        assert!(json.optional);
+        assert_eq!(json.rename.as_deref(), Some("fast-json"));
+        assert_eq!(json.requested_features, vec!["simd".to_string()]);
+        assert!(json.enabled_features.contains(&"simd".to_string()));
+
+        fs::remove_dir_all(repo).expect("cleanup dependency fixture");
+    }
+
+    #[test]
+    fn extracts_macro_definitions_invocations_and_derives() {
+        let (repo, validated) = fixture_repo(
+            "macros",
+            r#"
+#[macro_export]
+macro_rules! log_step { ($value:expr) => { record($value) }; }
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Step;
+fn record(_value: u32) {}
+fn parse() -> u32 { 1 }
+fn render() -> String { String::new() }
+fn fallback() {}
+fn handle(_value: u32) {}
+pub fn entry() {
+    log_step!(parse());
+    let _items = vec![render(), format!("{}", render())];
+    select! { value = parse() => { handle(value) }, else => fallback() }
+}
+"#,
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let node = |kind: GraphNodeKind, label: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| node.kind == kind && node.label == label)
+                .unwrap_or_else(|| panic!("{label} node"))
+        };
+        let calls_from_entry = |target: &str| {
+            let target_id = &node(GraphNodeKind::Function, target).id;
+            let entry_id = &node(GraphNodeKind::Function, "entry").id;
+            snapshot.edges.iter().any(|edge| {
+                edge.kind == GraphEdgeKind::Calls
+                    && &edge.source_id == entry_id
+                    && &edge.target_id == target_id
+            })
+        };
+
+        let log_step = node(GraphNodeKind::Macro, "log_step");
+        assert_eq!(log_step.visibility, Some(NodeVisibilityDto::Public));
+        let invocation = snapshot
+            .edges
+            .iter()
+            .find(|edge| edge.kind == GraphEdgeKind::InvokesMacro)
+            .expect("macro invocation edge");
+        assert_eq!(
+            invocation.source_id,
+            node(GraphNodeKind::Function, "entry").id
+        );
+        assert_eq!(invocation.target_id, log_step.id);
+        assert_eq!(
+            invocation.evidence.as_ref().map(|range| range.start_line),
+            Some(12)
+        );
+        for target in ["parse", "render", "handle", "fallback"] {
+            assert!(calls_from_entry(target), "entry should call {target}");
+        }
+        assert_eq!(
+            node(GraphNodeKind::Struct, "Step").derives,
+            vec!["Debug", "Clone", "serde::Serialize"]
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn records_unsafe_sites_on_functions_impls_and_traits() {
+        let (repo, validated) = fixture_repo(
+            "unsafe",
+            r#"
+pub struct Handle(*mut u8);
+/// # Safety
+/// Implementors must be thread safe.
+pub unsafe trait Shared {}
+// SAFETY: `Handle` is never aliased.
+unsafe impl Send for Handle {}
+impl Handle {
+    pub fn first(&self) -> u8 {
+        unsafe { *self.0 }
+    }
+}
+pub fn safe() {}
+"#,
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let sites = |kind: GraphNodeKind, label: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| node.kind == kind && node.label == label)
+                .unwrap_or_else(|| panic!("{label} node"))
+                .unsafe_sites
+                .iter()
+                .map(|site| (site.kind, site.range.start_line, site.has_safety_comment))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sites(GraphNodeKind::Trait, "Shared"),
+            vec![(UnsafeSiteKindDto::Trait, 5, true)]
+        );
+        assert_eq!(
+            sites(GraphNodeKind::Impl, "impl Send for Handle"),
+            vec![(UnsafeSiteKindDto::Impl, 7, true)]
+        );
+        assert_eq!(
+            sites(GraphNodeKind::Method, "first"),
+            vec![(UnsafeSiteKindDto::Block, 10, false)]
+        );
+        assert!(sites(GraphNodeKind::Function, "safe").is_empty());
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn maps_spawned_tasks_channels_and_sync_primitive_accesses() {
+        let (repo, validated) = fixture_repo(
+            "concurrency",
+            r#"
+pub struct AppState {
+    config: tokio::sync::RwLock<Config>,
+    running: std::sync::atomic::AtomicBool,
+    name: String,
+}
+pub struct Config;
+impl AppState {
+    pub async fn start(&self) {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        tokio::spawn(async move {
+            tx.send(1).await.ok();
+        });
+        let _config = self.config.write().await;
+        self.running.store(true, Ordering::SeqCst);
+        rx.recv().await;
+    }
+}
+pub async fn status(state: &AppState) -> bool {
+    let _config = state.config.read().await;
+    state.running.load(Ordering::SeqCst)
+}
+"#,
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let node = |kind: GraphNodeKind, label: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| {
+                    node.kind == kind && node.label == label && !node.id.contains("rust-analyzer")
+                })
+                .unwrap_or_else(|| panic!("{label} node"))
+        };
+        let edge = |kind: GraphEdgeKind, source: &GraphNodeDto, target: &GraphNodeDto| {
+            snapshot
+                .edges
+                .iter()
+                .find(|edge| {
+                    edge.kind == kind && edge.source_id == source.id && edge.target_id == target.id
+                })
+                .map(|edge| edge.confidence.clone())
+        };
+
+        let state = node(GraphNodeKind::Struct, "AppState");
+        let config = node(GraphNodeKind::SyncPrimitive, "AppState.config");
+        let running = node(GraphNodeKind::SyncPrimitive, "AppState.running");
+        let start = node(GraphNodeKind::Method, "start");
+        let status = node(GraphNodeKind::Function, "status");
+        let task = node(GraphNodeKind::Task, "tokio::spawn in AppState::start");
+        let channel = node(
+            GraphNodeKind::SyncPrimitive,
+            "tokio::sync::mpsc::channel in AppState::start",
+        );
+
+        assert_eq!(
+            config.signature.as_deref(),
+            Some("config: tokio::sync::RwLock<Config>")
+        );
+        assert!(!snapshot
+            .nodes
+            .iter()
+            .any(|node| node.label == "AppState.name"));
+        assert_eq!(
+            edge(GraphEdgeKind::Defines, state, config),
+            Some(EdgeConfidenceDto::Exact)
+        );
+        assert_eq!(
+            edge(GraphEdgeKind::Spawns, start, task),
+            Some(EdgeConfidenceDto::Exact)
+        );
+        assert_eq!(
+            edge(GraphEdgeKind::Defines, start, channel),
+            Some(EdgeConfidenceDto::Exact)
+        );
+        assert!(edge(GraphEdgeKind::Sends, task, channel).is_some());
+        assert!(edge(GraphEdgeKind::Receives, start, channel).is_some());
+        assert_eq!(
+            edge(GraphEdgeKind::Locks, start, config),
+            Some(EdgeConfidenceDto::Exact)
+        );
+        assert_eq!(
+            edge(GraphEdgeKind::References, start, running),
+            Some(EdgeConfidenceDto::Exact)
+        );
+        assert_eq!(
+            edge(GraphEdgeKind::Locks, status, config),
+            Some(EdgeConfidenceDto::Inferred)
+        );
+        assert_eq!(
+            edge(GraphEdgeKind::References, status, running),
+            Some(EdgeConfidenceDto::Inferred)
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn records_error_flow_on_functions_error_enums_and_from_impls() {
+        let (repo, validated) = fixture_repo(
+            "error_flow",
+            r#"
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+}
+pub struct CommandError(String);
+impl From<LoadError> for CommandError {
+    fn from(error: LoadError) -> Self {
+        CommandError(error.to_string())
+    }
+}
+pub fn load() -> Result<String, LoadError> {
+    Ok(std::fs::read_to_string("config.json")?)
+}
+pub fn command() -> Result<String, CommandError> {
+    let config = load()?;
+    Ok(config)
+}
+"#,
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let report = crate::graph::error_flow::error_flow_report(&snapshot);
+        let relations = report
+            .relations
+            .iter()
+            .map(|relation| {
+                (
+                    relation.source_type.as_deref(),
+                    relation.target_type.as_str(),
+                    relation.lossy,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            relations,
+            vec![
+                (Some("LoadError"), "CommandError", 1),
+                (Some("std::io::Error"), "LoadError", 0),
+            ]
+        );
+        assert_eq!(report.lossy_sites.len(), 1);
+        assert_eq!(
+            report.lossy_sites[0].node_label,
+            "impl From for CommandError"
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn records_state_assignments_only_for_reassigned_enum_fields() {
+        let (repo, validated) = fixture_repo(
+            "state_machine",
+            r#"
+pub enum Phase { Idle, Busy }
+pub enum Kind { File, Module }
+pub struct Status { phase: Phase, kind: Kind }
+impl Status {
+    pub fn new() -> Self {
+        Status { phase: Phase::Idle, kind: Kind::File }
+    }
+    pub fn begin(&mut self) {
+        if self.phase == Phase::Idle {
+            self.phase = Phase::Busy;
+        }
+    }
+}
+"#,
+        );
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let report = crate::graph::state_machine::state_machines(&snapshot);
+
+        assert_eq!(report.machines.len(), 1);
+        assert_eq!(report.machines[0].enum_name, "Phase");
+        assert_eq!(
+            report.machines[0].mermaid,
+            "stateDiagram-v2\n    [*] --> Idle : new\n    Idle --> Busy : begin\n"
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn records_visibility_signatures_and_public_paths_through_reexports() {
+        let (repo, validated) = fixture_repo(
+            "public-api",
+            r#"
+mod internal;
+pub mod api {
+    pub struct Client;
+    impl Client {
+        pub fn connect(address: &str) -> Self { Client }
+        fn retry(&self) {}
+    }
+    pub(crate) fn crate_only() {}
+    pub(super) fn parent_only() {}
+    pub(in crate::api) fn scoped() {}
+    pub(self) fn private_self() {}
+    pub const LIMIT: usize = 8;
+    pub static mut COUNTER: u32 = 0;
+    pub type Id = u64;
+    pub union Bits { pub raw: u32, float: f32 }
+}
+pub use internal::Engine as Runtime;
+pub use self::internal::prelude::*;
+#[macro_export]
+macro_rules! trace { () => {} }
+"#,
+        );
+        fs::write(
+            repo.join("src/internal.rs"),
+            "pub struct Engine;\npub mod prelude {\n    pub fn start() {}\n    pub(crate) fn hidden() {}\n}\n",
+        )
+        .expect("write internal module");
+
+        let snapshot = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract graph");
+        let node = |kind: GraphNodeKind, label: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| node.kind == kind && node.label == label)
+                .unwrap_or_else(|| panic!("{label} node"))
+        };
+        let public_paths =
+            |kind: GraphNodeKind, label: &str| node(kind, label).public_paths.clone();
+
+        for (label, visibility) in [
+            ("crate_only", NodeVisibilityDto::Crate),
+            ("parent_only", NodeVisibilityDto::Super),
+            ("scoped", NodeVisibilityDto::Restricted),
+            ("private_self", NodeVisibilityDto::Private),
+        ] {
+            assert_eq!(
+                node(GraphNodeKind::Function, label).visibility,
+                Some(visibility),
+                "{label} visibility"
+            );
+            assert!(public_paths(GraphNodeKind::Function, label).is_empty());
+        }
+        assert_eq!(public_paths(GraphNodeKind::Module, "lib"), vec!["fixture"]);
+        assert_eq!(
+            public_paths(GraphNodeKind::Struct, "Client"),
+            vec!["fixture::api::Client"]
+        );
+        assert_eq!(
+            public_paths(GraphNodeKind::Method, "connect"),
+            vec!["fixture::api::Client::connect"]
+        );
+        assert_eq!(
+            node(GraphNodeKind::Method, "connect").signature.as_deref(),
+            Some("pub fn connect(address: &str) -> Self")
+        );
+        assert!(public_paths(GraphNodeKind::Method, "retry").is_empty());
+        for (kind, label, signature) in [
+            (GraphNodeKind::Const, "LIMIT", "pub const LIMIT: usize;"),
+            (
+                GraphNodeKind::Static,
+                "COUNTER",
+                "pub static mut COUNTER: u32;",
+            ),
+            (GraphNodeKind::TypeAlias, "Id", "pub type Id = u64;"),
+            (
+                GraphNodeKind::Union,
+                "Bits",
+                "pub union Bits { pub raw: u32, .. }",
+            ),
+        ] {
+            assert_eq!(
+                public_paths(kind.clone(), label),
+                vec![format!("fixture::api::{label}")]
+            );
+            assert_eq!(node(kind, label).signature.as_deref(), Some(signature));
+        }
+        assert_eq!(
+            public_paths(GraphNodeKind::Struct, "Engine"),
+            vec!["fixture::Runtime"]
+        );
+        assert_eq!(
+            public_paths(GraphNodeKind::Function, "start"),
+            vec!["fixture::start"]
+        );
+        assert!(public_paths(GraphNodeKind::Function, "hidden").is_empty());
+        assert!(public_paths(GraphNodeKind::Module, "prelude").is_empty());
+        assert_eq!(
+            public_paths(GraphNodeKind::Macro, "trace"),
+            vec!["fixture::trace"]
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn annotates_cfg_predicates_and_prunes_compiled_out_items_on_request() {
+        let (repo, _) = fixture_repo(
+            "cfg",
+            r#"
+pub fn always() { helper(); }
+#[cfg(feature = "extra")]
+pub fn extra() { helper(); }
+#[cfg(feature = "cli")]
+pub fn cli() {}
+#[cfg(unix)]
+fn platform() {}
+#[cfg(windows)]
+fn platform() {}
+fn helper() {}
+#[cfg(test)]
+mod tests {
+    fn check() {}
+}
+"#,
+        );
+        fs::write(
+            repo.join("Cargo.toml"),
+            "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[features]\ndefault = [\"cli\"]\ncli = []\nextra = []\n",
+        )
+        .expect("write manifest");
+        let validated = ValidatedRepoPath::parse_existing_cargo_repo(&repo).expect("valid repo");
+
+        let defaults = RustGraphExtractor
+            .extract(&validated)
+            .expect("extract default graph");
+        let requested = RustGraphExtractor
+            .extract_with_configuration(
+                &validated,
+                &BuildConfigurationDto {
+                    features: vec!["extra".to_string()],
+                    test: true,
+                    ..BuildConfigurationDto::default()
+                },
+            )
+            .expect("extract requested graph");
+        let pruned = RustGraphExtractor
+            .extract_with_configuration(
+                &validated,
+                &BuildConfigurationDto {
+                    prune_inactive: true,
+                    ..BuildConfigurationDto::default()
+                },
+            )
+            .expect("extract pruned graph");
+        let cfg_of = |snapshot: &crate::graph::GraphSnapshotDto, label: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| node.label == label && !node.id.contains("rust-analyzer"))
+                .map(|node| node.cfg.clone())
+        };
+
+        assert_eq!(cfg_of(&defaults, "always"), Some(None));
+        assert_eq!(
+            cfg_of(&defaults, "cli"),
+            Some(Some(r#"feature = "cli""#.to_string()))
+        );
+        assert_eq!(
+            cfg_of(&defaults, "extra"),
+            Some(Some(r#"feature = "extra""#.to_string()))
+        );
+        assert_eq!(cfg_of(&defaults, "tests"), Some(Some("test".to_string())));
+        assert_eq!(cfg_of(&defaults, "check"), Some(Some("test".to_string())));
+        assert!(defaults
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.code == "cfg_inactive_items"));
+        assert_eq!(cfg_of(&pruned, "extra"), None);
+        assert_eq!(cfg_of(&pruned, "tests"), None);
+        assert!(pruned.edges.iter().all(|edge| {
+            pruned.nodes.iter().any(|node| node.id == edge.source_id)
+                && !edge.target_id.ends_with(":tests")
+        }));
+        assert_eq!(
+            defaults
+                .configuration
+                .as_ref()
+                .map(|configuration| configuration.features_by_crate["fixture"].clone()),
+            Some(vec!["cli".to_string(), "default".to_string()])
+        );
+        if cfg!(unix) {
+            assert_eq!(
+                cfg_of(&defaults, "platform"),
+                Some(Some("unix".to_string()))
+            );
+        }
+
+        assert_eq!(
+            cfg_of(&requested, "extra"),
+            Some(Some(r#"feature = "extra""#.to_string()))
+        );
+        assert_eq!(cfg_of(&requested, "check"), Some(Some("test".to_string())));
+        assert!(requested.edges.iter().any(|edge| {
+            edge.kind == GraphEdgeKind::Calls
+                && edge.source_id.ends_with(":extra")
+                && edge.cfg.as_deref() == Some(r#"feature = "extra""#)
+        }));
+
+        fs::remove_dir_all(repo).expect("cleanup cfg fixture");
+    }
 }
@@ -1,8 +1,17 @@
 //! rust-analyzer lifecycle and Rust extraction service boundary.
 #![allow(dead_code)]
 
+mod cfg;
+mod complexity;
+mod concurrency;
+mod error_flow;
+mod exports;
 mod extraction;
 pub mod rust_relations;
+mod signature;
+mod state_machine;
+mod unsafety;
+mod wire_contract;
 
 use std::path::PathBuf;
 use std::process::Stdio;
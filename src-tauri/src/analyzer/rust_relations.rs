@@ -1,16 +1,20 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 
+use cargo_metadata::MetadataCommand;
 use serde::{Deserialize, Serialize};
 use syn::visit::{self, Visit};
-use syn::{ItemUse, UseTree};
+use syn::{Item, ItemUse, UseTree, Visibility};
 use walkdir::WalkDir;
 
+use crate::graph::relations::FileRelationReexportHopDto;
 use crate::graph::{AnalyzerDiagnosticDto, SourceRangeDto};
 use crate::source::ValidatedRepoPath;
 
 const RUST_IMPORT_RELATION_ANALYZER: &str = "syn-rust-import-relations";
+/// Longest chain of `pub use` re-exports followed for one import.
+const MAX_REEXPORT_HOPS: usize = 8;
 
 #[derive(Debug, Default)]
 pub struct RustImportRelationExtractor;
@@ -21,6 +25,7 @@ impl RustImportRelationExtractor {
         source_root: &ValidatedRepoPath,
     ) -> Result<RustImportRelationSnapshotDto, RustImportRelationExtractionError> {
         let mut accumulator = RustImportRelationAccumulator::new(source_root);
+        accumulator.load_workspace_crates();
         accumulator.collect_source_files();
         accumulator.extract_imports();
         Ok(accumulator.finish())
@@ -44,6 +49,9 @@ pub struct RustImportRelationFactDto {
     pub target_path: Option<String>,
     pub status: RustImportResolutionStatusDto,
     pub evidence: SourceRangeDto,
+    /// `pub use` re-exports passed through on the way to `target_path`, in
+    /// order; empty when the import names the defining file directly.
+    pub reexport_hops: Vec<FileRelationReexportHopDto>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,7 +66,9 @@ pub enum RustImportRelationExtractionError {}
 
 struct RustImportRelationAccumulator<'a> {
     source_root: &'a ValidatedRepoPath,
+    workspace_crates: WorkspaceCrates,
     file_paths: BTreeSet<String>,
+    exports_by_path: BTreeMap<String, ModuleExports>,
     facts: Vec<RustImportRelationFactDto>,
     diagnostics: Vec<AnalyzerDiagnosticDto>,
 }
@@ -67,12 +77,32 @@ impl<'a> RustImportRelationAccumulator<'a> {
     fn new(source_root: &'a ValidatedRepoPath) -> Self {
         Self {
             source_root,
+            workspace_crates: WorkspaceCrates::default(),
             file_paths: BTreeSet::new(),
+            exports_by_path: BTreeMap::new(),
             facts: Vec::new(),
             diagnostics: Vec::new(),
         }
     }
 
+    /// Loads workspace members when the source root is a Cargo workspace.
+    /// Other source roots keep crate-local resolution only.
+    fn load_workspace_crates(&mut self) {
+        let manifest_path = self.source_root.as_path().join("Cargo.toml");
+        if !manifest_path.is_file() {
+            return;
+        }
+
+        match WorkspaceCrates::load(self.source_root.as_path(), &manifest_path) {
+            Ok(workspace_crates) => self.workspace_crates = workspace_crates,
+            Err(error) => self.add_diagnostic(
+                "rust_import_cargo_metadata_failed",
+                format!("failed to read Cargo metadata: {error}"),
+                None,
+            ),
+        }
+    }
+
     fn collect_source_files(&mut self) {
         for entry in WalkDir::new(self.source_root.as_path()) {
             let entry = match entry {
@@ -101,7 +131,10 @@ impl<'a> RustImportRelationAccumulator<'a> {
         }
     }
 
+    /// Parses every file first so export tables are complete before any
+    /// import is resolved through them.
     fn extract_imports(&mut self) {
+        let mut pending_imports = Vec::new();
         let file_paths = self.file_paths.iter().cloned().collect::<Vec<_>>();
         for relative_path in file_paths {
             let source_path = self.source_root.as_path().join(&relative_path);
@@ -131,45 +164,222 @@ impl<'a> RustImportRelationAccumulator<'a> {
 
             let mut visitor = UseVisitor::new(&relative_path, &source);
             visitor.visit_file(&parsed);
+            pending_imports.extend(visitor.imports);
+            self.exports_by_path.insert(
+                relative_path.clone(),
+                ModuleExports::from_items(&relative_path, &parsed.items),
+            );
+        }
 
-            for pending_import in visitor.imports {
-                self.add_import_fact(pending_import);
-            }
+        for pending_import in pending_imports {
+            self.add_import_facts(pending_import);
         }
     }
 
-    fn add_import_fact(&mut self, pending_import: PendingImportFact) {
-        let target_path = resolve_import_path(
-            &pending_import.source_path,
-            &pending_import.import_path,
-            &self.file_paths,
-        );
-        let status = if target_path.is_some() {
-            RustImportResolutionStatusDto::Resolved
-        } else {
-            RustImportResolutionStatusDto::Unresolved
-        };
-        let is_explicit_local = is_explicit_local_import(&pending_import.import_path);
+    fn add_import_facts(&mut self, pending_import: PendingImportFact) {
+        let targets = self.resolve_import(&pending_import.source_path, &pending_import.import_path);
+        let is_explicit_local = self
+            .workspace_lib_root(&pending_import.source_path, &pending_import.import_path)
+            .is_some()
+            || is_explicit_local_import(&pending_import.import_path);
 
-        if status == RustImportResolutionStatusDto::Unresolved && !is_explicit_local {
-            return;
-        }
-
-        if status == RustImportResolutionStatusDto::Unresolved {
+        if targets.is_empty() {
+            if !is_explicit_local {
+                return;
+            }
             self.add_diagnostic(
                 "rust_import_unresolved",
                 format!("unresolved Rust import '{}'", pending_import.import_path),
                 Some(pending_import.source_path.clone()),
             );
+            self.facts.push(RustImportRelationFactDto {
+                source_path: pending_import.source_path,
+                import_path: pending_import.import_path,
+                target_path: None,
+                status: RustImportResolutionStatusDto::Unresolved,
+                evidence: pending_import.evidence,
+                reexport_hops: Vec::new(),
+            });
+            return;
+        }
+
+        for target in targets {
+            self.facts.push(RustImportRelationFactDto {
+                source_path: pending_import.source_path.clone(),
+                import_path: pending_import.import_path.clone(),
+                target_path: Some(target.path),
+                status: RustImportResolutionStatusDto::Resolved,
+                evidence: pending_import.evidence.clone(),
+                reexport_hops: target.hops,
+            });
+        }
+    }
+
+    fn workspace_lib_root(&self, source_path: &str, import_path: &str) -> Option<&str> {
+        let crate_name = import_path.split("::").next()?;
+        self.workspace_crates.lib_root(source_path, crate_name)
+    }
+
+    /// Files an import resolves to. A named import resolves to the file that
+    /// defines the name, following `pub use` and glob re-exports; when the
+    /// chain leaves the workspace it stops at the last re-exporting file. A
+    /// glob import resolves to the module file plus every file it re-exports
+    /// from.
+    fn resolve_import(&self, source_path: &str, import_path: &str) -> Vec<ResolvedImport> {
+        let Some((path, rest, must_confirm)) = self.resolve_module(source_path, import_path) else {
+            return Vec::new();
+        };
+
+        if rest == ["*"] {
+            let mut targets = Vec::new();
+            self.collect_glob_targets(&path, Vec::new(), 0, &mut targets);
+            return targets;
         }
 
-        self.facts.push(RustImportRelationFactDto {
-            source_path: pending_import.source_path,
-            import_path: pending_import.import_path,
-            target_path,
-            status,
-            evidence: pending_import.evidence,
+        match self.follow_exports(&path, &rest, 0, &mut BTreeSet::new()) {
+            Some(resolved) => vec![resolved],
+            None if must_confirm => Vec::new(),
+            None => vec![ResolvedImport {
+                path,
+                hops: Vec::new(),
+            }],
+        }
+    }
+
+    /// Deepest module file named by the import and the segments left after
+    /// it. Workspace crate imports fall back to the library root, in which
+    /// case anything longer than a single item name must be confirmed by the
+    /// root's export table.
+    fn resolve_module(
+        &self,
+        source_path: &str,
+        import_path: &str,
+    ) -> Option<(String, Vec<String>, bool)> {
+        let Some(lib_root) = self.workspace_lib_root(source_path, import_path) else {
+            let (path, rest) = resolve_module_path(source_path, import_path, &self.file_paths)?;
+            return Some((path, rest, false));
+        };
+
+        let segments = import_path
+            .split("::")
+            .skip(1)
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        match resolve_segments_from_prefix(&parent_path(lib_root), &segments, &self.file_paths) {
+            Some((path, consumed)) => Some((path, owned_segments(&segments[consumed..]), false)),
+            None if self.file_paths.contains(lib_root) => Some((
+                lib_root.to_string(),
+                owned_segments(&segments),
+                segments.len() > 1,
+            )),
+            None => None,
+        }
+    }
+
+    /// Finds the file defining `rest[0]` starting at `path`. Returns `None`
+    /// when neither the file's items nor its re-exports provide the name,
+    /// including when the chain is longer than [`MAX_REEXPORT_HOPS`] or
+    /// revisits a `(file, name)` pair through a re-export cycle.
+    fn follow_exports(
+        &self,
+        path: &str,
+        rest: &[String],
+        depth: usize,
+        visited: &mut BTreeSet<(String, String)>,
+    ) -> Option<ResolvedImport> {
+        let Some(name) = rest.first() else {
+            return Some(ResolvedImport {
+                path: path.to_string(),
+                hops: Vec::new(),
+            });
+        };
+        let exports = self.exports_by_path.get(path)?;
+        if exports.items.contains(name) {
+            return Some(ResolvedImport {
+                path: path.to_string(),
+                hops: Vec::new(),
+            });
+        }
+        if depth >= MAX_REEXPORT_HOPS || !visited.insert((path.to_string(), name.clone())) {
+            return None;
+        }
+
+        if let Some(reexport) = exports.named.get(name) {
+            let mut reexported_path = reexport.import_path.clone();
+            for segment in &rest[1..] {
+                reexported_path = join_path(reexported_path, segment);
+            }
+            let resolved = self
+                .resolve_module(path, &reexported_path)
+                .and_then(|(target, rest, _)| {
+                    self.follow_exports(&target, &rest, depth + 1, visited)
+                })
+                .unwrap_or_else(|| ResolvedImport {
+                    path: path.to_string(),
+                    hops: Vec::new(),
+                });
+            return Some(resolved.through(reexport));
+        }
+
+        exports.globs.iter().find_map(|glob| {
+            let (target, glob_rest, _) = self.resolve_module(path, &glob.import_path)?;
+            if glob_rest != ["*"] {
+                return None;
+            }
+            self.follow_exports(&target, rest, depth + 1, visited)
+                .map(|resolved| resolved.through(glob))
+        })
+    }
+
+    fn collect_glob_targets(
+        &self,
+        path: &str,
+        hops: Vec<FileRelationReexportHopDto>,
+        depth: usize,
+        targets: &mut Vec<ResolvedImport>,
+    ) {
+        if targets.iter().any(|target| target.path == path) {
+            return;
+        }
+        targets.push(ResolvedImport {
+            path: path.to_string(),
+            hops: hops.clone(),
         });
+        let Some(exports) = self.exports_by_path.get(path) else {
+            return;
+        };
+        if depth >= MAX_REEXPORT_HOPS {
+            return;
+        }
+
+        for reexport in exports.named.values() {
+            let Some(resolved) =
+                self.resolve_module(path, &reexport.import_path)
+                    .and_then(|(target, rest, _)| {
+                        self.follow_exports(&target, &rest, depth + 1, &mut BTreeSet::new())
+                    })
+            else {
+                continue;
+            };
+            if !targets.iter().any(|target| target.path == resolved.path) {
+                let mut chain = hops.clone();
+                chain.push(reexport.hop());
+                chain.extend(resolved.hops);
+                targets.push(ResolvedImport {
+                    path: resolved.path,
+                    hops: chain,
+                });
+            }
+        }
+        for glob in &exports.globs {
+            if let Some((target, rest, _)) = self.resolve_module(path, &glob.import_path) {
+                if rest == ["*"] {
+                    let mut chain = hops.clone();
+                    chain.push(glob.hop());
+                    self.collect_glob_targets(&target, chain, depth + 1, targets);
+                }
+            }
+        }
     }
 
     fn add_diagnostic(
@@ -182,6 +392,7 @@ impl<'a> RustImportRelationAccumulator<'a> {
             code: code.into(),
             message: message.into(),
             source_path,
+            evidence: None,
         });
     }
 
@@ -195,6 +406,181 @@ impl<'a> RustImportRelationAccumulator<'a> {
     }
 }
 
+/// Workspace members and the extern crate names each one can use to reach
+/// another member's library.
+#[derive(Debug, Default)]
+struct WorkspaceCrates {
+    packages: Vec<WorkspacePackage>,
+}
+
+#[derive(Debug)]
+struct WorkspacePackage {
+    /// Package directory relative to the source root; empty for the root
+    /// package.
+    root: String,
+    /// Crate name as written in paths, after `package = ".."` renames and
+    /// `-` to `_` normalization, mapped to that library's root file.
+    lib_roots_by_crate_name: BTreeMap<String, String>,
+}
+
+impl WorkspaceCrates {
+    fn load(source_root: &Path, manifest_path: &Path) -> Result<Self, String> {
+        let metadata = MetadataCommand::new()
+            .manifest_path(manifest_path)
+            .no_deps()
+            .exec()
+            .map_err(|error| error.to_string())?;
+        let workspace_packages = metadata.workspace_packages();
+        let lib_roots = workspace_packages
+            .iter()
+            .filter_map(|package| {
+                let lib = package.targets.iter().find(|target| target.is_lib())?;
+                let lib_root = relative_path(source_root, lib.src_path.as_std_path());
+                Some((
+                    package.name.to_string(),
+                    (lib.name.replace('-', "_"), lib_root),
+                ))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let packages = workspace_packages
+            .iter()
+            .map(|package| {
+                let mut lib_roots_by_crate_name = BTreeMap::new();
+                if let Some((lib_name, lib_root)) = lib_roots.get(package.name.as_str()) {
+                    lib_roots_by_crate_name.insert(lib_name.clone(), lib_root.clone());
+                }
+                for dependency in &package.dependencies {
+                    let Some((lib_name, lib_root)) = lib_roots.get(dependency.name.as_str()) else {
+                        continue;
+                    };
+                    let crate_name = dependency
+                        .rename
+                        .as_deref()
+                        .map(|rename| rename.replace('-', "_"))
+                        .unwrap_or_else(|| lib_name.clone());
+                    lib_roots_by_crate_name.insert(crate_name, lib_root.clone());
+                }
+
+                let manifest_directory = package
+                    .manifest_path
+                    .parent()
+                    .map(|directory| directory.as_std_path())
+                    .unwrap_or(source_root);
+                WorkspacePackage {
+                    root: relative_path(source_root, manifest_directory),
+                    lib_roots_by_crate_name,
+                }
+            })
+            .collect();
+
+        Ok(Self { packages })
+    }
+
+    /// Library root for `crate_name` as seen from the package that owns
+    /// `source_path`, the deepest package directory containing it.
+    fn lib_root(&self, source_path: &str, crate_name: &str) -> Option<&str> {
+        self.packages
+            .iter()
+            .filter(|package| {
+                package.root.is_empty() || source_path.starts_with(&format!("{}/", package.root))
+            })
+            .max_by_key(|package| package.root.len())?
+            .lib_roots_by_crate_name
+            .get(crate_name)
+            .map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResolvedImport {
+    path: String,
+    hops: Vec<FileRelationReexportHopDto>,
+}
+
+impl ResolvedImport {
+    fn through(mut self, reexport: &ReExport) -> Self {
+        self.hops.insert(0, reexport.hop());
+        self
+    }
+}
+
+/// Names a module file makes available to importers: its own top-level
+/// items, `pub use` re-exports by exported name, and `pub use path::*`
+/// globs.
+#[derive(Debug, Default)]
+struct ModuleExports {
+    items: BTreeSet<String>,
+    named: BTreeMap<String, ReExport>,
+    globs: Vec<ReExport>,
+}
+
+#[derive(Debug, Clone)]
+struct ReExport {
+    import_path: String,
+    evidence: SourceRangeDto,
+}
+
+impl ReExport {
+    fn hop(&self) -> FileRelationReexportHopDto {
+        FileRelationReexportHopDto {
+            export_path: self.import_path.clone(),
+            source_range: self.evidence.clone(),
+        }
+    }
+}
+
+impl ModuleExports {
+    fn from_items(relative_path: &str, items: &[Item]) -> Self {
+        let mut exports = Self::default();
+        for item in items {
+            let name = match item {
+                Item::Const(item) => Some(&item.ident),
+                Item::Enum(item) => Some(&item.ident),
+                Item::Fn(item) => Some(&item.sig.ident),
+                Item::Macro(item) => item.ident.as_ref(),
+                Item::Mod(item) => Some(&item.ident),
+                Item::Static(item) => Some(&item.ident),
+                Item::Struct(item) => Some(&item.ident),
+                Item::Trait(item) => Some(&item.ident),
+                Item::TraitAlias(item) => Some(&item.ident),
+                Item::Type(item) => Some(&item.ident),
+                Item::Union(item) => Some(&item.ident),
+                Item::Use(item) if !matches!(item.vis, Visibility::Inherited) => {
+                    exports.add_reexports(
+                        relative_path,
+                        item.use_token.span.start().line as u32,
+                        &item.tree,
+                    );
+                    None
+                }
+                _ => None,
+            };
+            if let Some(name) = name {
+                exports.items.insert(name.to_string());
+            }
+        }
+        exports
+    }
+
+    fn add_reexports(&mut self, relative_path: &str, line: u32, tree: &UseTree) {
+        let mut entries = Vec::new();
+        collect_use_tree_entries(tree, String::new(), &mut entries);
+        for (import_path, exported_name) in entries {
+            let reexport = ReExport {
+                evidence: source_range(relative_path, line),
+                import_path,
+            };
+            match exported_name {
+                Some(name) => {
+                    self.named.insert(name, reexport);
+                }
+                None => self.globs.push(reexport),
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct PendingImportFact {
     source_path: String,
@@ -258,6 +644,35 @@ fn collect_use_tree_labels(tree: &UseTree, prefix: String, labels: &mut Vec<Stri
     }
 }
 
+/// Use tree paths with the name they bind: the alias for renames, the last
+/// segment otherwise, and `None` for globs.
+pub(super) fn collect_use_tree_entries(
+    tree: &UseTree,
+    prefix: String,
+    entries: &mut Vec<(String, Option<String>)>,
+) {
+    match tree {
+        UseTree::Path(path) => {
+            let next_prefix = join_path(prefix, &path.ident.to_string());
+            collect_use_tree_entries(&path.tree, next_prefix, entries);
+        }
+        UseTree::Name(name) => {
+            let name = name.ident.to_string();
+            entries.push((join_path(prefix, &name), Some(name)));
+        }
+        UseTree::Rename(rename) => entries.push((
+            join_path(prefix, &rename.ident.to_string()),
+            Some(rename.rename.to_string()),
+        )),
+        UseTree::Glob(_) => entries.push((join_path(prefix, "*"), None)),
+        UseTree::Group(group) => {
+            for item in &group.items {
+                collect_use_tree_entries(item, prefix.clone(), entries);
+            }
+        }
+    }
+}
+
 fn join_path(prefix: String, segment: &str) -> String {
     if prefix.is_empty() {
         segment.to_string()
@@ -266,11 +681,22 @@ fn join_path(prefix: String, segment: &str) -> String {
     }
 }
 
+#[cfg(test)]
 fn resolve_import_path(
     source_path: &str,
     import_path: &str,
     file_paths: &BTreeSet<String>,
 ) -> Option<String> {
+    resolve_module_path(source_path, import_path, file_paths).map(|(path, _)| path)
+}
+
+/// Deepest module file the import names inside the importing crate, and the
+/// item segments left after it.
+fn resolve_module_path(
+    source_path: &str,
+    import_path: &str,
+    file_paths: &BTreeSet<String>,
+) -> Option<(String, Vec<String>)> {
     let mut segments = import_path
         .split("::")
         .filter(|segment| !segment.is_empty())
@@ -304,37 +730,44 @@ fn resolve_import_path(
     };
 
     for base_prefix in candidates {
-        if let Some(target_path) = resolve_segments_from_prefix(&base_prefix, &segments, file_paths)
+        if let Some((target_path, consumed)) =
+            resolve_segments_from_prefix(&base_prefix, &segments, file_paths)
         {
-            return Some(target_path);
+            return Some((target_path, owned_segments(&segments[consumed..])));
         }
     }
 
     None
 }
 
+/// Longest leading run of `segments` that names a module file, with the
+/// number of segments it consumed.
 fn resolve_segments_from_prefix(
     base_prefix: &str,
     segments: &[&str],
     file_paths: &BTreeSet<String>,
-) -> Option<String> {
+) -> Option<(String, usize)> {
     for length in (1..=segments.len()).rev() {
         let module_path = segments[..length].join("/");
         let direct_file = join_relative_path(base_prefix, &format!("{module_path}.rs"));
         let module_file = join_relative_path(base_prefix, &format!("{module_path}/mod.rs"));
 
         if file_paths.contains(&direct_file) {
-            return Some(direct_file);
+            return Some((direct_file, length));
         }
 
         if file_paths.contains(&module_file) {
-            return Some(module_file);
+            return Some((module_file, length));
         }
     }
 
     None
 }
 
+fn owned_segments(segments: &[&str]) -> Vec<String> {
+    segments.iter().map(|segment| segment.to_string()).collect()
+}
+
 fn is_explicit_local_import(import_path: &str) -> bool {
     import_path
         .split("::")
@@ -504,6 +937,213 @@ pub mod model;
         fs::remove_dir_all(repo).expect("cleanup fixture repo");
     }
 
+    #[test]
+    fn resolves_imports_of_sibling_workspace_crates_through_renames() {
+        let (repo, source_root) = fixture_source_root("workspace-imports");
+        let write = |path: &str, contents: &str| {
+            let path = repo.join(path);
+            fs::create_dir_all(path.parent().expect("fixture parent")).expect("create dir");
+            fs::write(path, contents).expect("write fixture file");
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/app\", \"crates/engine-core\"]\nresolver = \"2\"\n",
+        );
+        write(
+            "crates/app/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nengine = { path = \"../engine-core\", package = \"engine-core\" }\n",
+        );
+        write(
+            "crates/app/src/main.rs",
+            r#"
+use engine::model::Model;
+use engine::Engine;
+use engine::missing::Thing;
+use engine_core::model::Model as Unrenamed;
+fn main() {}
+"#,
+        );
+        write(
+            "crates/engine-core/Cargo.toml",
+            "[package]\nname = \"engine-core\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        );
+        write(
+            "crates/engine-core/src/lib.rs",
+            "pub mod model;\npub struct Engine;\n",
+        );
+        write("crates/engine-core/src/model.rs", "pub struct Model;\n");
+
+        let snapshot = RustImportRelationExtractor
+            .extract(&source_root)
+            .expect("extract Rust import relations");
+        let target_of = |import_path: &str| {
+            snapshot
+                .facts
+                .iter()
+                .find(|fact| {
+                    fact.source_path == "crates/app/src/main.rs" && fact.import_path == import_path
+                })
+                .map(|fact| fact.target_path.clone())
+        };
+
+        assert_eq!(
+            target_of("engine::model::Model"),
+            Some(Some("crates/engine-core/src/model.rs".to_string()))
+        );
+        assert_eq!(
+            target_of("engine::Engine"),
+            Some(Some("crates/engine-core/src/lib.rs".to_string()))
+        );
+        assert_eq!(target_of("engine_core::model::Model"), None);
+        assert!(snapshot.diagnostics.iter().any(|diagnostic| {
+            diagnostic.code == "rust_import_unresolved"
+                && diagnostic.message.contains("engine::missing::Thing")
+        }));
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn resolves_imports_through_reexport_chains_and_globs() {
+        let (repo, source_root) = fixture_source_root("reexport-imports");
+        let write = |path: &str, contents: &str| {
+            let path = repo.join(path);
+            fs::create_dir_all(path.parent().expect("fixture parent")).expect("create dir");
+            fs::write(path, contents).expect("write fixture file");
+        };
+        write(
+            "src/lib.rs",
+            r#"
+use crate::facade::Model;
+use crate::facade::Renamed;
+use crate::facade::helper;
+use crate::prelude::*;
+"#,
+        );
+        write(
+            "src/facade.rs",
+            r#"pub use crate::domain::model::Model;
+pub use crate::domain::Service as Renamed;
+pub use crate::nested::*;
+"#,
+        );
+        write(
+            "src/prelude.rs",
+            "pub use crate::facade::*;\npub struct Local;\n",
+        );
+        write("src/domain/mod.rs", "pub mod model;\npub struct Service;\n");
+        write("src/domain/model.rs", "pub struct Model;\n");
+        write("src/nested.rs", "pub fn helper() {}\n");
+
+        let snapshot = RustImportRelationExtractor
+            .extract(&source_root)
+            .expect("extract Rust import relations");
+        let resolved = |import_path: &str| {
+            snapshot
+                .facts
+                .iter()
+                .filter(|fact| fact.source_path == "src/lib.rs" && fact.import_path == import_path)
+                .map(|fact| {
+                    (
+                        fact.target_path.clone().unwrap_or_default(),
+                        fact.reexport_hops
+                            .iter()
+                            .map(|hop| {
+                                format!(
+                                    "{}:{} {}",
+                                    hop.source_range.path,
+                                    hop.source_range.start_line,
+                                    hop.export_path
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            resolved("crate::facade::Model"),
+            vec![(
+                "src/domain/model.rs".to_string(),
+                vec!["src/facade.rs:1 crate::domain::model::Model".to_string()]
+            )]
+        );
+        assert_eq!(
+            resolved("crate::facade::Renamed"),
+            vec![(
+                "src/domain/mod.rs".to_string(),
+                vec!["src/facade.rs:2 crate::domain::Service".to_string()]
+            )]
+        );
+        assert_eq!(
+            resolved("crate::facade::helper"),
+            vec![(
+                "src/nested.rs".to_string(),
+                vec!["src/facade.rs:3 crate::nested::*".to_string()]
+            )]
+        );
+        let prelude_targets = resolved("crate::prelude::*")
+            .into_iter()
+            .map(|(target, hops)| (target, hops.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prelude_targets,
+            vec![
+                ("src/prelude.rs".to_string(), 0),
+                ("src/facade.rs".to_string(), 1),
+                ("src/domain/model.rs".to_string(), 2),
+                ("src/domain/mod.rs".to_string(), 2),
+                ("src/nested.rs".to_string(), 2),
+            ]
+        );
+        assert!(snapshot.diagnostics.is_empty());
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn glob_reexport_cycles_do_not_invent_targets() {
+        let (repo, source_root) = fixture_source_root("reexport-cycle");
+        let write = |path: &str, contents: &str| {
+            fs::write(repo.join(path), contents).expect("write fixture file");
+        };
+        write(
+            "src/lib.rs",
+            "mod a;\nmod b;\nuse crate::a::Missing;\nuse crate::a::Found;\n",
+        );
+        write("src/a.rs", "pub use crate::b::*;\n");
+        write("src/b.rs", "pub use crate::a::*;\npub struct Found;\n");
+
+        let snapshot = RustImportRelationExtractor
+            .extract(&source_root)
+            .expect("extract Rust import relations");
+        let resolved = |import_path: &str| {
+            snapshot
+                .facts
+                .iter()
+                .filter(|fact| fact.source_path == "src/lib.rs" && fact.import_path == import_path)
+                .map(|fact| {
+                    (
+                        fact.target_path.clone().unwrap_or_default(),
+                        fact.reexport_hops.len(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            resolved("crate::a::Missing"),
+            vec![("src/a.rs".to_string(), 0)]
+        );
+        assert_eq!(
+            resolved("crate::a::Found"),
+            vec![("src/b.rs".to_string(), 1)]
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
     #[test]
     fn unresolved_local_imports_are_retained_as_facts_and_diagnostics() {
         let (repo, source_root) = fixture_source_root("unresolved-imports");
@@ -0,0 +1,290 @@
+//! Normalized declaration signatures used to list and compare public API.
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    parse_quote, Attribute, Field, Fields, FieldsNamed, ItemConst, ItemEnum, ItemMacro, ItemMod,
+    ItemStatic, ItemStruct, ItemTrait, ItemType, ItemUnion, Signature, TraitItem, Type, Visibility,
+};
+
+/// `pub fn name<T>(value: T) -> Output where ..`, without the body.
+pub(super) fn function_signature(visibility: &Visibility, signature: &Signature) -> String {
+    let mut tokens = visibility.to_token_stream();
+    signature.to_tokens(&mut tokens);
+    normalize_tokens(tokens)
+}
+
+/// Struct declaration with attributes other than `#[non_exhaustive]` removed.
+/// Fields that are not plain `pub` are left out behind a trailing `..`;
+/// such tuple fields keep their position as `_`.
+pub(super) fn struct_signature(item: &ItemStruct) -> String {
+    let mut item = item.clone();
+    retain_non_exhaustive(&mut item.attrs);
+    let hidden = strip_private_fields(&mut item.fields);
+    let signature = normalize_tokens(item.into_token_stream());
+    if hidden {
+        mark_hidden_fields(signature)
+    } else {
+        signature
+    }
+}
+
+/// Union declaration, normalized like [`struct_signature`].
+pub(super) fn union_signature(item: &ItemUnion) -> String {
+    let mut item = item.clone();
+    retain_non_exhaustive(&mut item.attrs);
+    let hidden = strip_private_named_fields(&mut item.fields);
+    let signature = normalize_tokens(item.into_token_stream());
+    if hidden {
+        mark_hidden_fields(signature)
+    } else {
+        signature
+    }
+}
+
+/// `pub const NAME: Type;`, without the value.
+pub(super) fn const_signature(item: &ItemConst) -> String {
+    let mut tokens = item.vis.to_token_stream();
+    item.const_token.to_tokens(&mut tokens);
+    item.ident.to_tokens(&mut tokens);
+    item.generics.to_tokens(&mut tokens);
+    item.colon_token.to_tokens(&mut tokens);
+    item.ty.to_tokens(&mut tokens);
+    item.semi_token.to_tokens(&mut tokens);
+    normalize_tokens(tokens)
+}
+
+/// `pub static mut NAME: Type;`, without the value.
+pub(super) fn static_signature(item: &ItemStatic) -> String {
+    let mut tokens = item.vis.to_token_stream();
+    item.static_token.to_tokens(&mut tokens);
+    item.mutability.to_tokens(&mut tokens);
+    item.ident.to_tokens(&mut tokens);
+    item.colon_token.to_tokens(&mut tokens);
+    item.ty.to_tokens(&mut tokens);
+    item.semi_token.to_tokens(&mut tokens);
+    normalize_tokens(tokens)
+}
+
+/// `pub type Name<T> = Target;`, attributes removed.
+pub(super) fn type_alias_signature(item: &ItemType) -> String {
+    let mut item = item.clone();
+    item.attrs.clear();
+    normalize_tokens(item.into_token_stream())
+}
+
+/// Enum declaration with every variant and field, attributes other than
+/// `#[non_exhaustive]` removed.
+pub(super) fn enum_signature(item: &ItemEnum) -> String {
+    let mut item = item.clone();
+    retain_non_exhaustive(&mut item.attrs);
+    for variant in &mut item.variants {
+        retain_non_exhaustive(&mut variant.attrs);
+        for field in &mut variant.fields {
+            field.attrs.clear();
+        }
+    }
+    normalize_tokens(item.into_token_stream())
+}
+
+/// Trait declaration with associated item signatures and no default bodies.
+pub(super) fn trait_signature(item: &ItemTrait) -> String {
+    let mut item = item.clone();
+    item.attrs.clear();
+    for trait_item in &mut item.items {
+        match trait_item {
+            TraitItem::Fn(function) => {
+                function.attrs.clear();
+                function.default = None;
+                function.semi_token = Some(Default::default());
+            }
+            TraitItem::Const(constant) => {
+                constant.attrs.clear();
+                constant.default = None;
+            }
+            TraitItem::Type(associated) => {
+                associated.attrs.clear();
+                associated.default = None;
+            }
+            _ => {}
+        }
+    }
+    normalize_tokens(item.into_token_stream())
+}
+
+/// `pub name: Type` for one named struct field, attributes removed.
+pub(super) fn field_signature(field: &Field) -> String {
+    let mut field = field.clone();
+    field.attrs.clear();
+    normalize_tokens(field.into_token_stream())
+}
+
+/// Type as written, such as `std::io::Error` or `Box<dyn Error + Send>`.
+pub(super) fn type_text(ty: &Type) -> String {
+    normalize_tokens(ty.to_token_stream())
+}
+
+pub(super) fn module_signature(item: &ItemMod) -> String {
+    let mut tokens = item.vis.to_token_stream();
+    item.mod_token.to_tokens(&mut tokens);
+    item.ident.to_tokens(&mut tokens);
+    normalize_tokens(tokens)
+}
+
+pub(super) fn macro_signature(item: &ItemMacro) -> Option<String> {
+    let ident = item.ident.as_ref()?;
+    Some(format!("macro_rules! {ident}"))
+}
+
+/// `#[non_exhaustive]` changes what downstream crates may construct and
+/// match, so it is the one attribute that stays part of the signature.
+fn retain_non_exhaustive(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| attr.path().is_ident("non_exhaustive"));
+}
+
+/// Removes fields that are not plain `pub`, including `pub(crate)` and
+/// `pub(super)` ones, and reports whether any were removed.
+fn strip_private_fields(fields: &mut Fields) -> bool {
+    match fields {
+        Fields::Named(named) => strip_private_named_fields(named),
+        Fields::Unnamed(unnamed) => {
+            let mut hidden = false;
+            for field in &mut unnamed.unnamed {
+                field.attrs.clear();
+                if !matches!(field.vis, Visibility::Public(_)) {
+                    field.vis = Visibility::Inherited;
+                    field.ty = parse_quote!(_);
+                    hidden = true;
+                }
+            }
+            hidden
+        }
+        Fields::Unit => false,
+    }
+}
+
+fn strip_private_named_fields(fields: &mut FieldsNamed) -> bool {
+    let count = fields.named.len();
+    fields.named = std::mem::take(&mut fields.named)
+        .into_iter()
+        .filter(|field| matches!(field.vis, Visibility::Public(_)))
+        .map(|mut field| {
+            field.attrs.clear();
+            field
+        })
+        .collect();
+    fields.named.len() != count
+}
+
+/// Appends `..` to a braced field list, so adding the first hidden field
+/// still changes the signature. Tuple signatures already show `_`.
+fn mark_hidden_fields(signature: String) -> String {
+    if let Some(fields) = signature.strip_suffix("{ }") {
+        format!("{fields}{{ .. }}")
+    } else if let Some(fields) = signature.strip_suffix(" }") {
+        format!("{fields}, .. }}")
+    } else {
+        signature
+    }
+}
+
+/// Prints tokens with rustfmt-like spacing so formatting and comments never
+/// show up as signature changes.
+fn normalize_tokens(tokens: TokenStream) -> String {
+    let mut text = tokens.to_string();
+    for (from, to) in [
+        (" :: ", "::"),
+        (":: ", "::"),
+        (" ::", "::"),
+        ("# [", "#["),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ,", ","),
+        (" ;", ";"),
+        (" :", ":"),
+        ("< ", "<"),
+        (" <", "<"),
+        (" >", ">"),
+        ("& ", "&"),
+    ] {
+        text = text.replace(from, to);
+    }
+    attach_call_parentheses(&text)
+}
+
+/// Drops the space before `(` after a name or generic list, as in
+/// `load<T>(..)` or `Fn(u32)`, but keeps `-> (..)`.
+fn attach_call_parentheses(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(current) = chars.next() {
+        if current == ' ' && chars.peek() == Some(&'(') {
+            let mut previous = normalized.chars().rev();
+            let attaches = match previous.next() {
+                Some('>') => previous.next() != Some('-'),
+                Some(character) => character.is_alphanumeric() || character == '_',
+                None => false,
+            };
+            if attaches {
+                continue;
+            }
+        }
+        normalized.push(current);
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{enum_signature, function_signature, struct_signature, trait_signature};
+
+    #[test]
+    fn normalizes_declarations_independent_of_formatting() {
+        let function: syn::ItemFn = syn::parse_str(
+            "/// Docs.\npub fn load < 'a , T : Clone > ( path : & 'a str ,\n items : Vec < T > ) -> Result < T , String > { todo!() }",
+        )
+        .expect("parse function");
+        let record: syn::ItemStruct = syn::parse_str(
+            "#[derive(Debug)] pub struct Record<T> { pub id: u64, secret: String, #[serde(default)] pub items: Vec<T> }",
+        )
+        .expect("parse struct");
+        let scoped: syn::ItemStruct = syn::parse_str(
+            "#[non_exhaustive] #[derive(Clone)] pub struct Scoped { pub(crate) id: u64, pub(super) name: String }",
+        )
+        .expect("parse scoped struct");
+        let handle: syn::ItemStruct =
+            syn::parse_str("pub struct Handle(pub u32, pub(crate) std::sync::Arc<str>);")
+                .expect("parse tuple");
+        let state: syn::ItemEnum =
+            syn::parse_str("#[non_exhaustive] pub enum State { #[default] Idle, Busy { since: u64 }, Done(String) }")
+                .expect("parse enum");
+        let store: syn::ItemTrait = syn::parse_str(
+            "pub trait Store: Send { type Key; fn get(&self, key: &Self::Key) -> Option<String> { None } }",
+        )
+        .expect("parse trait");
+
+        assert_eq!(
+            function_signature(&function.vis, &function.sig),
+            "pub fn load<'a, T: Clone>(path: &'a str, items: Vec<T>) -> Result<T, String>"
+        );
+        assert_eq!(
+            struct_signature(&record),
+            "pub struct Record<T> { pub id: u64, pub items: Vec<T>, .. }"
+        );
+        assert_eq!(
+            struct_signature(&scoped),
+            "#[non_exhaustive] pub struct Scoped { .. }"
+        );
+        assert_eq!(struct_signature(&handle), "pub struct Handle(pub u32, _);");
+        assert_eq!(
+            enum_signature(&state),
+            "#[non_exhaustive] pub enum State { Idle, Busy { since: u64 }, Done(String) }"
+        );
+        assert_eq!(
+            trait_signature(&store),
+            "pub trait Store: Send { type Key; fn get(&self, key: &Self::Key) -> Option<String>; }"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Syntax-level state facts: enum variants assigned to or constructed into a
+//! field, with the prior-state guard of the enclosing `match` or `if`.
+
+use proc_macro2::{Span, TokenStream};
+use syn::parse::ParseStream;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    BinOp, Block, Expr, ExprAssign, ExprIf, ExprMatch, ExprStruct, Member, Pat, Stmt, Token,
+};
+
+/// One `value.field = Enum::Variant` assignment, or one `field:
+/// Enum::Variant` initializer in a struct literal.
+#[derive(Debug, Clone)]
+pub(super) struct StateAssignmentFact {
+    pub(super) enum_name: String,
+    pub(super) variant: String,
+    pub(super) field: String,
+    /// Variants the same field was matched against to reach the assignment;
+    /// empty when no enclosing `match` arm or `if` condition narrows it.
+    pub(super) guard: Vec<String>,
+    pub(super) construction: bool,
+    pub(super) span: Span,
+}
+
+/// Prior-state narrowing in effect while visiting a branch.
+struct StateGuard {
+    field: String,
+    enum_name: String,
+    variants: Vec<String>,
+}
+
+/// State assignments and constructions in a function body. `self_type`
+/// names the enum that `Self::Variant` refers to inside its own impl.
+pub(super) fn body_state_assignments(
+    block: &Block,
+    self_type: Option<&str>,
+) -> Vec<StateAssignmentFact> {
+    let mut visitor = StateVisitor {
+        self_type,
+        guards: Vec::new(),
+        facts: Vec::new(),
+    };
+    visitor.visit_block(block);
+    visitor.facts
+}
+
+struct StateVisitor<'a> {
+    self_type: Option<&'a str>,
+    guards: Vec<StateGuard>,
+    facts: Vec<StateAssignmentFact>,
+}
+
+impl StateVisitor<'_> {
+    fn record(&mut self, field: &str, value: &Expr, construction: bool) {
+        for (enum_name, variant, span) in self.variant_targets(value) {
+            let guard = if construction {
+                Vec::new()
+            } else {
+                self.guards
+                    .iter()
+                    .rev()
+                    .find(|guard| guard.field == field && guard.enum_name == enum_name)
+                    .map(|guard| guard.variants.clone())
+                    .unwrap_or_default()
+            };
+            self.facts.push(StateAssignmentFact {
+                enum_name,
+                variant,
+                field: field.to_string(),
+                guard,
+                construction,
+                span,
+            });
+        }
+    }
+
+    /// Variants an expression evaluates to, following `if`/`match` branches
+    /// and block tails.
+    fn variant_targets(&self, expr: &Expr) -> Vec<(String, String, Span)> {
+        let single = |path: &syn::Path| {
+            self.path_variant(path)
+                .map(|(enum_name, variant)| vec![(enum_name, variant, expr.span())])
+                .unwrap_or_default()
+        };
+        match expr {
+            Expr::Path(path) if path.qself.is_none() => single(&path.path),
+            Expr::Call(call) => match call.func.as_ref() {
+                Expr::Path(path) if path.qself.is_none() => single(&path.path),
+                _ => Vec::new(),
+            },
+            Expr::Struct(literal) => single(&literal.path),
+            Expr::Paren(paren) => self.variant_targets(&paren.expr),
+            Expr::Group(group) => self.variant_targets(&group.expr),
+            Expr::Block(block) => self.block_targets(&block.block),
+            Expr::If(branch) => {
+                let mut targets = self.block_targets(&branch.then_branch);
+                if let Some((_, else_branch)) = &branch.else_branch {
+                    targets.extend(self.variant_targets(else_branch));
+                }
+                targets
+            }
+            Expr::Match(matched) => matched
+                .arms
+                .iter()
+                .flat_map(|arm| self.variant_targets(&arm.body))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn block_targets(&self, block: &Block) -> Vec<(String, String, Span)> {
+        match block.stmts.last() {
+            Some(Stmt::Expr(expr, None)) => self.variant_targets(expr),
+            _ => Vec::new(),
+        }
+    }
+
+    /// `Enum::Variant` or `Self::Variant` as `(enum, variant)`.
+    fn path_variant(&self, path: &syn::Path) -> Option<(String, String)> {
+        let mut segments = path.segments.iter().rev();
+        let variant = segments.next()?.ident.to_string();
+        let enum_segment = segments.next()?.ident.to_string();
+        if !variant.starts_with(|character: char| character.is_ascii_uppercase()) {
+            return None;
+        }
+        let enum_name = if enum_segment == "Self" {
+            self.self_type?.to_string()
+        } else {
+            enum_segment
+        };
+        Some((enum_name, variant))
+    }
+
+    /// `(enum, variants)` matched by a pattern, or `None` when any arm of
+    /// it also matches other values.
+    fn pattern_variants(&self, pat: &Pat) -> Option<(String, Vec<String>)> {
+        match pat {
+            Pat::Path(path) => self
+                .path_variant(&path.path)
+                .map(|(enum_name, variant)| (enum_name, vec![variant])),
+            Pat::TupleStruct(tuple) => self
+                .path_variant(&tuple.path)
+                .map(|(enum_name, variant)| (enum_name, vec![variant])),
+            Pat::Struct(structure) => self
+                .path_variant(&structure.path)
+                .map(|(enum_name, variant)| (enum_name, vec![variant])),
+            Pat::Reference(reference) => self.pattern_variants(&reference.pat),
+            Pat::Paren(paren) => self.pattern_variants(&paren.pat),
+            Pat::Or(or) => {
+                let mut cases = or.cases.iter().map(|case| self.pattern_variants(case));
+                let (enum_name, mut variants) = cases.next()??;
+                for case in cases {
+                    let (case_enum, case_variants) = case?;
+                    if case_enum != enum_name {
+                        return None;
+                    }
+                    variants.extend(case_variants);
+                }
+                Some((enum_name, variants))
+            }
+            _ => None,
+        }
+    }
+
+    /// Guard from `field == Enum::A`, `let Enum::A = field`,
+    /// `matches!(field, Enum::A | Enum::B)`, or either side of `&&`.
+    fn condition_guard(&self, condition: &Expr) -> Option<StateGuard> {
+        match condition {
+            Expr::Paren(paren) => self.condition_guard(&paren.expr),
+            Expr::Binary(binary) if matches!(binary.op, BinOp::And(_)) => self
+                .condition_guard(&binary.left)
+                .or_else(|| self.condition_guard(&binary.right)),
+            Expr::Binary(binary) if matches!(binary.op, BinOp::Eq(_)) => {
+                let (field, value) = match field_name(&binary.left) {
+                    Some(field) => (field, binary.right.as_ref()),
+                    None => (field_name(&binary.right)?, binary.left.as_ref()),
+                };
+                let Expr::Path(path) = value else {
+                    return None;
+                };
+                let (enum_name, variant) = self.path_variant(&path.path)?;
+                Some(StateGuard {
+                    field,
+                    enum_name,
+                    variants: vec![variant],
+                })
+            }
+            Expr::Let(binding) => {
+                let field = field_name(&binding.expr)?;
+                let (enum_name, variants) = self.pattern_variants(&binding.pat)?;
+                Some(StateGuard {
+                    field,
+                    enum_name,
+                    variants,
+                })
+            }
+            Expr::Macro(invocation) if invocation.mac.path.is_ident("matches") => {
+                let (expr, pat) = invocation
+                    .mac
+                    .parse_body_with(|input: ParseStream<'_>| {
+                        let expr = input.parse::<Expr>()?;
+                        input.parse::<Token![,]>()?;
+                        let pat = Pat::parse_multi_with_leading_vert(input)?;
+                        input.parse::<TokenStream>()?;
+                        Ok((expr, pat))
+                    })
+                    .ok()?;
+                let field = field_name(&expr)?;
+                let (enum_name, variants) = self.pattern_variants(&pat)?;
+                Some(StateGuard {
+                    field,
+                    enum_name,
+                    variants,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for StateVisitor<'_> {
+    fn visit_item(&mut self, _node: &'ast syn::Item) {}
+
+    fn visit_expr_assign(&mut self, node: &'ast ExprAssign) {
+        if let Some(field) = field_name(&node.left) {
+            self.record(&field, &node.right, false);
+        }
+        visit::visit_expr_assign(self, node);
+    }
+
+    fn visit_expr_struct(&mut self, node: &'ast ExprStruct) {
+        for field in &node.fields {
+            if let Member::Named(ident) = &field.member {
+                self.record(&ident.to_string(), &field.expr, true);
+            }
+        }
+        visit::visit_expr_struct(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast ExprMatch) {
+        self.visit_expr(&node.expr);
+        let field = field_name(&node.expr);
+        for arm in &node.arms {
+            let guard = field.as_ref().and_then(|field| {
+                let (enum_name, variants) = self.pattern_variants(&arm.pat)?;
+                Some(StateGuard {
+                    field: field.clone(),
+                    enum_name,
+                    variants,
+                })
+            });
+            let guarded = guard.is_some();
+            self.guards.extend(guard);
+            if let Some((_, arm_guard)) = &arm.guard {
+                self.visit_expr(arm_guard);
+            }
+            self.visit_expr(&arm.body);
+            if guarded {
+                self.guards.pop();
+            }
+        }
+    }
+
+    fn visit_expr_if(&mut self, node: &'ast ExprIf) {
+        self.visit_expr(&node.cond);
+        let guard = self.condition_guard(&node.cond);
+        let guarded = guard.is_some();
+        self.guards.extend(guard);
+        self.visit_block(&node.then_branch);
+        if guarded {
+            self.guards.pop();
+        }
+        if let Some((_, else_branch)) = &node.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+}
+
+/// Name of the field an expression reads, such as `phase` in
+/// `&state.status.phase`.
+fn field_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Field(field) => match &field.member {
+            Member::Named(ident) => Some(ident.to_string()),
+            Member::Unnamed(_) => None,
+        },
+        Expr::Reference(reference) => field_name(&reference.expr),
+        Expr::Paren(paren) => field_name(&paren.expr),
+        Expr::Group(group) => field_name(&group.expr),
+        Expr::Unary(unary) => field_name(&unary.expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::body_state_assignments;
+
+    #[test]
+    fn records_assignments_constructions_and_prior_state_guards() {
+        let block = syn::parse_str(
+            r#"{
+                state.status = Status { phase: Phase::Starting, job: None };
+                match state.status.phase {
+                    Phase::Ready | Phase::Idle => state.status.phase = Phase::Busy,
+                    _ => state.status.phase = Phase::Failed(code),
+                }
+                if matches!(self.phase, Self::Busy) {
+                    self.phase = if done { Self::Ready } else { Self::Busy };
+                }
+                if let Phase::Stopping = state.status.phase {
+                    state.status.phase = Phase::Stopped;
+                }
+                self.phase = Phase::Idle;
+                let helper = || { fn nested(s: &mut S) { s.phase = Phase::Idle; } };
+            }"#,
+        )
+        .expect("parse fixture block");
+
+        let facts = body_state_assignments(&block, Some("Phase"));
+
+        let summary = facts
+            .iter()
+            .map(|fact| {
+                format!(
+                    "{}{}:{}::{}<-[{}]",
+                    if fact.construction { "new " } else { "" },
+                    fact.field,
+                    fact.enum_name,
+                    fact.variant,
+                    fact.guard.join("|")
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                "new phase:Phase::Starting<-[]",
+                "phase:Phase::Busy<-[Ready|Idle]",
+                "phase:Phase::Failed<-[]",
+                "phase:Phase::Ready<-[Busy]",
+                "phase:Phase::Busy<-[Busy]",
+                "phase:Phase::Stopped<-[Stopping]",
+                "phase:Phase::Idle<-[]",
+            ]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! `unsafe` declarations and blocks with their safety comments.
+
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Block, ExprUnsafe, Signature, Stmt, Token};
+
+use crate::graph::unsafe_code::{UnsafeSiteDto, UnsafeSiteKindDto};
+use crate::graph::SourceRangeDto;
+
+/// Source file being scanned, for ranges and comment lookup.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct UnsafeSource<'a> {
+    pub(super) relative_path: &'a str,
+    pub(super) source: &'a str,
+}
+
+/// The `unsafe` keyword of an `unsafe fn`, `unsafe impl`, or `unsafe trait`
+/// declaration, ranged to the end of `item_span`.
+pub(super) fn declaration_site(
+    source: UnsafeSource<'_>,
+    kind: UnsafeSiteKindDto,
+    unsafe_token: &Token![unsafe],
+    item_span: Span,
+) -> UnsafeSiteDto {
+    let line = unsafe_token.span.start().line;
+    UnsafeSiteDto {
+        kind,
+        range: site_range(source.relative_path, unsafe_token.span, item_span),
+        has_safety_comment: has_safety_comment(
+            source.source,
+            &[line],
+            kind != UnsafeSiteKindDto::Impl,
+        ),
+    }
+}
+
+/// The function's own `unsafe fn` declaration, if any, followed by every
+/// `unsafe { }` block in its body, including closures and nested items.
+/// Blocks inside macro invocations are not parsed and are not listed.
+pub(super) fn function_sites(
+    source: UnsafeSource<'_>,
+    signature: &Signature,
+    block: &Block,
+) -> Vec<UnsafeSiteDto> {
+    let mut sites = Vec::new();
+    if let Some(unsafe_token) = &signature.unsafety {
+        let end = block.brace_token.span.close();
+        sites.push(declaration_site(
+            source,
+            UnsafeSiteKindDto::Function,
+            unsafe_token,
+            end,
+        ));
+    }
+
+    let mut visitor = UnsafeBlockVisitor {
+        source,
+        statement_line: None,
+        sites: Vec::new(),
+    };
+    visitor.visit_block(block);
+    sites.extend(visitor.sites);
+    sites
+}
+
+struct UnsafeBlockVisitor<'a> {
+    source: UnsafeSource<'a>,
+    /// First line of the innermost statement, where a comment above
+    /// `let value = unsafe { .. };` is written.
+    statement_line: Option<usize>,
+    sites: Vec<UnsafeSiteDto>,
+}
+
+impl<'ast> Visit<'ast> for UnsafeBlockVisitor<'_> {
+    fn visit_stmt(&mut self, node: &'ast Stmt) {
+        let outer = self.statement_line.replace(node.span().start().line);
+        visit::visit_stmt(self, node);
+        self.statement_line = outer;
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast ExprUnsafe) {
+        let token_line = node.unsafe_token.span.start().line;
+        let anchor_lines = match self.statement_line {
+            Some(line) if line != token_line => vec![line, token_line],
+            _ => vec![token_line],
+        };
+        self.sites.push(UnsafeSiteDto {
+            kind: UnsafeSiteKindDto::Block,
+            range: site_range(
+                self.source.relative_path,
+                node.unsafe_token.span,
+                node.block.brace_token.span.close(),
+            ),
+            has_safety_comment: has_safety_comment(self.source.source, &anchor_lines, false),
+        });
+        visit::visit_expr_unsafe(self, node);
+    }
+}
+
+fn site_range(relative_path: &str, start: Span, end: Span) -> SourceRangeDto {
+    let start = start.start();
+    let end = end.end();
+    SourceRangeDto {
+        path: relative_path.to_string(),
+        start_line: start.line as u32,
+        start_column: start.column as u32 + 1,
+        end_line: end.line as u32,
+        end_column: end.column as u32 + 1,
+    }
+}
+
+/// Looks for `SAFETY:` on each anchor line and in the run of comment and
+/// attribute lines directly above it; a blank line ends the run. With
+/// `allow_safety_section`, a `# Safety` doc heading also counts.
+fn has_safety_comment(source: &str, anchor_lines: &[usize], allow_safety_section: bool) -> bool {
+    let lines = source.lines().collect::<Vec<_>>();
+    let documents_safety = |line: &str| {
+        comment_text(line).is_some_and(|comment| {
+            let comment = comment.to_ascii_lowercase();
+            comment.contains("safety:") || (allow_safety_section && comment.contains("# safety"))
+        })
+    };
+
+    anchor_lines.iter().any(|&anchor| {
+        let Some(index) = anchor.checked_sub(1) else {
+            return false;
+        };
+        if lines.get(index).is_some_and(|line| documents_safety(line)) {
+            return true;
+        }
+        lines[..index.min(lines.len())]
+            .iter()
+            .rev()
+            .take_while(|line| is_comment_or_attribute(line))
+            .any(|line| documents_safety(line))
+    })
+}
+
+/// Comment part of a line: from `//` or `/*`, or the whole line inside a
+/// block comment that starts with `*`.
+fn comment_text(line: &str) -> Option<&str> {
+    if line.trim_start().starts_with('*') {
+        return Some(line);
+    }
+    let start = match (line.find("//"), line.find("/*")) {
+        (Some(line_comment), Some(block_comment)) => line_comment.min(block_comment),
+        (line_comment, block_comment) => line_comment.or(block_comment)?,
+    };
+    Some(&line[start..])
+}
+
+fn is_comment_or_attribute(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("//")
+        || line.starts_with("/*")
+        || line.starts_with('*')
+        || line.starts_with("#[")
+        || line.starts_with("#![")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{declaration_site, function_sites, UnsafeSource};
+    use crate::graph::unsafe_code::UnsafeSiteKindDto;
+    use syn::spanned::Spanned;
+
+    #[test]
+    fn finds_unsafe_sites_and_their_safety_comments() {
+        let source = r#"/// Reads the raw value.
+///
+/// # Safety
+/// `pointer` must be valid.
+pub unsafe fn read(pointer: *const u32) -> u32 {
+    // SAFETY: the caller guarantees `pointer` is valid.
+    let value = unsafe { *pointer };
+    let copy =
+        unsafe { *pointer };
+    // Not documented.
+
+    let _ = || unsafe { *pointer };
+    value + copy
+}
+
+// SAFETY: `Handle` only wraps an index.
+unsafe impl Send for Handle {}
+"#;
+        let file = syn::parse_file(source).expect("parse fixture");
+        let unsafe_source = UnsafeSource {
+            relative_path: "src/raw.rs",
+            source,
+        };
+        let syn::Item::Fn(function) = &file.items[0] else {
+            panic!("expected function");
+        };
+        let syn::Item::Impl(send) = &file.items[1] else {
+            panic!("expected impl");
+        };
+
+        let sites = function_sites(unsafe_source, &function.sig, &function.block);
+        let summary = sites
+            .iter()
+            .map(|site| (site.kind, site.range.start_line, site.has_safety_comment))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (UnsafeSiteKindDto::Function, 5, true),
+                (UnsafeSiteKindDto::Block, 7, true),
+                (UnsafeSiteKindDto::Block, 9, false),
+                (UnsafeSiteKindDto::Block, 12, false),
+            ]
+        );
+        assert_eq!(sites[0].range.end_line, 14);
+        assert_eq!(sites[1].range.start_column, 17);
+
+        let impl_site = declaration_site(
+            unsafe_source,
+            UnsafeSiteKindDto::Impl,
+            send.unsafety.as_ref().expect("unsafe impl"),
+            send.span(),
+        );
+        assert_eq!(impl_site.range.start_line, 17);
+        assert!(impl_site.has_safety_comment);
+    }
+}
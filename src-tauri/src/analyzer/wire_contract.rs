@@ -0,0 +1,596 @@
+//! Serde wire contracts of structs and enums deriving `Serialize` or
+//! `Deserialize`, with container, variant, and field attributes applied.
+
+use std::collections::BTreeSet;
+
+use proc_macro2::TokenStream;
+use syn::meta::ParseNestedMeta;
+use syn::{
+    Attribute, Fields, GenericArgument, Generics, ItemEnum, ItemStruct, LitStr, PathArguments,
+    Token, Type,
+};
+
+use crate::graph::wire_contract::{
+    EnumTaggingDto, WireContractDto, WireFieldDto, WireShapeDto, WireTypeDto, WireVariantDto,
+};
+
+/// Wire contract of a struct, or `None` when it derives neither
+/// `Serialize` nor `Deserialize`.
+pub(super) fn struct_wire_contract(
+    item: &ItemStruct,
+    derives: &[String],
+) -> Option<WireContractDto> {
+    let (serialize, deserialize) = serde_derives(derives)?;
+    let container = SerdeAttributes::parse(&item.attrs);
+    let generics = generic_names(&item.generics);
+    let shape = if container.transparent {
+        let item = item
+            .fields
+            .iter()
+            .find(|field| !SerdeAttributes::parse(&field.attrs).skip)
+            .map(|field| wire_type(&field.ty, &generics))
+            .unwrap_or(WireTypeDto::Null);
+        WireShapeDto::Newtype { item }
+    } else {
+        fields_shape(
+            &item.fields,
+            container.rename_all,
+            container.default,
+            &generics,
+        )
+    };
+
+    Some(WireContractDto {
+        wire_name: container.rename.unwrap_or_else(|| item.ident.to_string()),
+        serialize,
+        deserialize,
+        deny_unknown_fields: container.deny_unknown_fields,
+        shape,
+    })
+}
+
+/// Wire contract of an enum, or `None` when it derives neither
+/// `Serialize` nor `Deserialize`. Skipped variants are omitted.
+pub(super) fn enum_wire_contract(item: &ItemEnum, derives: &[String]) -> Option<WireContractDto> {
+    let (serialize, deserialize) = serde_derives(derives)?;
+    let container = SerdeAttributes::parse(&item.attrs);
+    let generics = generic_names(&item.generics);
+    let tagging = match (container.untagged, container.tag, container.content) {
+        (true, _, _) => EnumTaggingDto::Untagged,
+        (false, Some(tag), Some(content)) => EnumTaggingDto::Adjacent { tag, content },
+        (false, Some(tag), None) => EnumTaggingDto::Internal { tag },
+        (false, None, _) => EnumTaggingDto::External,
+    };
+    let variants = item
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let attributes = SerdeAttributes::parse(&variant.attrs);
+            if attributes.skip {
+                return None;
+            }
+            let name = variant.ident.to_string();
+            let field_rule = attributes.rename_all.or(container.rename_all_fields);
+            Some(WireVariantDto {
+                wire_name: attributes
+                    .rename
+                    .unwrap_or_else(|| container.rename_all.apply_to_variant(&name)),
+                name,
+                shape: fields_shape(&variant.fields, field_rule, false, &generics),
+            })
+        })
+        .collect();
+
+    Some(WireContractDto {
+        wire_name: container.rename.unwrap_or_else(|| item.ident.to_string()),
+        serialize,
+        deserialize,
+        deny_unknown_fields: container.deny_unknown_fields,
+        shape: WireShapeDto::Enum { tagging, variants },
+    })
+}
+
+fn serde_derives(derives: &[String]) -> Option<(bool, bool)> {
+    let derives_trait = |name: &str| {
+        derives
+            .iter()
+            .any(|derive| derive.rsplit("::").next() == Some(name))
+    };
+    let serialize = derives_trait("Serialize");
+    let deserialize = derives_trait("Deserialize");
+    (serialize || deserialize).then_some((serialize, deserialize))
+}
+
+fn generic_names(generics: &Generics) -> BTreeSet<String> {
+    generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect()
+}
+
+fn fields_shape(
+    fields: &Fields,
+    rename_all: RenameRule,
+    container_default: bool,
+    generics: &BTreeSet<String>,
+) -> WireShapeDto {
+    match fields {
+        Fields::Named(named) => WireShapeDto::Object {
+            fields: named
+                .named
+                .iter()
+                .filter_map(|field| {
+                    let attributes = SerdeAttributes::parse(&field.attrs);
+                    if attributes.skip {
+                        return None;
+                    }
+                    let name = field
+                        .ident
+                        .as_ref()
+                        .map(|ident| ident.to_string())
+                        .unwrap_or_default();
+                    let ty = if attributes.with {
+                        WireTypeDto::Any
+                    } else {
+                        wire_type(&field.ty, generics)
+                    };
+                    let required = !(container_default
+                        || attributes.default
+                        || attributes.skip_one_side
+                        || attributes.skip_serializing_if
+                        || matches!(ty, WireTypeDto::Optional { .. }));
+                    Some(WireFieldDto {
+                        wire_name: attributes
+                            .rename
+                            .unwrap_or_else(|| rename_all.apply_to_field(&name)),
+                        name,
+                        ty,
+                        required,
+                        flatten: attributes.flatten,
+                    })
+                })
+                .collect(),
+        },
+        Fields::Unnamed(unnamed) => {
+            let mut items = unnamed
+                .unnamed
+                .iter()
+                .filter(|field| !SerdeAttributes::parse(&field.attrs).skip)
+                .map(|field| wire_type(&field.ty, generics))
+                .collect::<Vec<_>>();
+            if unnamed.unnamed.len() == 1 && items.len() == 1 {
+                WireShapeDto::Newtype {
+                    item: items.remove(0),
+                }
+            } else {
+                WireShapeDto::Tuple { items }
+            }
+        }
+        Fields::Unit => WireShapeDto::Unit,
+    }
+}
+
+/// JSON value serde's default impls produce for a Rust type. Unrecognized
+/// paths are `Named` by their last segment.
+fn wire_type(ty: &Type, generics: &BTreeSet<String>) -> WireTypeDto {
+    match ty {
+        Type::Reference(reference) => wire_type(&reference.elem, generics),
+        Type::Paren(paren) => wire_type(&paren.elem, generics),
+        Type::Group(group) => wire_type(&group.elem, generics),
+        Type::Array(array) => WireTypeDto::Array {
+            items: Box::new(wire_type(&array.elem, generics)),
+        },
+        Type::Slice(slice) => WireTypeDto::Array {
+            items: Box::new(wire_type(&slice.elem, generics)),
+        },
+        Type::Tuple(tuple) if tuple.elems.is_empty() => WireTypeDto::Null,
+        Type::Tuple(tuple) => WireTypeDto::Tuple {
+            items: tuple
+                .elems
+                .iter()
+                .map(|elem| wire_type(elem, generics))
+                .collect(),
+        },
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else {
+                return WireTypeDto::Any;
+            };
+            let name = segment.ident.to_string();
+            if path.path.segments.len() == 1 && generics.contains(&name) {
+                return WireTypeDto::Any;
+            }
+            let arguments = type_arguments(&segment.arguments);
+            let argument = |index: usize| {
+                arguments
+                    .get(index)
+                    .map(|argument| wire_type(argument, generics))
+                    .unwrap_or(WireTypeDto::Any)
+            };
+            match name.as_str() {
+                "bool" => WireTypeDto::Boolean,
+                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+                | "u128" | "usize" | "NonZeroU32" | "NonZeroU64" | "NonZeroUsize" => {
+                    WireTypeDto::Integer
+                }
+                "f32" | "f64" => WireTypeDto::Number,
+                "String" | "str" | "char" | "PathBuf" | "Path" => WireTypeDto::String,
+                "Value" => WireTypeDto::Any,
+                "Option" => WireTypeDto::Optional {
+                    item: Box::new(argument(0)),
+                },
+                "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" | "HashSet" | "BTreeSet"
+                | "IndexSet" => WireTypeDto::Array {
+                    items: Box::new(argument(0)),
+                },
+                "HashMap" | "BTreeMap" | "IndexMap" => WireTypeDto::Map {
+                    values: Box::new(argument(1)),
+                },
+                "Box" | "Rc" | "Arc" | "Cow" => argument(0),
+                _ => WireTypeDto::Named { name },
+            }
+        }
+        _ => WireTypeDto::Any,
+    }
+}
+
+fn type_arguments(arguments: &PathArguments) -> Vec<&Type> {
+    let PathArguments::AngleBracketed(arguments) = arguments else {
+        return Vec::new();
+    };
+    arguments
+        .args
+        .iter()
+        .filter_map(|argument| match argument {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The `#[serde(..)]` keys that change the wire shape. Unknown keys are
+/// skipped, and `rename(serialize = ..)` wins over `deserialize = ..`.
+#[derive(Debug, Default)]
+struct SerdeAttributes {
+    rename: Option<String>,
+    rename_all: RenameRule,
+    rename_all_fields: RenameRule,
+    tag: Option<String>,
+    content: Option<String>,
+    untagged: bool,
+    transparent: bool,
+    deny_unknown_fields: bool,
+    default: bool,
+    skip: bool,
+    /// `skip_serializing` or `skip_deserializing`: present on one side only.
+    skip_one_side: bool,
+    skip_serializing_if: bool,
+    flatten: bool,
+    /// `with`, `serialize_with`, or `deserialize_with`: custom wire shape.
+    with: bool,
+}
+
+impl SerdeAttributes {
+    fn parse(attributes: &[Attribute]) -> Self {
+        let mut parsed = Self::default();
+        for attribute in attributes {
+            if attribute.path().is_ident("serde") {
+                // Malformed attributes fail the real build; keep what parsed.
+                let _ = attribute.parse_nested_meta(|meta| parsed.apply(meta));
+            }
+        }
+        parsed
+    }
+
+    fn apply(&mut self, meta: ParseNestedMeta<'_>) -> syn::Result<()> {
+        let Some(key) = meta.path.get_ident().map(ToString::to_string) else {
+            return skip_meta_value(&meta);
+        };
+        match key.as_str() {
+            "rename" => self.rename = sided_string(&meta)?,
+            "rename_all" => {
+                self.rename_all = sided_string(&meta)?
+                    .and_then(|rule| RenameRule::parse(&rule))
+                    .unwrap_or_default();
+            }
+            "rename_all_fields" => {
+                self.rename_all_fields = sided_string(&meta)?
+                    .and_then(|rule| RenameRule::parse(&rule))
+                    .unwrap_or_default();
+            }
+            "tag" => self.tag = Some(meta.value()?.parse::<LitStr>()?.value()),
+            "content" => self.content = Some(meta.value()?.parse::<LitStr>()?.value()),
+            "untagged" => self.untagged = true,
+            "transparent" => self.transparent = true,
+            "deny_unknown_fields" => self.deny_unknown_fields = true,
+            "default" => {
+                self.default = true;
+                skip_meta_value(&meta)?;
+            }
+            "skip" => self.skip = true,
+            "skip_serializing" | "skip_deserializing" => self.skip_one_side = true,
+            "skip_serializing_if" => {
+                self.skip_serializing_if = true;
+                skip_meta_value(&meta)?;
+            }
+            "flatten" => self.flatten = true,
+            "with" | "serialize_with" | "deserialize_with" => {
+                self.with = true;
+                skip_meta_value(&meta)?;
+            }
+            _ => skip_meta_value(&meta)?,
+        }
+        Ok(())
+    }
+}
+
+/// `key = "value"` or `key(serialize = "value", deserialize = "..")`.
+fn sided_string(meta: &ParseNestedMeta<'_>) -> syn::Result<Option<String>> {
+    if meta.input.peek(Token![=]) {
+        return Ok(Some(meta.value()?.parse::<LitStr>()?.value()));
+    }
+    let mut serialize = None;
+    let mut deserialize = None;
+    meta.parse_nested_meta(|side| {
+        let value = side.value()?.parse::<LitStr>()?.value();
+        if side.path.is_ident("serialize") {
+            serialize = Some(value);
+        } else if side.path.is_ident("deserialize") {
+            deserialize = Some(value);
+        }
+        Ok(())
+    })?;
+    Ok(serialize.or(deserialize))
+}
+
+fn skip_meta_value(meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse::<TokenStream>()?;
+    }
+    Ok(())
+}
+
+/// Serde's `rename_all` case conventions. Fields are assumed to be written in
+/// snake_case and variants in PascalCase, as serde does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum RenameRule {
+    #[default]
+    None,
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    fn or(self, fallback: Self) -> Self {
+        if self == Self::None {
+            fallback
+        } else {
+            self
+        }
+    }
+
+    fn apply_to_variant(self, variant: &str) -> String {
+        let variant = variant.trim_start_matches("r#");
+        match self {
+            Self::None | Self::Pascal => variant.to_string(),
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Camel => {
+                let mut chars = variant.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+                let mut snake = String::new();
+                for (index, character) in variant.char_indices() {
+                    if index > 0 && character.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(character.to_ascii_lowercase());
+                }
+                self.apply_to_field(&snake)
+            }
+        }
+    }
+
+    fn apply_to_field(self, field: &str) -> String {
+        let field = field.trim_start_matches("r#");
+        match self {
+            Self::None | Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+            Self::Pascal | Self::Camel => {
+                let mut renamed = String::new();
+                let mut capitalize = self == Self::Pascal;
+                for character in field.chars() {
+                    if character == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        renamed.push(character.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        renamed.push(character);
+                    }
+                }
+                renamed
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{enum_wire_contract, struct_wire_contract, RenameRule};
+    use crate::graph::wire_contract::{
+        EnumTaggingDto, WireFieldDto, WireShapeDto, WireTypeDto, WireVariantDto,
+    };
+
+    fn derives(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn applies_container_and_field_attributes_to_structs() {
+        let item = syn::parse_str(
+            r#"
+            #[derive(Serialize, Deserialize)]
+            #[serde(rename_all = "camelCase", deny_unknown_fields)]
+            struct GraphNodeDto {
+                node_id: String,
+                #[serde(rename = "type")]
+                kind: GraphNodeKind,
+                source_range: Option<SourceRangeDto>,
+                #[serde(default, skip_serializing_if = "Vec::is_empty")]
+                public_paths: Vec<String>,
+                #[serde(skip)]
+                cache: Arc<Mutex<Cache>>,
+                #[serde(flatten)]
+                extra: BTreeMap<String, serde_json::Value>,
+                #[serde(with = "humantime_serde")]
+                timeout: Duration,
+            }
+            "#,
+        )
+        .expect("parse struct fixture");
+
+        let contract = struct_wire_contract(&item, &derives(&["Serialize", "serde::Deserialize"]))
+            .expect("serde struct has a contract");
+
+        assert!(contract.serialize && contract.deserialize && contract.deny_unknown_fields);
+        let field = |name: &str, wire_name: &str, ty, required, flatten| WireFieldDto {
+            name: name.to_string(),
+            wire_name: wire_name.to_string(),
+            ty,
+            required,
+            flatten,
+        };
+        assert_eq!(
+            contract.shape,
+            WireShapeDto::Object {
+                fields: vec![
+                    field("node_id", "nodeId", WireTypeDto::String, true, false),
+                    field(
+                        "kind",
+                        "type",
+                        WireTypeDto::Named {
+                            name: "GraphNodeKind".to_string()
+                        },
+                        true,
+                        false
+                    ),
+                    field(
+                        "source_range",
+                        "sourceRange",
+                        WireTypeDto::Optional {
+                            item: Box::new(WireTypeDto::Named {
+                                name: "SourceRangeDto".to_string()
+                            })
+                        },
+                        false,
+                        false
+                    ),
+                    field(
+                        "public_paths",
+                        "publicPaths",
+                        WireTypeDto::Array {
+                            items: Box::new(WireTypeDto::String)
+                        },
+                        false,
+                        false
+                    ),
+                    field(
+                        "extra",
+                        "extra",
+                        WireTypeDto::Map {
+                            values: Box::new(WireTypeDto::Any)
+                        },
+                        true,
+                        true
+                    ),
+                    field("timeout", "timeout", WireTypeDto::Any, true, false),
+                ]
+            }
+        );
+        assert_eq!(struct_wire_contract(&item, &derives(&["Debug"])), None);
+    }
+
+    #[test]
+    fn applies_tagging_and_variant_renames_to_enums() {
+        let item = syn::parse_str(
+            r#"
+            #[derive(Serialize)]
+            #[serde(tag = "operation", rename_all = "snake_case", rename_all_fields = "camelCase")]
+            enum GraphQueryDto {
+                PathBetween { source_id: String, max_depth: Option<u32> },
+                #[serde(rename = "neighbours")]
+                Neighbors(NeighborQuery),
+                #[serde(skip)]
+                Internal,
+                Unit,
+            }
+            "#,
+        )
+        .expect("parse enum fixture");
+
+        let contract =
+            enum_wire_contract(&item, &derives(&["Serialize"])).expect("serde enum has a contract");
+
+        assert!(contract.serialize && !contract.deserialize);
+        let WireShapeDto::Enum { tagging, variants } = contract.shape else {
+            panic!("enum contract has an enum shape");
+        };
+        assert_eq!(
+            tagging,
+            EnumTaggingDto::Internal {
+                tag: "operation".to_string()
+            }
+        );
+        assert_eq!(
+            variants
+                .iter()
+                .map(|variant| variant.wire_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["path_between", "neighbours", "unit"]
+        );
+        let WireVariantDto {
+            shape: WireShapeDto::Object { fields },
+            ..
+        } = &variants[0]
+        else {
+            panic!("struct variant has an object shape");
+        };
+        assert_eq!(fields[0].wire_name, "sourceId");
+        assert!(!fields[1].required);
+        assert_eq!(
+            RenameRule::ScreamingKebab.apply_to_variant("PathBetween"),
+            "PATH-BETWEEN"
+        );
+        assert_eq!(RenameRule::Pascal.apply_to_field("r#type_name"), "TypeName");
+    }
+}
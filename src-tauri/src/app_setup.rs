@@ -31,6 +31,24 @@ pub fn run_app() -> AppStartupResult<()> {
             commands::load_file_relation_graph,
             commands::get_graph_snapshot,
             commands::get_source_snippet,
+            commands::get_dependency_cycles,
+            commands::get_graph_metrics,
+            commands::get_complexity_offenders,
+            commands::get_dead_code,
+            commands::query_graph,
+            commands::search_symbols,
+            commands::get_public_api,
+            commands::get_public_api_diff,
+            commands::get_unsafe_inventory,
+            commands::get_concurrency_topology,
+            commands::get_error_flow,
+            commands::get_wire_contracts,
+            commands::get_schema_bundle,
+            commands::get_state_machines,
+            commands::import_coverage,
+            commands::get_uncovered_public_functions,
+            commands::get_hotspots,
+            commands::get_cross_owner_dependencies,
             commands::set_source_repo_path
         ])
         .build(tauri::generate_context!())?
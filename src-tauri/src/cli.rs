@@ -0,0 +1,757 @@
+//! Headless command-line entrypoints for CI and scripted analysis.
+
+use std::io::Write;
+
+use crate::analyzer::RustGraphExtractor;
+use crate::graph::api_diff::diff_revisions;
+use crate::graph::churn::{
+    file_churn, hotspot_report, ChurnWindowDto, HotspotMeasureDto, DEFAULT_HOTSPOT_LIMIT,
+};
+use crate::graph::coverage::{attach_coverage, uncovered_public_functions, LineCoverage};
+use crate::graph::metrics::attach_complexity_rollups;
+use crate::graph::query::GraphQueryDto;
+use crate::graph::rules::{ArchitectureRuleSetDto, ARCHITECTURE_RULES_PATH};
+use crate::graph::state_machine::state_machines;
+use crate::graph::wire_contract::schema_bundle;
+use crate::source::git::commit_history;
+use crate::source::ValidatedRepoPath;
+
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_VIOLATIONS: i32 = 1;
+const EXIT_FAILURE: i32 = 2;
+const USAGE: &str = concat!(
+    "usage: whip-docs check <cargo-repo-path>\n",
+    "       whip-docs query <cargo-repo-path> <query-json>\n",
+    "       whip-docs api-diff <cargo-repo-path> <base-rev> [head-rev]\n",
+    "       whip-docs schemas <cargo-repo-path>\n",
+    "       whip-docs state-diagrams <cargo-repo-path> [mermaid|dot]\n",
+    "       whip-docs coverage <cargo-repo-path> <coverage-report-path>\n",
+    "       whip-docs hotspots <cargo-repo-path> [since-days]"
+);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CliCommand {
+    CheckRules {
+        repo_path: String,
+    },
+    Query {
+        repo_path: String,
+        query: String,
+    },
+    ApiDiff {
+        repo_path: String,
+        base_revision: String,
+        head_revision: Option<String>,
+    },
+    Schemas {
+        repo_path: String,
+    },
+    StateDiagrams {
+        repo_path: String,
+        format: DiagramFormat,
+    },
+    Coverage {
+        repo_path: String,
+        report_path: String,
+    },
+    Hotspots {
+        repo_path: String,
+        since_days: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiagramFormat {
+    Mermaid,
+    Dot,
+}
+
+/// Runs a headless subcommand when the arguments name one. Returns `None` so
+/// the caller starts the desktop app for every other argument list.
+pub fn run_from_args(args: impl IntoIterator<Item = String>) -> Option<i32> {
+    let command = match parse_args(args)? {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{message}");
+            return Some(EXIT_FAILURE);
+        }
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    let exit_code = match command {
+        CliCommand::CheckRules { repo_path } => check_rules(&repo_path, &mut stdout),
+        CliCommand::Query { repo_path, query } => run_query(&repo_path, &query, &mut stdout),
+        CliCommand::ApiDiff {
+            repo_path,
+            base_revision,
+            head_revision,
+        } => api_diff(
+            &repo_path,
+            &base_revision,
+            head_revision.as_deref(),
+            &mut stdout,
+        ),
+        CliCommand::Schemas { repo_path } => schemas(&repo_path, &mut stdout),
+        CliCommand::StateDiagrams { repo_path, format } => {
+            state_diagrams(&repo_path, format, &mut stdout)
+        }
+        CliCommand::Coverage {
+            repo_path,
+            report_path,
+        } => coverage(&repo_path, &report_path, &mut stdout),
+        CliCommand::Hotspots {
+            repo_path,
+            since_days,
+        } => hotspots(&repo_path, since_days, &mut stdout),
+    };
+    Some(exit_code)
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Option<Result<CliCommand, String>> {
+    let mut args = args.into_iter();
+    let subcommand = args.next()?;
+
+    match subcommand.as_str() {
+        "check" => Some(match (args.next(), args.next()) {
+            (Some(repo_path), None) => Ok(CliCommand::CheckRules { repo_path }),
+            _ => Err(USAGE.to_string()),
+        }),
+        "query" => Some(match (args.next(), args.next(), args.next()) {
+            (Some(repo_path), Some(query), None) => Ok(CliCommand::Query { repo_path, query }),
+            _ => Err(USAGE.to_string()),
+        }),
+        "api-diff" => Some(match (args.next(), args.next(), args.next(), args.next()) {
+            (Some(repo_path), Some(base_revision), head_revision, None) => {
+                Ok(CliCommand::ApiDiff {
+                    repo_path,
+                    base_revision,
+                    head_revision,
+                })
+            }
+            _ => Err(USAGE.to_string()),
+        }),
+        "schemas" => Some(match (args.next(), args.next()) {
+            (Some(repo_path), None) => Ok(CliCommand::Schemas { repo_path }),
+            _ => Err(USAGE.to_string()),
+        }),
+        "state-diagrams" => Some(match (args.next(), args.next(), args.next()) {
+            (Some(repo_path), format, None) => match format.as_deref() {
+                None | Some("mermaid") => Ok(CliCommand::StateDiagrams {
+                    repo_path,
+                    format: DiagramFormat::Mermaid,
+                }),
+                Some("dot") => Ok(CliCommand::StateDiagrams {
+                    repo_path,
+                    format: DiagramFormat::Dot,
+                }),
+                Some(_) => Err(USAGE.to_string()),
+            },
+            _ => Err(USAGE.to_string()),
+        }),
+        "coverage" => Some(match (args.next(), args.next(), args.next()) {
+            (Some(repo_path), Some(report_path), None) => Ok(CliCommand::Coverage {
+                repo_path,
+                report_path,
+            }),
+            _ => Err(USAGE.to_string()),
+        }),
+        "hotspots" => Some(match (args.next(), args.next(), args.next()) {
+            (Some(repo_path), since_days, None) => match since_days.map(|days| days.parse()) {
+                None => Ok(CliCommand::Hotspots {
+                    repo_path,
+                    since_days: None,
+                }),
+                Some(Ok(days)) => Ok(CliCommand::Hotspots {
+                    repo_path,
+                    since_days: Some(days),
+                }),
+                Some(Err(_)) => Err(USAGE.to_string()),
+            },
+            _ => Err(USAGE.to_string()),
+        }),
+        _ => None,
+    }
+}
+
+fn check_rules(repo_path: &str, output: &mut impl Write) -> i32 {
+    match architecture_violation_lines(repo_path) {
+        Ok(lines) => {
+            for line in &lines {
+                let _ = writeln!(output, "{line}");
+            }
+            let _ = writeln!(output, "{} architecture rule violation(s)", lines.len());
+
+            if lines.is_empty() {
+                EXIT_SUCCESS
+            } else {
+                EXIT_VIOLATIONS
+            }
+        }
+        Err(message) => {
+            eprintln!("whip-docs check failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn architecture_violation_lines(repo_path: &str) -> Result<Vec<String>, String> {
+    let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())?;
+    let rule_set = ArchitectureRuleSetDto::load_from_repo(&source_repo)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| format!("no architecture rules found at {ARCHITECTURE_RULES_PATH}"))?;
+    let snapshot = RustGraphExtractor
+        .extract(&source_repo)
+        .map_err(|error| error.to_string())?;
+
+    Ok(rule_set
+        .evaluate(&snapshot)
+        .into_iter()
+        .map(|diagnostic| match diagnostic.evidence {
+            Some(range) => format!(
+                "{}:{}:{}: {}",
+                range.path, range.start_line, range.start_column, diagnostic.message
+            ),
+            None => diagnostic.message,
+        })
+        .collect())
+}
+
+/// Runs one graph query against a fresh extraction and prints the reduced
+/// snapshot as JSON.
+fn run_query(repo_path: &str, query: &str, output: &mut impl Write) -> i32 {
+    match query_result_json(repo_path, query) {
+        Ok(json) => {
+            let _ = writeln!(output, "{json}");
+            EXIT_SUCCESS
+        }
+        Err(message) => {
+            eprintln!("whip-docs query failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn query_result_json(repo_path: &str, query: &str) -> Result<String, String> {
+    let query: GraphQueryDto =
+        serde_json::from_str(query).map_err(|error| format!("invalid query: {error}"))?;
+    let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())?;
+    let snapshot = RustGraphExtractor
+        .extract(&source_repo)
+        .map_err(|error| error.to_string())?;
+    let result = query
+        .execute(&snapshot)
+        .map_err(|error| error.to_string())?;
+
+    serde_json::to_string_pretty(&result).map_err(|error| error.to_string())
+}
+
+/// Prints the public API changes between two revisions as Markdown. The
+/// head defaults to the working tree.
+fn api_diff(
+    repo_path: &str,
+    base_revision: &str,
+    head_revision: Option<&str>,
+    output: &mut impl Write,
+) -> i32 {
+    let diff = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())
+        .and_then(|source_repo| {
+            diff_revisions(&source_repo, base_revision, head_revision)
+                .map_err(|error| error.to_string())
+        });
+    match diff {
+        Ok(diff) => {
+            let _ = write!(output, "{}", diff.to_markdown());
+            EXIT_SUCCESS
+        }
+        Err(message) => {
+            eprintln!("whip-docs api-diff failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// Prints the JSON Schema bundle of every serde wire contract.
+fn schemas(repo_path: &str, output: &mut impl Write) -> i32 {
+    let bundle = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())
+        .and_then(|source_repo| {
+            RustGraphExtractor
+                .extract(&source_repo)
+                .map_err(|error| error.to_string())
+        })
+        .and_then(|snapshot| {
+            serde_json::to_string_pretty(&schema_bundle(&snapshot))
+                .map_err(|error| error.to_string())
+        });
+    match bundle {
+        Ok(json) => {
+            let _ = writeln!(output, "{json}");
+            EXIT_SUCCESS
+        }
+        Err(message) => {
+            eprintln!("whip-docs schemas failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// Prints every enum state machine: Markdown with one Mermaid block per
+/// enum, or one Graphviz `digraph` per enum.
+fn state_diagrams(repo_path: &str, format: DiagramFormat, output: &mut impl Write) -> i32 {
+    let report = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())
+        .and_then(|source_repo| {
+            RustGraphExtractor
+                .extract(&source_repo)
+                .map_err(|error| error.to_string())
+        })
+        .map(|snapshot| state_machines(&snapshot));
+    match report {
+        Ok(report) => {
+            for machine in &report.machines {
+                let _ = match format {
+                    DiagramFormat::Mermaid => writeln!(
+                        output,
+                        "## `{}`\n\n```mermaid\n{}```\n",
+                        machine.enum_name, machine.mermaid
+                    ),
+                    DiagramFormat::Dot => write!(output, "{}", machine.dot),
+                };
+            }
+            EXIT_SUCCESS
+        }
+        Err(message) => {
+            eprintln!("whip-docs state-diagrams failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// Overlays a coverage report on a fresh extraction and prints every public
+/// function or method none of whose instrumented lines ran.
+fn coverage(repo_path: &str, report_path: &str, output: &mut impl Write) -> i32 {
+    match uncovered_function_lines(repo_path, report_path) {
+        Ok(lines) => {
+            for line in &lines {
+                let _ = writeln!(output, "{line}");
+            }
+            let _ = writeln!(output, "{} uncovered public function(s)", lines.len());
+            EXIT_SUCCESS
+        }
+        Err(message) => {
+            eprintln!("whip-docs coverage failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn uncovered_function_lines(repo_path: &str, report_path: &str) -> Result<Vec<String>, String> {
+    let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())?;
+    let (format, line_coverage) = LineCoverage::load(&source_repo, report_path.as_ref())
+        .map_err(|error| error.to_string())?;
+    let mut snapshot = RustGraphExtractor
+        .extract(&source_repo)
+        .map_err(|error| error.to_string())?;
+    attach_coverage(&mut snapshot, &line_coverage, report_path, format);
+
+    Ok(uncovered_public_functions(&snapshot)
+        .functions
+        .into_iter()
+        .map(|function| {
+            format!(
+                "{}:{}: {} ({} line(s))",
+                function.range.path,
+                function.range.start_line,
+                function.public_paths.join(", "),
+                function.total_lines
+            )
+        })
+        .collect())
+}
+
+/// Prints the highest-ranked churn hotspots, optionally over the last
+/// `since_days` days of history.
+fn hotspots(repo_path: &str, since_days: Option<u32>, output: &mut impl Write) -> i32 {
+    match hotspot_lines(repo_path, since_days) {
+        Ok(lines) => {
+            for line in &lines {
+                let _ = writeln!(output, "{line}");
+            }
+            EXIT_SUCCESS
+        }
+        Err(message) => {
+            eprintln!("whip-docs hotspots failed: {message}");
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn hotspot_lines(repo_path: &str, since_days: Option<u32>) -> Result<Vec<String>, String> {
+    let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(repo_path)
+        .map_err(|error| error.to_string())?;
+    let commits = commit_history(&source_repo, since_days).map_err(|error| error.to_string())?;
+    let mut snapshot = RustGraphExtractor
+        .extract(&source_repo)
+        .map_err(|error| error.to_string())?;
+    attach_complexity_rollups(&mut snapshot);
+    let report = hotspot_report(
+        &source_repo,
+        ChurnWindowDto { since_days },
+        file_churn(&commits),
+        Some(&snapshot),
+        DEFAULT_HOTSPOT_LIMIT,
+    );
+
+    Ok(report
+        .hotspots
+        .into_iter()
+        .map(|hotspot| {
+            let measure = match hotspot.measure {
+                HotspotMeasureDto::CyclomaticComplexity => "complexity",
+                HotspotMeasureDto::LinesOfCode => "lines",
+            };
+            format!(
+                "{}: {} ({} commit(s) x {} {measure}, {} author(s))",
+                hotspot.path,
+                hotspot.score,
+                hotspot.churn.commit_count,
+                hotspot.measure_value,
+                hotspot.churn.author_count
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        api_diff, check_rules, coverage, hotspots, parse_args, run_query, schemas, state_diagrams,
+        CliCommand, DiagramFormat, EXIT_FAILURE, EXIT_SUCCESS, EXIT_VIOLATIONS,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "whip-docs-cli-{name}-{}-{timestamp}",
+            std::process::id()
+        ))
+    }
+
+    fn fixture_repo(name: &str, rules: Option<&str>) -> PathBuf {
+        let repo = unique_temp_dir(name);
+        fs::create_dir_all(repo.join("src/graph")).expect("create fixture src");
+        fs::write(
+            repo.join("Cargo.toml"),
+            "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .expect("write manifest");
+        fs::write(repo.join("src/lib.rs"), "mod commands;\nmod graph;\n").expect("write lib");
+        fs::write(repo.join("src/commands.rs"), "pub fn handle() {}\n").expect("write commands");
+        fs::write(
+            repo.join("src/graph/mod.rs"),
+            "use crate::commands::handle;\npub fn build() { handle(); }\n",
+        )
+        .expect("write graph");
+        if let Some(rules) = rules {
+            fs::create_dir_all(repo.join(".whip-docs")).expect("create rules dir");
+            fs::write(repo.join(".whip-docs/architecture-rules.json"), rules).expect("write rules");
+        }
+        repo
+    }
+
+    #[test]
+    fn unrecognized_arguments_start_the_desktop_app() {
+        assert_eq!(parse_args(Vec::<String>::new()), None);
+        assert_eq!(parse_args(vec!["-psn_0_1234".to_string()]), None);
+        assert_eq!(
+            parse_args(vec!["check".to_string(), "/repo".to_string()]),
+            Some(Ok(CliCommand::CheckRules {
+                repo_path: "/repo".to_string()
+            }))
+        );
+        assert!(matches!(
+            parse_args(vec!["check".to_string()]),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn check_exits_with_violation_status_and_prints_evidence() {
+        let repo = fixture_repo(
+            "violations",
+            Some(
+                r#"{ "schemaVersion": 1, "rules": [
+                    { "id": "graph-independent", "kind": "forbid", "from": ["graph"], "to": ["commands"] }
+                ] }"#,
+            ),
+        );
+        let mut output = Vec::new();
+
+        let exit_code = check_rules(&repo.to_string_lossy(), &mut output);
+        let output = String::from_utf8(output).expect("utf8 output");
+
+        assert_eq!(exit_code, EXIT_VIOLATIONS);
+        assert!(output.contains("src/graph/mod.rs:1:"));
+        assert!(output.contains("'graph-independent'"));
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn check_passes_clean_repos_and_fails_without_rules() {
+        let clean = fixture_repo(
+            "clean",
+            Some(
+                r#"{ "schemaVersion": 1, "rules": [
+                    { "id": "commands-independent", "kind": "forbid", "from": ["commands"], "to": ["graph"] }
+                ] }"#,
+            ),
+        );
+        let missing = fixture_repo("missing-rules", None);
+
+        assert_eq!(
+            check_rules(&clean.to_string_lossy(), &mut Vec::new()),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            check_rules(&missing.to_string_lossy(), &mut Vec::new()),
+            EXIT_FAILURE
+        );
+
+        fs::remove_dir_all(clean).expect("cleanup clean repo");
+        fs::remove_dir_all(missing).expect("cleanup missing repo");
+    }
+
+    #[test]
+    fn query_prints_the_reduced_snapshot_as_json() {
+        let repo = fixture_repo("query", None);
+        let mut output = Vec::new();
+
+        let exit_code = run_query(
+            &repo.to_string_lossy(),
+            r#"{ "operation": "filter", "filter": { "kinds": ["function"], "label": "build" } }"#,
+            &mut output,
+        );
+        let result: serde_json::Value =
+            serde_json::from_slice(&output).expect("query output is json");
+
+        assert_eq!(exit_code, EXIT_SUCCESS);
+        assert!(result["snapshot"]["nodes"]
+            .as_array()
+            .expect("node array")
+            .iter()
+            .all(|node| node["label"] == "build"));
+        assert_eq!(
+            run_query(&repo.to_string_lossy(), "{}", &mut Vec::new()),
+            EXIT_FAILURE
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup query repo");
+    }
+
+    #[test]
+    fn schemas_prints_a_bundle_of_serde_contracts() {
+        let repo = fixture_repo("schemas", None);
+        fs::write(
+            repo.join("src/commands.rs"),
+            concat!(
+                "#[derive(serde::Serialize)]\n",
+                "#[serde(rename_all = \"camelCase\")]\n",
+                "pub struct HandleDto { node_id: String, retries: Option<u32> }\n",
+            ),
+        )
+        .expect("write commands");
+        let mut output = Vec::new();
+
+        let exit_code = schemas(&repo.to_string_lossy(), &mut output);
+        let bundle: serde_json::Value =
+            serde_json::from_slice(&output).expect("schemas output is json");
+
+        assert_eq!(exit_code, EXIT_SUCCESS);
+        assert_eq!(
+            bundle["$defs"]["HandleDto"]["required"],
+            serde_json::json!(["nodeId"])
+        );
+        assert_eq!(
+            bundle["$defs"]["HandleDto"]["properties"]["retries"]["anyOf"][1],
+            serde_json::json!({ "type": "null" })
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup schemas repo");
+    }
+
+    #[test]
+    fn state_diagrams_print_mermaid_markdown_or_dot() {
+        let repo = fixture_repo("state-diagrams", None);
+        fs::write(
+            repo.join("src/commands.rs"),
+            concat!(
+                "pub enum Phase { Idle, Busy }\n",
+                "pub struct Job { phase: Phase }\n",
+                "pub fn handle(job: &mut Job) { job.phase = Phase::Busy; }\n",
+            ),
+        )
+        .expect("write commands");
+        let mut mermaid = Vec::new();
+        let mut dot = Vec::new();
+
+        let mermaid_exit = state_diagrams(
+            &repo.to_string_lossy(),
+            DiagramFormat::Mermaid,
+            &mut mermaid,
+        );
+        let dot_exit = state_diagrams(&repo.to_string_lossy(), DiagramFormat::Dot, &mut dot);
+
+        assert_eq!((mermaid_exit, dot_exit), (EXIT_SUCCESS, EXIT_SUCCESS));
+        assert!(String::from_utf8_lossy(&mermaid).contains("```mermaid\nstateDiagram-v2\n"));
+        assert!(String::from_utf8_lossy(&dot).contains("\"__any\" -> \"Busy\" [label=\"handle\"];"));
+        assert!(matches!(
+            parse_args(vec![
+                "state-diagrams".to_string(),
+                "/repo".to_string(),
+                "svg".to_string()
+            ]),
+            Some(Err(_))
+        ));
+
+        fs::remove_dir_all(repo).expect("cleanup state diagram repo");
+    }
+
+    #[test]
+    fn coverage_lists_public_functions_without_hit_lines() {
+        let repo = fixture_repo("coverage", None);
+        fs::write(repo.join("src/lib.rs"), "pub mod commands;\nmod graph;\n").expect("write lib");
+        fs::write(
+            repo.join("lcov.info"),
+            "SF:src/commands.rs\nDA:1,0\nend_of_record\nSF:src/graph/mod.rs\nDA:2,0\nend_of_record\n",
+        )
+        .expect("write lcov report");
+        let mut output = Vec::new();
+
+        let exit_code = coverage(&repo.to_string_lossy(), "lcov.info", &mut output);
+        let output = String::from_utf8(output).expect("utf8 output");
+
+        assert_eq!(exit_code, EXIT_SUCCESS);
+        assert_eq!(
+            output,
+            "src/commands.rs:1: fixture::commands::handle (1 line(s))\n1 uncovered public function(s)\n"
+        );
+        assert_eq!(
+            coverage(&repo.to_string_lossy(), "../lcov.info", &mut Vec::new()),
+            EXIT_FAILURE
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup coverage repo");
+    }
+
+    fn git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args([
+                "-c",
+                "user.name=Fixture",
+                "-c",
+                "user.email=fixture@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn api_diff_prints_markdown_against_the_working_tree() {
+        let repo = fixture_repo("api-diff", None);
+        git(&repo, &["init", "--quiet"]);
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "base"]);
+        fs::write(
+            repo.join("src/lib.rs"),
+            "mod commands;\nmod graph;\npub fn version() -> u32 { 2 }\n",
+        )
+        .expect("add public function");
+        let mut output = Vec::new();
+
+        let exit_code = api_diff(&repo.to_string_lossy(), "HEAD", None, &mut output);
+        let output = String::from_utf8(output).expect("utf8 output");
+
+        assert_eq!(exit_code, EXIT_SUCCESS);
+        assert!(output.contains("Suggested semver impact: **minor**"));
+        assert!(output.contains("- `fixture::version` (function)"));
+        assert_eq!(
+            parse_args(vec![
+                "api-diff".to_string(),
+                "/repo".to_string(),
+                "v1".to_string()
+            ]),
+            Some(Ok(CliCommand::ApiDiff {
+                repo_path: "/repo".to_string(),
+                base_revision: "v1".to_string(),
+                head_revision: None,
+            }))
+        );
+        assert_eq!(
+            api_diff(
+                &repo.to_string_lossy(),
+                "missing-tag",
+                None,
+                &mut Vec::new()
+            ),
+            EXIT_FAILURE
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup api diff repo");
+    }
+
+    #[test]
+    fn hotspots_rank_files_by_commits_times_complexity() {
+        let repo = fixture_repo("hotspots", None);
+        git(&repo, &["init", "--quiet"]);
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "base"]);
+        fs::write(
+            repo.join("src/graph/mod.rs"),
+            "use crate::commands::handle;\npub fn build() { if true { handle(); } }\n",
+        )
+        .expect("edit graph");
+        git(&repo, &["commit", "--quiet", "-am", "branch"]);
+        fs::write(
+            repo.join("src/graph/mod.rs"),
+            "use crate::commands::handle;\npub fn build() { if false { handle(); } }\n",
+        )
+        .expect("edit graph again");
+        git(&repo, &["commit", "--quiet", "-am", "flip"]);
+        let mut output = Vec::new();
+
+        let exit_code = hotspots(&repo.to_string_lossy(), Some(30), &mut output);
+        let output = String::from_utf8(output).expect("utf8 output");
+
+        assert_eq!(exit_code, EXIT_SUCCESS);
+        assert_eq!(
+            output.lines().next(),
+            Some("src/graph/mod.rs: 6 (3 commit(s) x 2 complexity, 1 author(s))")
+        );
+        assert!(matches!(
+            parse_args(vec![
+                "hotspots".to_string(),
+                "/repo".to_string(),
+                "soon".to_string()
+            ]),
+            Some(Err(_))
+        ));
+
+        fs::remove_dir_all(repo).expect("cleanup hotspots repo");
+    }
+}
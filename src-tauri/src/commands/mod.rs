@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use serde::{Deserialize, Serialize};
@@ -6,8 +7,41 @@ use tokio::sync::RwLock;
 use crate::analyzer::rust_relations::RustImportRelationExtractor;
 use crate::analyzer::{AnalysisStatusDto, RustAnalyzerService, RustGraphExtractor};
 use crate::config::{AppConfigDto, ConfigStore, SourceRepoStatusDto};
+use crate::graph::api_diff::{diff_revisions, PublicApiDiffDto, PublicApiDiffError};
+use crate::graph::churn::{
+    attach_directory_churn, attach_file_relation_churn, file_churn, hotspot_report, ChurnWindowDto,
+    CoChangeOptionsDto, HotspotReportDto, DEFAULT_HOTSPOT_LIMIT,
+};
+use crate::graph::codeowners::{
+    attach_code_owners, cross_owner_dependencies, CodeOwners, CrossOwnerReportDto,
+};
+use crate::graph::concurrency::concurrency_topology;
+use crate::graph::coverage::{
+    attach_coverage, uncovered_public_functions, CoverageImportDto, LineCoverage,
+    UncoveredFunctionsReportDto,
+};
+use crate::graph::cycles::{
+    dependency_cycle_diagnostics, find_dependency_cycles, DependencyCycleGranularity,
+    DependencyCycleReportDto,
+};
+use crate::graph::dead_code::{dead_code_diagnostics, find_dead_code, DeadCodeReportDto};
+use crate::graph::error_flow::{error_flow_report, ErrorFlowReportDto};
+use crate::graph::metrics::{
+    attach_complexity_rollups, attach_coupling_metrics, complexity_offenders,
+    ComplexityOffenderDto, FunctionComplexityMetric, GraphMetricsReportDto,
+};
+use crate::graph::public_api::{public_api_report, PublicApiReportDto};
+use crate::graph::query::{GraphQueryDto, GraphQueryResultDto};
 use crate::graph::relations::{FileRelationGraphBuilder, FileRelationGraphSnapshotDto};
-use crate::graph::{DirectoryGraphBuilder, DirectoryGraphSnapshotDto, GraphSnapshotDto};
+use crate::graph::rules::architecture_rule_diagnostics;
+use crate::graph::search::{SymbolSearchIndex, SymbolSearchMatchDto, DEFAULT_SYMBOL_SEARCH_LIMIT};
+use crate::graph::state_machine::{state_machines, StateMachineReportDto};
+use crate::graph::unsafe_code::{attach_unsafe_rollups, unsafe_inventory, UnsafeInventoryDto};
+use crate::graph::wire_contract::{schema_bundle, wire_contract_catalog, WireContractCatalogDto};
+use crate::graph::{
+    BuildConfigurationDto, DirectoryGraphBuilder, DirectoryGraphSnapshotDto, GraphSnapshotDto,
+};
+use crate::source::git::{commit_history, GitError};
 use crate::source::ValidatedRepoPath;
 
 pub struct AppState {
@@ -16,6 +50,7 @@ pub struct AppState {
     config: RwLock<AppConfigDto>,
     analyzer_service: RustAnalyzerService,
     graph_snapshot: RwLock<Option<GraphSnapshotDto>>,
+    symbol_index: RwLock<Option<SymbolSearchIndex>>,
 }
 
 impl std::fmt::Debug for AppState {
@@ -45,6 +80,7 @@ impl AppState {
             config: RwLock::new(config),
             analyzer_service,
             graph_snapshot: RwLock::new(None),
+            symbol_index: RwLock::new(None),
         }
     }
 
@@ -122,7 +158,247 @@ impl AppState {
         })
     }
 
-    pub async fn analyze_source_repo(&self) -> Result<GraphSnapshotDto, CommandErrorDto> {
+    pub async fn dependency_cycles(
+        &self,
+        granularity: DependencyCycleGranularity,
+    ) -> Result<DependencyCycleReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(find_dependency_cycles(&snapshot, granularity))
+    }
+
+    pub async fn graph_metrics(&self) -> Result<GraphMetricsReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(GraphMetricsReportDto::from_snapshot(&snapshot))
+    }
+
+    pub async fn complexity_offenders(
+        &self,
+        metric: FunctionComplexityMetric,
+        limit: usize,
+    ) -> Result<Vec<ComplexityOffenderDto>, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(complexity_offenders(&snapshot, metric, limit))
+    }
+
+    pub async fn dead_code(&self) -> Result<DeadCodeReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(find_dead_code(&snapshot))
+    }
+
+    pub async fn query_graph(
+        &self,
+        query: GraphQueryDto,
+    ) -> Result<GraphQueryResultDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        query
+            .execute(&snapshot)
+            .map_err(|error| CommandErrorDto::validation(error.to_string()))
+    }
+
+    pub async fn search_symbols(
+        &self,
+        query: String,
+        limit: Option<usize>,
+    ) -> Result<Vec<SymbolSearchMatchDto>, CommandErrorDto> {
+        let guard = self.symbol_index.read().await;
+        let index = guard
+            .as_ref()
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(index.search(&query, limit.unwrap_or(DEFAULT_SYMBOL_SEARCH_LIMIT)))
+    }
+
+    pub async fn public_api(&self) -> Result<PublicApiReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(public_api_report(&snapshot))
+    }
+
+    /// Compares the configured repository's public API at `base_revision`
+    /// with `head_revision`, or with the working tree when no head is given.
+    pub async fn public_api_diff(
+        &self,
+        base_revision: String,
+        head_revision: Option<String>,
+    ) -> Result<PublicApiDiffDto, CommandErrorDto> {
+        let source_repo_path =
+            self.app_config().await.source_repo_path.ok_or_else(|| {
+                CommandErrorDto::validation("source repository is not configured")
+            })?;
+        let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(&source_repo_path)
+            .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
+
+        tokio::task::spawn_blocking(move || {
+            diff_revisions(&source_repo, &base_revision, head_revision.as_deref())
+        })
+        .await
+        .map_err(|error| CommandErrorDto::internal(error.to_string()))?
+        .map_err(|error| match error {
+            PublicApiDiffError::Git(
+                GitError::InvalidRevision(_) | GitError::UnknownRevision(_),
+            ) => CommandErrorDto::validation(error.to_string()),
+            _ => CommandErrorDto::internal(error.to_string()),
+        })
+    }
+
+    pub async fn unsafe_inventory(&self) -> Result<UnsafeInventoryDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(unsafe_inventory(&snapshot))
+    }
+
+    pub async fn concurrency_topology(&self) -> Result<GraphSnapshotDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(concurrency_topology(&snapshot))
+    }
+
+    pub async fn error_flow(&self) -> Result<ErrorFlowReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(error_flow_report(&snapshot))
+    }
+
+    pub async fn wire_contracts(&self) -> Result<WireContractCatalogDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(wire_contract_catalog(&snapshot))
+    }
+
+    pub async fn schema_bundle(&self) -> Result<serde_json::Value, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(schema_bundle(&snapshot))
+    }
+
+    pub async fn state_machines(&self) -> Result<StateMachineReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(state_machines(&snapshot))
+    }
+
+    /// Overlays a coverage report from inside the source repository on the
+    /// stored snapshot. Re-analysis replaces the snapshot and drops it.
+    pub async fn import_coverage(
+        &self,
+        report_path: String,
+    ) -> Result<CoverageImportDto, CommandErrorDto> {
+        let source_repo_path =
+            self.app_config().await.source_repo_path.ok_or_else(|| {
+                CommandErrorDto::validation("source repository is not configured")
+            })?;
+        let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(&source_repo_path)
+            .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
+        let (format, coverage) = LineCoverage::load(&source_repo, report_path.as_ref())
+            .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
+        let mut guard = self.graph_snapshot.write().await;
+        let snapshot = guard
+            .as_mut()
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(attach_coverage(snapshot, &coverage, &report_path, format))
+    }
+
+    /// Ranks files in the configured repository by churn within the window
+    /// times complexity from the current snapshot, or line count without one.
+    pub async fn hotspots(
+        &self,
+        window: ChurnWindowDto,
+        limit: Option<usize>,
+    ) -> Result<HotspotReportDto, CommandErrorDto> {
+        let source_repo_path =
+            self.app_config().await.source_repo_path.ok_or_else(|| {
+                CommandErrorDto::validation("source repository is not configured")
+            })?;
+        let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(&source_repo_path)
+            .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
+        let snapshot = self.graph_snapshot().await;
+
+        tokio::task::spawn_blocking(move || {
+            let commits = commit_history(&source_repo, window.since_days)
+                .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
+            Ok(hotspot_report(
+                &source_repo,
+                window,
+                file_churn(&commits),
+                snapshot.as_ref(),
+                limit.unwrap_or(DEFAULT_HOTSPOT_LIMIT),
+            ))
+        })
+        .await
+        .map_err(|error| CommandErrorDto::internal(error.to_string()))?
+    }
+
+    /// Imports and calls between files of the configured repository whose
+    /// CODEOWNERS owners do not overlap.
+    pub async fn cross_owner_dependencies(&self) -> Result<CrossOwnerReportDto, CommandErrorDto> {
+        let source_repo_path =
+            self.app_config().await.source_repo_path.ok_or_else(|| {
+                CommandErrorDto::validation("source repository is not configured")
+            })?;
+        let snapshot = self
+            .load_file_relation_graph(source_repo_path, None, None)
+            .await?;
+
+        Ok(cross_owner_dependencies(&snapshot))
+    }
+
+    pub async fn uncovered_public_functions(
+        &self,
+    ) -> Result<UncoveredFunctionsReportDto, CommandErrorDto> {
+        let snapshot = self
+            .graph_snapshot()
+            .await
+            .ok_or_else(|| CommandErrorDto::validation("graph snapshot is not available"))?;
+
+        Ok(uncovered_public_functions(&snapshot))
+    }
+
+    pub async fn analyze_source_repo(
+        &self,
+        configuration: Option<BuildConfigurationDto>,
+    ) -> Result<GraphSnapshotDto, CommandErrorDto> {
         let config = self.app_config().await;
         let source_repo_path = config
             .source_repo_path
@@ -136,11 +412,23 @@ impl AppState {
             .await
             .map_err(|error| CommandErrorDto::internal(error.to_string()))?;
 
-        let extraction_result = RustGraphExtractor.extract(&source_repo);
+        let extraction_result = RustGraphExtractor
+            .extract_with_configuration(&source_repo, &configuration.unwrap_or_default());
         let _ = self.analyzer_service.complete_analysis_job(job_id).await;
-        let snapshot =
+        let mut snapshot =
             extraction_result.map_err(|error| CommandErrorDto::internal(error.to_string()))?;
-
+        attach_coupling_metrics(&mut snapshot);
+        attach_complexity_rollups(&mut snapshot);
+        attach_unsafe_rollups(&mut snapshot);
+        let rule_diagnostics = architecture_rule_diagnostics(&source_repo, &snapshot);
+        snapshot.diagnostics.extend(rule_diagnostics);
+        let cycle_diagnostics = dependency_cycle_diagnostics(&snapshot);
+        snapshot.diagnostics.extend(cycle_diagnostics);
+        let dead_code_diagnostics = dead_code_diagnostics(&snapshot);
+        snapshot.diagnostics.extend(dead_code_diagnostics);
+
+        let symbol_index = SymbolSearchIndex::from_snapshot(&snapshot);
+        *self.symbol_index.write().await = Some(symbol_index);
         let mut guard = self.graph_snapshot.write().await;
         *guard = Some(snapshot.clone());
         Ok(snapshot)
@@ -149,19 +437,30 @@ impl AppState {
     pub async fn load_directory_graph(
         &self,
         raw_path: String,
+        churn_window: Option<ChurnWindowDto>,
     ) -> Result<DirectoryGraphSnapshotDto, CommandErrorDto> {
         let source_repo = ValidatedRepoPath::parse_existing_cargo_repo(&raw_path)
             .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
 
-        tokio::task::spawn_blocking(move || DirectoryGraphBuilder::build(&source_repo))
-            .await
-            .map_err(|error| CommandErrorDto::internal(error.to_string()))?
-            .map_err(|error| CommandErrorDto::internal(error.to_string()))
+        tokio::task::spawn_blocking(move || {
+            let mut snapshot = DirectoryGraphBuilder::build(&source_repo)
+                .map_err(|error| CommandErrorDto::internal(error.to_string()))?;
+            if let Some(window) = churn_window {
+                let commits = commit_history(&source_repo, window.since_days)
+                    .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
+                attach_directory_churn(&mut snapshot, &file_churn(&commits));
+            }
+            Ok(snapshot)
+        })
+        .await
+        .map_err(|error| CommandErrorDto::internal(error.to_string()))?
     }
 
     pub async fn load_file_relation_graph(
         &self,
         raw_path: String,
+        churn_window: Option<ChurnWindowDto>,
+        co_change: Option<CoChangeOptionsDto>,
     ) -> Result<FileRelationGraphSnapshotDto, CommandErrorDto> {
         let source_root = ValidatedRepoPath::parse_existing_source_root(&raw_path)
             .map_err(|error| CommandErrorDto::validation(error.to_string()))?;
@@ -169,6 +468,20 @@ impl AppState {
         tokio::task::spawn_blocking(move || {
             let mut snapshot = FileRelationGraphBuilder::build_structure(&source_root)
                 .map_err(|error| error.to_string())?;
+            match CodeOwners::load_from_repo(&source_root) {
+                Ok(Some(code_owners)) => attach_code_owners(&mut snapshot, &code_owners),
+                Ok(None) => {}
+                Err(error) => {
+                    snapshot
+                        .diagnostics
+                        .push(crate::graph::AnalyzerDiagnosticDto {
+                            code: "codeowners_unavailable".to_string(),
+                            message: error.to_string(),
+                            source_path: None,
+                            evidence: None,
+                        });
+                }
+            }
             let import_snapshot = RustImportRelationExtractor
                 .extract(&source_root)
                 .map_err(|error| error.to_string())?;
@@ -189,10 +502,50 @@ impl AppState {
                                 code: "rust_call_extraction_failed".to_string(),
                                 message: error.to_string(),
                                 source_path: None,
+                                evidence: None,
+                            });
+                    }
+                }
+            }
+            // Churn and co-change share one history read when their windows match.
+            let mut histories = BTreeMap::new();
+            for window in churn_window
+                .iter()
+                .chain(co_change.as_ref().map(|options| &options.window))
+            {
+                if histories.contains_key(&window.since_days) {
+                    continue;
+                }
+                match commit_history(&source_root, window.since_days) {
+                    Ok(commits) => {
+                        histories.insert(window.since_days, commits);
+                    }
+                    Err(error) => {
+                        snapshot
+                            .diagnostics
+                            .push(crate::graph::AnalyzerDiagnosticDto {
+                                code: "git_history_unavailable".to_string(),
+                                message: error.to_string(),
+                                source_path: None,
+                                evidence: None,
                             });
+                        break;
                     }
                 }
             }
+            if let Some(commits) = churn_window.and_then(|window| histories.get(&window.since_days))
+            {
+                attach_file_relation_churn(&mut snapshot, &file_churn(commits));
+            }
+            if let Some(options) = co_change {
+                if let Some(commits) = histories.get(&options.window.since_days) {
+                    FileRelationGraphBuilder::add_co_change_relations(
+                        &mut snapshot,
+                        commits,
+                        &options,
+                    );
+                }
+            }
             Ok::<_, String>(snapshot)
         })
         .await
@@ -300,25 +653,31 @@ pub async fn get_analysis_status(
 
 #[tauri::command]
 pub async fn analyze_source_repo(
+    configuration: Option<BuildConfigurationDto>,
     state: tauri::State<'_, std::sync::Arc<AppState>>,
 ) -> Result<GraphSnapshotDto, CommandErrorDto> {
-    state.analyze_source_repo().await
+    state.analyze_source_repo(configuration).await
 }
 
 #[tauri::command]
 pub async fn load_directory_graph(
     path: String,
+    churn_window: Option<ChurnWindowDto>,
     state: tauri::State<'_, std::sync::Arc<AppState>>,
 ) -> Result<DirectoryGraphSnapshotDto, CommandErrorDto> {
-    state.load_directory_graph(path).await
+    state.load_directory_graph(path, churn_window).await
 }
 
 #[tauri::command]
 pub async fn load_file_relation_graph(
     path: String,
+    churn_window: Option<ChurnWindowDto>,
+    co_change: Option<CoChangeOptionsDto>,
     state: tauri::State<'_, std::sync::Arc<AppState>>,
 ) -> Result<FileRelationGraphSnapshotDto, CommandErrorDto> {
-    state.load_file_relation_graph(path).await
+    state
+        .load_file_relation_graph(path, churn_window, co_change)
+        .await
 }
 
 #[tauri::command]
@@ -336,6 +695,143 @@ pub async fn get_source_snippet(
     state.source_snippet(node_id).await
 }
 
+#[tauri::command]
+pub async fn get_dependency_cycles(
+    granularity: DependencyCycleGranularity,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<DependencyCycleReportDto, CommandErrorDto> {
+    state.dependency_cycles(granularity).await
+}
+
+#[tauri::command]
+pub async fn get_graph_metrics(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<GraphMetricsReportDto, CommandErrorDto> {
+    state.graph_metrics().await
+}
+
+#[tauri::command]
+pub async fn get_complexity_offenders(
+    metric: FunctionComplexityMetric,
+    limit: usize,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<Vec<ComplexityOffenderDto>, CommandErrorDto> {
+    state.complexity_offenders(metric, limit).await
+}
+
+#[tauri::command]
+pub async fn get_dead_code(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<DeadCodeReportDto, CommandErrorDto> {
+    state.dead_code().await
+}
+
+#[tauri::command]
+pub async fn query_graph(
+    query: GraphQueryDto,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<GraphQueryResultDto, CommandErrorDto> {
+    state.query_graph(query).await
+}
+
+#[tauri::command]
+pub async fn search_symbols(
+    query: String,
+    limit: Option<usize>,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<Vec<SymbolSearchMatchDto>, CommandErrorDto> {
+    state.search_symbols(query, limit).await
+}
+
+#[tauri::command]
+pub async fn get_public_api(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<PublicApiReportDto, CommandErrorDto> {
+    state.public_api().await
+}
+
+#[tauri::command]
+pub async fn get_public_api_diff(
+    base_revision: String,
+    head_revision: Option<String>,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<PublicApiDiffDto, CommandErrorDto> {
+    state.public_api_diff(base_revision, head_revision).await
+}
+
+#[tauri::command]
+pub async fn get_unsafe_inventory(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<UnsafeInventoryDto, CommandErrorDto> {
+    state.unsafe_inventory().await
+}
+
+#[tauri::command]
+pub async fn get_concurrency_topology(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<GraphSnapshotDto, CommandErrorDto> {
+    state.concurrency_topology().await
+}
+
+#[tauri::command]
+pub async fn get_error_flow(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<ErrorFlowReportDto, CommandErrorDto> {
+    state.error_flow().await
+}
+
+#[tauri::command]
+pub async fn get_wire_contracts(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<WireContractCatalogDto, CommandErrorDto> {
+    state.wire_contracts().await
+}
+
+#[tauri::command]
+pub async fn get_schema_bundle(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<serde_json::Value, CommandErrorDto> {
+    state.schema_bundle().await
+}
+
+#[tauri::command]
+pub async fn get_state_machines(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<StateMachineReportDto, CommandErrorDto> {
+    state.state_machines().await
+}
+
+#[tauri::command]
+pub async fn import_coverage(
+    report_path: String,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<CoverageImportDto, CommandErrorDto> {
+    state.import_coverage(report_path).await
+}
+
+#[tauri::command]
+pub async fn get_uncovered_public_functions(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<UncoveredFunctionsReportDto, CommandErrorDto> {
+    state.uncovered_public_functions().await
+}
+
+#[tauri::command]
+pub async fn get_hotspots(
+    window: ChurnWindowDto,
+    limit: Option<usize>,
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<HotspotReportDto, CommandErrorDto> {
+    state.hotspots(window, limit).await
+}
+
+#[tauri::command]
+pub async fn get_cross_owner_dependencies(
+    state: tauri::State<'_, std::sync::Arc<AppState>>,
+) -> Result<CrossOwnerReportDto, CommandErrorDto> {
+    state.cross_owner_dependencies().await
+}
+
 #[tauri::command]
 pub async fn set_source_repo_path(
     path: String,
@@ -461,7 +957,7 @@ mod tests {
             .expect("set source repo");
 
         let snapshot = state
-            .analyze_source_repo()
+            .analyze_source_repo(None)
             .await
             .expect("analyze source repo");
 
@@ -486,7 +982,7 @@ mod tests {
         let state = AppState::new(store, AppConfigDto::default());
 
         let snapshot = state
-            .load_directory_graph(repo_dir.to_string_lossy().into_owned())
+            .load_directory_graph(repo_dir.to_string_lossy().into_owned(), None)
             .await
             .expect("load directory graph");
 
@@ -512,7 +1008,7 @@ mod tests {
         let state = AppState::new(store, AppConfigDto::default());
 
         let snapshot = state
-            .load_file_relation_graph(repo_dir.to_string_lossy().into_owned())
+            .load_file_relation_graph(repo_dir.to_string_lossy().into_owned(), None, None)
             .await
             .expect("load file relation graph");
 
@@ -558,7 +1054,7 @@ mod tests {
         let state = AppState::new(store, AppConfigDto::default());
 
         let snapshot = state
-            .load_file_relation_graph(repo_dir.to_string_lossy().into_owned())
+            .load_file_relation_graph(repo_dir.to_string_lossy().into_owned(), None, None)
             .await
             .expect("load file relation graph");
 
@@ -596,7 +1092,7 @@ mod tests {
             .await
             .expect("set source repo");
         let snapshot = state
-            .analyze_source_repo()
+            .analyze_source_repo(None)
             .await
             .expect("analyze source repo");
         let node_id = snapshot
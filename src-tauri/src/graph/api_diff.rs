@@ -0,0 +1,478 @@
+//! Public API comparison between two revisions with suggested semver impact.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use super::public_api::{public_api_report, CratePublicApiDto, PublicApiReportDto};
+use super::GraphNodeKind;
+use crate::analyzer::RustGraphExtractor;
+use crate::source::git::{GitError, RevisionCheckout};
+use crate::source::ValidatedRepoPath;
+
+/// Head label used when comparing against the working tree.
+pub const WORKING_TREE_REVISION: &str = "working tree";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicApiDiffDto {
+    pub base_revision: String,
+    pub head_revision: String,
+    /// Highest impact across crates; `patch` when nothing public changed.
+    pub impact: SemverImpactDto,
+    /// Crates with at least one change, sorted by name.
+    pub crates: Vec<CrateApiDiffDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrateApiDiffDto {
+    pub crate_name: String,
+    pub impact: SemverImpactDto,
+    /// Sorted by path, then kind.
+    pub changes: Vec<PublicApiChangeDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicApiChangeDto {
+    pub path: String,
+    pub kind: GraphNodeKind,
+    pub change: PublicApiChangeKindDto,
+    pub base_signature: Option<String>,
+    pub head_signature: Option<String>,
+    pub impact: SemverImpactDto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicApiChangeKindDto {
+    Added,
+    Removed,
+    SignatureChanged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SemverImpactDto {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverImpactDto {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        }
+    }
+}
+
+impl PublicApiChangeKindDto {
+    /// Additions are compatible. Removals and signature changes are treated
+    /// as breaking because the signature text cannot tell a widened bound
+    /// from a narrowed one.
+    fn impact(self) -> SemverImpactDto {
+        match self {
+            Self::Added => SemverImpactDto::Minor,
+            Self::Removed | Self::SignatureChanged => SemverImpactDto::Major,
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Removed => "Removed",
+            Self::SignatureChanged => "Changed",
+            Self::Added => "Added",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PublicApiDiffError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error("failed to analyze revision '{revision}': {message}")]
+    Extraction { revision: String, message: String },
+}
+
+/// Extracts the public API at `base_revision` and at `head_revision`, or
+/// the working tree when no head is given, and compares them.
+pub fn diff_revisions(
+    source_root: &ValidatedRepoPath,
+    base_revision: &str,
+    head_revision: Option<&str>,
+) -> Result<PublicApiDiffDto, PublicApiDiffError> {
+    let base = revision_public_api(source_root, base_revision)?;
+    let head = match head_revision {
+        Some(revision) => revision_public_api(source_root, revision)?,
+        None => extract_public_api(source_root, WORKING_TREE_REVISION)?,
+    };
+    Ok(diff_public_api(
+        &base,
+        &head,
+        base_revision,
+        head_revision.unwrap_or(WORKING_TREE_REVISION),
+    ))
+}
+
+fn revision_public_api(
+    source_root: &ValidatedRepoPath,
+    revision: &str,
+) -> Result<PublicApiReportDto, PublicApiDiffError> {
+    let checkout = RevisionCheckout::export(source_root, revision)?;
+    extract_public_api(checkout.source_root(), revision)
+}
+
+fn extract_public_api(
+    source_root: &ValidatedRepoPath,
+    revision: &str,
+) -> Result<PublicApiReportDto, PublicApiDiffError> {
+    let snapshot = RustGraphExtractor.extract(source_root).map_err(|error| {
+        PublicApiDiffError::Extraction {
+            revision: revision.to_string(),
+            message: error.to_string(),
+        }
+    })?;
+    Ok(public_api_report(&snapshot))
+}
+
+/// Matches items by crate name, public path, and kind. A kind change at one
+/// path is a removal plus an addition.
+pub fn diff_public_api(
+    base: &PublicApiReportDto,
+    head: &PublicApiReportDto,
+    base_revision: &str,
+    head_revision: &str,
+) -> PublicApiDiffDto {
+    let base_crates = crates_by_name(base);
+    let head_crates = crates_by_name(head);
+    let crate_names = base_crates
+        .keys()
+        .chain(head_crates.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+
+    let crates = crate_names
+        .into_iter()
+        .filter_map(|crate_name| {
+            let changes = diff_crate(
+                base_crates.get(crate_name).copied(),
+                head_crates.get(crate_name).copied(),
+            );
+            let impact = changes.iter().map(|change| change.impact).max()?;
+            Some(CrateApiDiffDto {
+                crate_name: crate_name.to_string(),
+                impact,
+                changes,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    PublicApiDiffDto {
+        base_revision: base_revision.to_string(),
+        head_revision: head_revision.to_string(),
+        impact: crates
+            .iter()
+            .map(|diff| diff.impact)
+            .max()
+            .unwrap_or(SemverImpactDto::Patch),
+        crates,
+    }
+}
+
+fn crates_by_name(report: &PublicApiReportDto) -> BTreeMap<&str, &CratePublicApiDto> {
+    report
+        .crates
+        .iter()
+        .map(|api| (api.crate_name.as_str(), api))
+        .collect()
+}
+
+/// Items keyed by path and kind label, with their kind and signature.
+type ItemSignatures<'a> = BTreeMap<(&'a str, String), (&'a GraphNodeKind, Option<&'a str>)>;
+
+fn item_signatures(api: Option<&CratePublicApiDto>) -> ItemSignatures<'_> {
+    api.into_iter()
+        .flat_map(|api| &api.items)
+        .map(|item| {
+            (
+                (item.path.as_str(), kind_label(&item.kind)),
+                (&item.kind, item.signature.as_deref()),
+            )
+        })
+        .collect()
+}
+
+fn diff_crate(
+    base: Option<&CratePublicApiDto>,
+    head: Option<&CratePublicApiDto>,
+) -> Vec<PublicApiChangeDto> {
+    let base = item_signatures(base);
+    let head = item_signatures(head);
+    let keys = base.keys().chain(head.keys()).collect::<BTreeSet<_>>();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let base_item = base.get(key);
+            let head_item = head.get(key);
+            let change = match (base_item, head_item) {
+                (Some(_), None) => PublicApiChangeKindDto::Removed,
+                (None, Some(_)) => PublicApiChangeKindDto::Added,
+                (Some((_, before)), Some((_, after))) if before != after => {
+                    PublicApiChangeKindDto::SignatureChanged
+                }
+                _ => return None,
+            };
+            let (kind, _) = base_item.or(head_item)?;
+            Some(PublicApiChangeDto {
+                path: key.0.to_string(),
+                kind: (*kind).clone(),
+                change,
+                base_signature: base_item.and_then(|(_, signature)| signature.map(str::to_string)),
+                head_signature: head_item.and_then(|(_, signature)| signature.map(str::to_string)),
+                impact: change.impact(),
+            })
+        })
+        .collect()
+}
+
+impl PublicApiDiffDto {
+    /// Release-notes style summary grouped by crate, then by removed,
+    /// changed, and added items.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Public API changes: `{}` to `{}`\n\nSuggested semver impact: **{}**\n",
+            self.base_revision,
+            self.head_revision,
+            self.impact.as_str()
+        );
+        if self.crates.is_empty() {
+            markdown.push_str("\nNo public API changes.\n");
+            return markdown;
+        }
+
+        for crate_diff in &self.crates {
+            let _ = write!(
+                markdown,
+                "\n## `{}` ({})\n",
+                crate_diff.crate_name,
+                crate_diff.impact.as_str()
+            );
+            for change_kind in [
+                PublicApiChangeKindDto::Removed,
+                PublicApiChangeKindDto::SignatureChanged,
+                PublicApiChangeKindDto::Added,
+            ] {
+                let changes = crate_diff
+                    .changes
+                    .iter()
+                    .filter(|change| change.change == change_kind)
+                    .collect::<Vec<_>>();
+                if changes.is_empty() {
+                    continue;
+                }
+                let _ = write!(markdown, "\n### {}\n\n", change_kind.heading());
+                for change in changes {
+                    let _ = writeln!(
+                        markdown,
+                        "- `{}` ({})",
+                        change.path,
+                        kind_label(&change.kind)
+                    );
+                    match (&change.base_signature, &change.head_signature) {
+                        (Some(before), Some(after)) => {
+                            let _ = writeln!(markdown, "  - before: `{before}`");
+                            let _ = writeln!(markdown, "  - after: `{after}`");
+                        }
+                        (Some(signature), None) | (None, Some(signature)) => {
+                            let _ = writeln!(markdown, "  - `{signature}`");
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+        markdown
+    }
+}
+
+fn kind_label(kind: &GraphNodeKind) -> String {
+    serde_json::to_value(kind)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_public_api, PublicApiChangeKindDto, SemverImpactDto};
+    use crate::graph::public_api::{CratePublicApiDto, PublicApiItemDto, PublicApiReportDto};
+    use crate::graph::GraphNodeKind;
+
+    fn report(crate_name: &str, items: &[(&str, GraphNodeKind, &str)]) -> PublicApiReportDto {
+        PublicApiReportDto {
+            crates: vec![CratePublicApiDto {
+                crate_id: format!("crate:{crate_name}"),
+                crate_name: crate_name.to_string(),
+                items: items
+                    .iter()
+                    .map(|(path, kind, signature)| PublicApiItemDto {
+                        path: path.to_string(),
+                        node_id: format!("node:{path}"),
+                        kind: kind.clone(),
+                        signature: Some(signature.to_string()),
+                        source_range: None,
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    #[test]
+    fn classifies_changes_and_suggests_the_highest_impact() {
+        let base = report(
+            "engine",
+            &[
+                (
+                    "engine::load",
+                    GraphNodeKind::Function,
+                    "pub fn load(path: &str)",
+                ),
+                (
+                    "engine::Config",
+                    GraphNodeKind::Struct,
+                    "pub struct Config;",
+                ),
+                (
+                    "engine::Event",
+                    GraphNodeKind::Enum,
+                    "pub enum Event { Start, Stop }",
+                ),
+                (
+                    "engine::Limits",
+                    GraphNodeKind::Struct,
+                    "pub struct Limits { pub max: u32 }",
+                ),
+                (
+                    "engine::Session",
+                    GraphNodeKind::Struct,
+                    "pub struct Session { pub id: u64, .. }",
+                ),
+                ("engine::legacy", GraphNodeKind::Function, "pub fn legacy()"),
+            ],
+        );
+        let head = report(
+            "engine",
+            &[
+                (
+                    "engine::load",
+                    GraphNodeKind::Function,
+                    "pub fn load<P: AsRef<str>>(path: P)",
+                ),
+                (
+                    "engine::Config",
+                    GraphNodeKind::Struct,
+                    "pub struct Config;",
+                ),
+                // Adding `#[non_exhaustive]` stops downstream construction
+                // and exhaustive matches.
+                (
+                    "engine::Event",
+                    GraphNodeKind::Enum,
+                    "#[non_exhaustive] pub enum Event { Start, Stop }",
+                ),
+                // The first private field stops struct-literal construction.
+                (
+                    "engine::Limits",
+                    GraphNodeKind::Struct,
+                    "pub struct Limits { pub max: u32, .. }",
+                ),
+                // Editing a `pub(crate)` field leaves the signature as is.
+                (
+                    "engine::Session",
+                    GraphNodeKind::Struct,
+                    "pub struct Session { pub id: u64, .. }",
+                ),
+                ("engine::save", GraphNodeKind::Function, "pub fn save()"),
+            ],
+        );
+
+        let diff = diff_public_api(&base, &head, "v1.0.0", "HEAD");
+
+        assert_eq!(diff.impact, SemverImpactDto::Major);
+        assert_eq!(diff.crates.len(), 1);
+        let changes = diff.crates[0]
+            .changes
+            .iter()
+            .map(|change| (change.path.as_str(), change.change, change.impact))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "engine::Event",
+                    PublicApiChangeKindDto::SignatureChanged,
+                    SemverImpactDto::Major
+                ),
+                (
+                    "engine::Limits",
+                    PublicApiChangeKindDto::SignatureChanged,
+                    SemverImpactDto::Major
+                ),
+                (
+                    "engine::legacy",
+                    PublicApiChangeKindDto::Removed,
+                    SemverImpactDto::Major
+                ),
+                (
+                    "engine::load",
+                    PublicApiChangeKindDto::SignatureChanged,
+                    SemverImpactDto::Major
+                ),
+                (
+                    "engine::save",
+                    PublicApiChangeKindDto::Added,
+                    SemverImpactDto::Minor
+                ),
+            ]
+        );
+
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("Suggested semver impact: **major**"));
+        assert!(markdown
+            .contains("### Removed\n\n- `engine::legacy` (function)\n  - `pub fn legacy()`"));
+        assert!(markdown.contains("  - before: `pub fn load(path: &str)`\n  - after: `pub fn load<P: AsRef<str>>(path: P)`"));
+    }
+
+    #[test]
+    fn additive_changes_are_minor_and_identical_apis_are_patch() {
+        let base = report(
+            "engine",
+            &[("engine::load", GraphNodeKind::Function, "pub fn load()")],
+        );
+        let head = report(
+            "engine",
+            &[
+                ("engine::load", GraphNodeKind::Function, "pub fn load()"),
+                (
+                    "engine::Mode",
+                    GraphNodeKind::Enum,
+                    "pub enum Mode { Fast }",
+                ),
+            ],
+        );
+
+        assert_eq!(
+            diff_public_api(&base, &head, "a", "b").impact,
+            SemverImpactDto::Minor
+        );
+        let unchanged = diff_public_api(&base, &base, "a", "b");
+        assert_eq!(unchanged.impact, SemverImpactDto::Patch);
+        assert!(unchanged.crates.is_empty());
+        assert!(unchanged.to_markdown().contains("No public API changes."));
+    }
+}
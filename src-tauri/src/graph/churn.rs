@@ -0,0 +1,357 @@
+//! Git churn from local history: per-file commit, author, and line change
+//! counts over a time window, hotspots ranking churn against size, and the
+//! thresholds for co-change relations.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::relations::{FileRelationGraphSnapshotDto, FileRelationNodeKind};
+use super::{DirectoryGraphNodeKind, DirectoryGraphSnapshotDto, GraphNodeKind, GraphSnapshotDto};
+use crate::source::git::GitCommit;
+use crate::source::ValidatedRepoPath;
+
+pub const DEFAULT_HOTSPOT_LIMIT: usize = 25;
+
+/// History window for churn; all history when `since_days` is absent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChurnWindowDto {
+    pub since_days: Option<u32>,
+}
+
+/// Thresholds for co-change relations between files. Missing fields take
+/// their defaults, so `{}` is a valid request.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CoChangeOptionsDto {
+    pub window: ChurnWindowDto,
+    /// Commits that must change both files.
+    pub min_support: u32,
+    /// Share of the less frequently changed file's commits that must also
+    /// change the other file.
+    pub min_confidence: f64,
+    /// Commits changing more files are skipped as bulk edits.
+    pub max_files_per_commit: usize,
+}
+
+impl Default for CoChangeOptionsDto {
+    fn default() -> Self {
+        Self {
+            window: ChurnWindowDto::default(),
+            min_support: 3,
+            min_confidence: 0.5,
+            max_files_per_commit: 30,
+        }
+    }
+}
+
+/// Change history of one file within a churn window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChurnDto {
+    pub commit_count: u32,
+    /// Distinct author emails, compared case-insensitively.
+    pub author_count: u32,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    pub last_commit: LastCommitDto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCommitDto {
+    pub hash: String,
+    pub author_name: String,
+    /// Committer time in seconds since the Unix epoch.
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotspotReportDto {
+    pub window: ChurnWindowDto,
+    /// Highest score first.
+    pub hotspots: Vec<HotspotDto>,
+}
+
+/// A file whose commit count times size measure ranks it for review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotspotDto {
+    pub path: String,
+    pub churn: FileChurnDto,
+    pub measure: HotspotMeasureDto,
+    pub measure_value: u32,
+    /// `churn.commit_count * measure_value`.
+    pub score: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotspotMeasureDto {
+    /// Summed cyclomatic complexity from the file's complexity rollup.
+    CyclomaticComplexity,
+    /// Line count of the file on disk, for files without Rust analysis.
+    LinesOfCode,
+}
+
+/// Churn of every path touched by the commits, keyed by path relative to
+/// the source root. Commits are expected newest first.
+pub fn file_churn(commits: &[GitCommit]) -> BTreeMap<String, FileChurnDto> {
+    let mut authors = BTreeMap::<&str, BTreeSet<String>>::new();
+    let mut churn = BTreeMap::<String, FileChurnDto>::new();
+    for commit in commits {
+        for change in &commit.files {
+            authors
+                .entry(change.path.as_str())
+                .or_default()
+                .insert(commit.author_email.to_lowercase());
+            let entry = churn
+                .entry(change.path.clone())
+                .or_insert_with(|| FileChurnDto {
+                    commit_count: 0,
+                    author_count: 0,
+                    lines_added: 0,
+                    lines_removed: 0,
+                    last_commit: LastCommitDto {
+                        hash: commit.hash.clone(),
+                        author_name: commit.author_name.clone(),
+                        timestamp: commit.timestamp,
+                    },
+                });
+            entry.commit_count += 1;
+            entry.lines_added = entry.lines_added.saturating_add(change.added);
+            entry.lines_removed = entry.lines_removed.saturating_add(change.removed);
+        }
+    }
+    for (path, entry) in &mut churn {
+        entry.author_count = authors.get(path.as_str()).map_or(0, |set| set.len() as u32);
+    }
+    churn
+}
+
+/// Stores churn on directory graph file nodes.
+pub fn attach_directory_churn(
+    snapshot: &mut DirectoryGraphSnapshotDto,
+    churn: &BTreeMap<String, FileChurnDto>,
+) {
+    for node in &mut snapshot.nodes {
+        if node.kind == DirectoryGraphNodeKind::File {
+            node.churn = churn.get(&node.path).cloned();
+        }
+    }
+}
+
+/// Stores churn on file relation graph file nodes.
+pub fn attach_file_relation_churn(
+    snapshot: &mut FileRelationGraphSnapshotDto,
+    churn: &BTreeMap<String, FileChurnDto>,
+) {
+    for node in &mut snapshot.nodes {
+        if node.kind == FileRelationNodeKind::File {
+            node.churn = churn.get(&node.path).cloned();
+        }
+    }
+}
+
+/// Ranks churned files that still exist by commit count times summed
+/// cyclomatic complexity when the graph snapshot has a rollup for the file,
+/// otherwise times its line count. Zero scores are left out.
+pub fn hotspot_report(
+    source_root: &ValidatedRepoPath,
+    window: ChurnWindowDto,
+    churn: BTreeMap<String, FileChurnDto>,
+    graph_snapshot: Option<&GraphSnapshotDto>,
+    limit: usize,
+) -> HotspotReportDto {
+    let complexity_by_path = graph_snapshot
+        .into_iter()
+        .flat_map(|snapshot| &snapshot.nodes)
+        .filter(|node| node.kind == GraphNodeKind::File)
+        .filter_map(|node| {
+            let rollup = node.metrics.as_ref()?.complexity_rollup.as_ref()?;
+            Some((
+                node.source_range.as_ref()?.path.as_str(),
+                rollup.total_cyclomatic_complexity,
+            ))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut hotspots = churn
+        .into_iter()
+        .filter_map(|(path, churn)| {
+            let (measure, measure_value) = match complexity_by_path.get(path.as_str()) {
+                Some(complexity) => (HotspotMeasureDto::CyclomaticComplexity, *complexity),
+                None => {
+                    let file = source_root.resolve_existing_child(&path).ok()?;
+                    let lines = fs::read_to_string(file).ok()?.lines().count();
+                    (HotspotMeasureDto::LinesOfCode, lines as u32)
+                }
+            };
+            let score = u64::from(churn.commit_count) * u64::from(measure_value);
+            (score > 0).then_some(HotspotDto {
+                path,
+                churn,
+                measure,
+                measure_value,
+                score,
+            })
+        })
+        .collect::<Vec<_>>();
+    hotspots.sort_by(|left, right| {
+        right
+            .score
+            .cmp(&left.score)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    hotspots.truncate(limit);
+
+    HotspotReportDto { window, hotspots }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_churn, hotspot_report, ChurnWindowDto, HotspotMeasureDto};
+    use crate::graph::metrics::{ComplexityRollupDto, NodeMetricsDto};
+    use crate::graph::{
+        stable_node_id, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+    use crate::source::git::{GitCommit, GitFileChange};
+    use crate::source::ValidatedRepoPath;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn commit(hash: &str, email: &str, files: &[(&str, u32, u32)]) -> GitCommit {
+        GitCommit {
+            hash: hash.to_string(),
+            timestamp: 1_700_000_000,
+            author_name: email.split('@').next().unwrap_or_default().to_string(),
+            author_email: email.to_string(),
+            files: files
+                .iter()
+                .map(|(path, added, removed)| GitFileChange {
+                    path: path.to_string(),
+                    added: *added,
+                    removed: *removed,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn ranks_churned_files_by_complexity_or_line_count() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        let repo = std::env::temp_dir().join(format!(
+            "whip-docs-churn-{}-{timestamp}",
+            std::process::id()
+        ));
+        fs::create_dir_all(repo.join("src")).expect("create fixture src");
+        fs::write(repo.join("Cargo.toml"), "[package]\nname = \"fixture\"\n").expect("manifest");
+        fs::write(repo.join("src/lib.rs"), "pub fn a() {}\n").expect("write lib");
+        fs::write(repo.join("README.md"), "one\ntwo\nthree\n").expect("write readme");
+        let source_root =
+            ValidatedRepoPath::parse_existing_cargo_repo(&repo).expect("valid fixture root");
+        let commits = vec![
+            commit("c3", "ana@example.com", &[("src/lib.rs", 4, 1)]),
+            commit(
+                "c2",
+                "ANA@example.com",
+                &[("src/lib.rs", 2, 0), ("README.md", 3, 0)],
+            ),
+            commit(
+                "c1",
+                "bo@example.com",
+                &[("src/lib.rs", 1, 0), ("src/old.rs", 9, 0)],
+            ),
+        ];
+        let mut file_node = GraphNodeDto {
+            id: stable_node_id(GraphNodeKind::File, &["src/lib.rs"]),
+            kind: GraphNodeKind::File,
+            label: "src/lib.rs".to_string(),
+            source_range: Some(SourceRangeDto {
+                path: "src/lib.rs".to_string(),
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 1,
+            }),
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        };
+        file_node.metrics = Some(NodeMetricsDto {
+            complexity_rollup: Some(ComplexityRollupDto {
+                function_count: 1,
+                lines_of_code: 1,
+                total_cyclomatic_complexity: 2,
+                max_cyclomatic_complexity: 2,
+                max_nesting_depth: 0,
+                question_mark_count: 0,
+                unwrap_count: 0,
+                expect_count: 0,
+                await_count: 0,
+            }),
+            ..NodeMetricsDto::default()
+        });
+        let snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: repo.to_string_lossy().into_owned(),
+            generated_at: "test".to_string(),
+            nodes: vec![file_node],
+            edges: Vec::new(),
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        let churn = file_churn(&commits);
+        let lib = &churn["src/lib.rs"];
+        assert_eq!(
+            (
+                lib.commit_count,
+                lib.author_count,
+                lib.lines_added,
+                lib.lines_removed
+            ),
+            (3, 2, 7, 1)
+        );
+        assert_eq!(lib.last_commit.hash, "c3");
+
+        let report = hotspot_report(
+            &source_root,
+            ChurnWindowDto {
+                since_days: Some(90),
+            },
+            churn,
+            Some(&snapshot),
+            10,
+        );
+
+        assert_eq!(
+            report
+                .hotspots
+                .iter()
+                .map(|hotspot| (hotspot.path.as_str(), hotspot.measure, hotspot.score))
+                .collect::<Vec<_>>(),
+            vec![
+                ("src/lib.rs", HotspotMeasureDto::CyclomaticComplexity, 6),
+                ("README.md", HotspotMeasureDto::LinesOfCode, 3),
+            ]
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+}
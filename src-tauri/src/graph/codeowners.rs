@@ -0,0 +1,520 @@
+//! CODEOWNERS ownership: rules from the first CODEOWNERS file GitHub would
+//! read, resolved per file relation node with the last matching rule
+//! winning, and the imports or calls that cross owner boundaries.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::query::segments_match;
+use super::relations::{FileRelationEdgeKind, FileRelationGraphSnapshotDto, FileRelationNodeKind};
+use crate::source::git::git_output;
+use crate::source::ValidatedRepoPath;
+
+/// Locations GitHub reads CODEOWNERS from, in lookup order.
+pub const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// The CODEOWNERS rule that decided a node's owners.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeOwnershipDto {
+    /// Empty when the matching rule lists no owners, which leaves the path
+    /// explicitly unowned.
+    pub owners: Vec<String>,
+    pub pattern: String,
+    /// CODEOWNERS file holding the rule, relative to the repository top
+    /// level.
+    pub source_path: String,
+    /// 1-based line of the rule.
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossOwnerReportDto {
+    /// Highest weight first.
+    pub edges: Vec<CrossOwnerEdgeDto>,
+}
+
+/// An import or call between files whose owner sets do not overlap.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossOwnerEdgeDto {
+    pub edge_id: String,
+    pub kind: FileRelationEdgeKind,
+    pub from_path: String,
+    pub to_path: String,
+    pub from_owners: Vec<String>,
+    pub to_owners: Vec<String>,
+    pub weight: u32,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CodeOwnersError {
+    #[error("failed to read CODEOWNERS '{path}': {message}")]
+    Read { path: String, message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeOwners {
+    source_path: String,
+    /// Position of the source root inside the repository, such as
+    /// `crates/app/`, prepended to source-relative paths before matching.
+    source_prefix: String,
+    rules: Vec<CodeOwnerRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CodeOwnerRule {
+    pattern: String,
+    owners: Vec<String>,
+    line: u32,
+}
+
+impl CodeOwners {
+    /// Reads the first CODEOWNERS file found in [`CODEOWNERS_PATHS`] under
+    /// the repository top level. A source root outside git is treated as
+    /// the top level.
+    pub fn load_from_repo(
+        source_root: &ValidatedRepoPath,
+    ) -> Result<Option<Self>, CodeOwnersError> {
+        let (toplevel, source_prefix) = match (
+            git_output(source_root, &["rev-parse", "--show-toplevel"]),
+            git_output(source_root, &["rev-parse", "--show-prefix"]),
+        ) {
+            (Ok(toplevel), Ok(prefix)) => (PathBuf::from(toplevel), prefix),
+            _ => (source_root.as_path().to_path_buf(), String::new()),
+        };
+        let Some(source_path) = CODEOWNERS_PATHS
+            .into_iter()
+            .find(|path| toplevel.join(path).is_file())
+        else {
+            return Ok(None);
+        };
+
+        let contents = fs::read_to_string(toplevel.join(source_path)).map_err(|error| {
+            CodeOwnersError::Read {
+                path: source_path.to_string(),
+                message: error.to_string(),
+            }
+        })?;
+        let mut code_owners = Self::parse(source_path, &contents);
+        code_owners.source_prefix = source_prefix;
+        Ok(Some(code_owners))
+    }
+
+    /// Parses `pattern owner...` lines. Blank lines and `#` comments are
+    /// skipped, and a `#` token ends the owner list.
+    pub fn parse(source_path: &str, contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let mut tokens = line.split_whitespace();
+                let pattern = tokens.next().filter(|token| !token.starts_with('#'))?;
+                Some(CodeOwnerRule {
+                    pattern: pattern.to_string(),
+                    owners: tokens
+                        .take_while(|token| !token.starts_with('#'))
+                        .map(str::to_string)
+                        .collect(),
+                    line: index as u32 + 1,
+                })
+            })
+            .collect();
+
+        Self {
+            source_path: source_path.to_string(),
+            source_prefix: String::new(),
+            rules,
+        }
+    }
+
+    /// Ownership of a path relative to the source root from the last rule
+    /// that matches its repository-relative path.
+    pub fn ownership(&self, path: &str, is_directory: bool) -> Option<CodeOwnershipDto> {
+        let path = format!("{}{path}", self.source_prefix);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| codeowners_pattern_matches(&rule.pattern, &path, is_directory))
+            .map(|rule| CodeOwnershipDto {
+                owners: rule.owners.clone(),
+                pattern: rule.pattern.clone(),
+                source_path: self.source_path.clone(),
+                line: rule.line,
+            })
+    }
+}
+
+/// Stores ownership on file relation graph file and directory nodes.
+pub fn attach_code_owners(snapshot: &mut FileRelationGraphSnapshotDto, code_owners: &CodeOwners) {
+    for node in &mut snapshot.nodes {
+        node.ownership = match node.kind {
+            FileRelationNodeKind::Repo => None,
+            FileRelationNodeKind::Directory => code_owners.ownership(&node.path, true),
+            FileRelationNodeKind::File => code_owners.ownership(&node.path, false),
+        };
+    }
+}
+
+/// Imports and calls between owned files that share no owner. Owners are
+/// compared case-insensitively, and files without owners are left out.
+pub fn cross_owner_dependencies(snapshot: &FileRelationGraphSnapshotDto) -> CrossOwnerReportDto {
+    let owned_files = snapshot
+        .nodes
+        .iter()
+        .filter(|node| node.kind == FileRelationNodeKind::File)
+        .filter_map(|node| {
+            let ownership = node.ownership.as_ref()?;
+            (!ownership.owners.is_empty()).then_some((node.id.as_str(), (node, ownership)))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut edges = snapshot
+        .edges
+        .iter()
+        .filter(|edge| {
+            matches!(
+                edge.kind,
+                FileRelationEdgeKind::Imports | FileRelationEdgeKind::Calls
+            )
+        })
+        .filter_map(|edge| {
+            let (from_node, from_ownership) = owned_files.get(edge.from_node_id.as_str())?;
+            let (to_node, to_ownership) = owned_files.get(edge.to_node_id.as_str())?;
+            let from_owners = lowercase_owners(&from_ownership.owners);
+            let to_owners = lowercase_owners(&to_ownership.owners);
+            from_owners
+                .is_disjoint(&to_owners)
+                .then(|| CrossOwnerEdgeDto {
+                    edge_id: edge.id.clone(),
+                    kind: edge.kind.clone(),
+                    from_path: from_node.path.clone(),
+                    to_path: to_node.path.clone(),
+                    from_owners: from_ownership.owners.clone(),
+                    to_owners: to_ownership.owners.clone(),
+                    weight: edge.weight,
+                })
+        })
+        .collect::<Vec<_>>();
+    edges.sort_by(|left, right| {
+        right
+            .weight
+            .cmp(&left.weight)
+            .then_with(|| left.from_path.cmp(&right.from_path))
+            .then_with(|| left.to_path.cmp(&right.to_path))
+            .then_with(|| left.edge_id.cmp(&right.edge_id))
+    });
+
+    CrossOwnerReportDto { edges }
+}
+
+fn lowercase_owners(owners: &[String]) -> BTreeSet<String> {
+    owners.iter().map(|owner| owner.to_lowercase()).collect()
+}
+
+/// Matches gitignore-style CODEOWNERS patterns. A leading or inner `/`
+/// anchors the pattern to the repository top level, otherwise it matches at any
+/// depth; a trailing `/` matches directories only. `*` and `?` match within
+/// one segment and `**` matches any number of segments. A pattern that
+/// matches a directory also covers everything under it, except that a
+/// trailing `/*` matches direct children only.
+fn codeowners_pattern_matches(pattern: &str, path: &str, is_directory: bool) -> bool {
+    let directory_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let mut pattern_segments = pattern.split('/').collect::<Vec<_>>();
+    if !anchored {
+        pattern_segments.insert(0, "**");
+    }
+    let covers_descendants = pattern_segments.last() != Some(&"*");
+    let path_segments = path.split('/').collect::<Vec<_>>();
+
+    (1..=path_segments.len()).any(|length| {
+        let is_ancestor = length < path_segments.len();
+        if is_ancestor && !covers_descendants {
+            return false;
+        }
+        (is_ancestor || is_directory || !directory_only)
+            && segments_match(&pattern_segments, &path_segments[..length])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attach_code_owners, cross_owner_dependencies, CodeOwners, CODEOWNERS_PATHS};
+    use crate::graph::relations::{
+        FileRelationDirectionDto, FileRelationEdgeDto, FileRelationEdgeKind,
+        FileRelationGraphSnapshotDto, FileRelationNodeDto, FileRelationNodeKind,
+        FILE_RELATION_GRAPH_SCHEMA_VERSION,
+    };
+    use crate::graph::{EdgeConfidenceDto, EdgeProvenanceDto};
+    use crate::source::ValidatedRepoPath;
+    use std::fs;
+    use std::process::Command;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*       @org/core
+
+/src/ui/        @org/frontend   # web client
+*.md            @org/docs
+docs/*          @org/docs @ana
+/src/vendor/
+**/generated    @org/tooling
+";
+
+    fn owners_of(code_owners: &CodeOwners, path: &str, is_directory: bool) -> Vec<String> {
+        code_owners
+            .ownership(path, is_directory)
+            .map(|ownership| ownership.owners)
+            .unwrap_or_default()
+    }
+
+    fn node(kind: FileRelationNodeKind, path: &str) -> FileRelationNodeDto {
+        FileRelationNodeDto {
+            id: format!("{path}-id"),
+            kind,
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.to_string(),
+            parent_id: None,
+            child_ids: Vec::new(),
+            language: None,
+            churn: None,
+            ownership: None,
+        }
+    }
+
+    fn edge(kind: FileRelationEdgeKind, from: &str, to: &str, weight: u32) -> FileRelationEdgeDto {
+        FileRelationEdgeDto {
+            id: format!("{from}->{to}"),
+            kind,
+            from_node_id: format!("{from}-id"),
+            to_node_id: format!("{to}-id"),
+            weight,
+            direction: FileRelationDirectionDto::Directed,
+            confidence: EdgeConfidenceDto::Exact,
+            provenance: EdgeProvenanceDto::Syn,
+            evidence_count: 0,
+            evidence_sample: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resolves_owners_with_the_last_matching_rule() {
+        let code_owners = CodeOwners::parse(".github/CODEOWNERS", CODEOWNERS);
+
+        assert_eq!(owners_of(&code_owners, "src/lib.rs", false), ["@org/core"]);
+        assert_eq!(
+            owners_of(&code_owners, "src/ui/app/view.rs", false),
+            ["@org/frontend"]
+        );
+        assert_eq!(owners_of(&code_owners, "src/ui", true), ["@org/frontend"]);
+        assert_eq!(
+            owners_of(&code_owners, "src/ui/README.md", false),
+            ["@org/docs"]
+        );
+        assert_eq!(
+            owners_of(&code_owners, "docs/guide.rs", false),
+            ["@org/docs", "@ana"]
+        );
+        assert_eq!(
+            owners_of(&code_owners, "docs/api/index.rs", false),
+            ["@org/core"]
+        );
+        assert!(owners_of(&code_owners, "src/vendor/dep.rs", false).is_empty());
+        assert_eq!(owners_of(&code_owners, "src/vendor", false), ["@org/core"]);
+        assert_eq!(
+            owners_of(&code_owners, "crates/a/generated/types.rs", false),
+            ["@org/tooling"]
+        );
+
+        let ownership = code_owners
+            .ownership("src/ui/app/view.rs", false)
+            .expect("ui ownership");
+        assert_eq!(
+            (
+                ownership.pattern.as_str(),
+                ownership.source_path.as_str(),
+                ownership.line
+            ),
+            ("/src/ui/", ".github/CODEOWNERS", 4)
+        );
+    }
+
+    #[test]
+    fn loads_codeowners_in_github_lookup_order() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        let repo = std::env::temp_dir().join(format!(
+            "whip-docs-codeowners-{}-{timestamp}",
+            std::process::id()
+        ));
+        fs::create_dir_all(repo.join(".github")).expect("create fixture .github");
+        fs::create_dir_all(repo.join("docs")).expect("create fixture docs");
+        let source_root =
+            ValidatedRepoPath::parse_existing_source_root(&repo).expect("valid fixture root");
+        assert_eq!(
+            CodeOwners::load_from_repo(&source_root).expect("no codeowners"),
+            None
+        );
+
+        fs::write(repo.join("docs/CODEOWNERS"), "* @docs\n").expect("write docs codeowners");
+        fs::write(repo.join("CODEOWNERS"), "* @root\n").expect("write root codeowners");
+        let code_owners = CodeOwners::load_from_repo(&source_root)
+            .expect("read codeowners")
+            .expect("codeowners present");
+        assert_eq!(
+            code_owners
+                .ownership("src/lib.rs", false)
+                .map(|ownership| (ownership.source_path, ownership.owners)),
+            Some((CODEOWNERS_PATHS[1].to_string(), vec!["@root".to_string()]))
+        );
+
+        fs::write(repo.join(".github/CODEOWNERS"), "* @github\n").expect("write codeowners");
+        let code_owners = CodeOwners::load_from_repo(&source_root)
+            .expect("read codeowners")
+            .expect("codeowners present");
+        assert_eq!(owners_of(&code_owners, "src/lib.rs", false), ["@github"]);
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn matches_workspace_member_paths_against_the_repository_top_level() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        let repo = std::env::temp_dir().join(format!(
+            "whip-docs-codeowners-member-{}-{timestamp}",
+            std::process::id()
+        ));
+        fs::create_dir_all(repo.join(".github")).expect("create fixture .github");
+        fs::create_dir_all(repo.join("crates/app/src")).expect("create fixture member");
+        fs::write(
+            repo.join(".github/CODEOWNERS"),
+            "* @org/core\n/crates/app/src/ @org/app\n",
+        )
+        .expect("write codeowners");
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["init", "--quiet"])
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git init");
+
+        let source_root = ValidatedRepoPath::parse_existing_source_root(repo.join("crates/app"))
+            .expect("valid fixture member");
+        let code_owners = CodeOwners::load_from_repo(&source_root)
+            .expect("read codeowners")
+            .expect("codeowners found at the top level");
+        assert_eq!(owners_of(&code_owners, "src/lib.rs", false), ["@org/app"]);
+        assert_eq!(owners_of(&code_owners, "src", true), ["@org/app"]);
+        assert_eq!(owners_of(&code_owners, "Cargo.toml", false), ["@org/core"]);
+        assert_eq!(
+            code_owners
+                .ownership("src/lib.rs", false)
+                .map(|ownership| ownership.source_path),
+            Some(CODEOWNERS_PATHS[0].to_string())
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn reports_imports_and_calls_between_disjoint_owners() {
+        let code_owners = CodeOwners::parse(
+            "CODEOWNERS",
+            "* @org/core\n/ui/ @org/Frontend\n/shared/ @org/frontend @org/core\n/vendor/\n",
+        );
+        let mut snapshot = FileRelationGraphSnapshotDto {
+            schema_version: FILE_RELATION_GRAPH_SCHEMA_VERSION,
+            source_root: "/repo".to_string(),
+            generated_at: "unix:1".to_string(),
+            root_node_id: "repo:.".to_string(),
+            nodes: vec![
+                node(FileRelationNodeKind::Repo, ""),
+                node(FileRelationNodeKind::Directory, "ui"),
+                node(FileRelationNodeKind::File, "ui/app.rs"),
+                node(FileRelationNodeKind::File, "core/lib.rs"),
+                node(FileRelationNodeKind::File, "core/model.rs"),
+                node(FileRelationNodeKind::File, "shared/util.rs"),
+                node(FileRelationNodeKind::File, "vendor/dep.rs"),
+            ],
+            edges: vec![
+                edge(FileRelationEdgeKind::Imports, "ui/app.rs", "core/lib.rs", 1),
+                edge(FileRelationEdgeKind::Calls, "ui/app.rs", "core/lib.rs", 4),
+                edge(
+                    FileRelationEdgeKind::Calls,
+                    "core/lib.rs",
+                    "core/model.rs",
+                    2,
+                ),
+                edge(
+                    FileRelationEdgeKind::Calls,
+                    "ui/app.rs",
+                    "shared/util.rs",
+                    3,
+                ),
+                edge(
+                    FileRelationEdgeKind::Calls,
+                    "core/lib.rs",
+                    "vendor/dep.rs",
+                    5,
+                ),
+                edge(
+                    FileRelationEdgeKind::CoChanges,
+                    "core/lib.rs",
+                    "ui/app.rs",
+                    9,
+                ),
+                edge(FileRelationEdgeKind::Contains, "ui", "ui/app.rs", 1),
+            ],
+            analyzers: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+
+        attach_code_owners(&mut snapshot, &code_owners);
+        assert_eq!(snapshot.nodes[0].ownership, None);
+        assert_eq!(
+            snapshot.nodes[1]
+                .ownership
+                .as_ref()
+                .map(|ownership| ownership.owners.clone()),
+            Some(vec!["@org/Frontend".to_string()])
+        );
+
+        let report = cross_owner_dependencies(&snapshot);
+        assert_eq!(
+            report
+                .edges
+                .iter()
+                .map(|edge| (
+                    edge.kind.clone(),
+                    edge.from_path.as_str(),
+                    edge.to_path.as_str(),
+                    edge.weight
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (FileRelationEdgeKind::Calls, "ui/app.rs", "core/lib.rs", 4),
+                (FileRelationEdgeKind::Imports, "ui/app.rs", "core/lib.rs", 1),
+            ]
+        );
+        assert_eq!(report.edges[0].from_owners, ["@org/Frontend"]);
+        assert_eq!(report.edges[0].to_owners, ["@org/core"]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Concurrency topology: spawned tasks, sync primitives, and the functions
+//! and types that spawn, send to, receive from, lock, or own them.
+
+use std::collections::BTreeSet;
+
+use super::{GraphEdgeDto, GraphEdgeKind, GraphNodeKind, GraphSnapshotDto};
+
+/// Reduced snapshot with every `Task` and `SyncPrimitive` node, the nodes on
+/// the other end of their `Spawns`, `Sends`, `Receives`, `Locks`, atomic
+/// `References`, and owning `Defines` edges, and those edges.
+pub fn concurrency_topology(snapshot: &GraphSnapshotDto) -> GraphSnapshotDto {
+    let concurrency_ids = snapshot
+        .nodes
+        .iter()
+        .filter(|node| {
+            matches!(
+                node.kind,
+                GraphNodeKind::Task | GraphNodeKind::SyncPrimitive
+            )
+        })
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    let is_topology_edge = |edge: &GraphEdgeDto| match edge.kind {
+        GraphEdgeKind::Spawns
+        | GraphEdgeKind::Sends
+        | GraphEdgeKind::Receives
+        | GraphEdgeKind::Locks => true,
+        GraphEdgeKind::References | GraphEdgeKind::Defines => {
+            concurrency_ids.contains(edge.target_id.as_str())
+        }
+        _ => false,
+    };
+
+    let edges = snapshot
+        .edges
+        .iter()
+        .filter(|edge| is_topology_edge(edge))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut node_ids = concurrency_ids.clone();
+    for edge in &edges {
+        node_ids.insert(edge.source_id.as_str());
+        node_ids.insert(edge.target_id.as_str());
+    }
+
+    GraphSnapshotDto {
+        schema_version: snapshot.schema_version,
+        source_root: snapshot.source_root.clone(),
+        generated_at: snapshot.generated_at.clone(),
+        nodes: snapshot
+            .nodes
+            .iter()
+            .filter(|node| node_ids.contains(node.id.as_str()))
+            .cloned()
+            .collect(),
+        edges,
+        diagnostics: Vec::new(),
+        configuration: snapshot.configuration.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::concurrency_topology;
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, GRAPH_SCHEMA_VERSION,
+    };
+
+    fn node(kind: GraphNodeKind, label: &str) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &["fixture", label]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn edge(kind: GraphEdgeKind, source: &GraphNodeDto, target: &GraphNodeDto) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: stable_edge_id(kind.clone(), &source.id, &target.id),
+            kind,
+            source_id: source.id.clone(),
+            target_id: target.id.clone(),
+            provenance: EdgeProvenanceDto::Syn,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    #[test]
+    fn keeps_tasks_primitives_and_their_immediate_partners() {
+        let file = node(GraphNodeKind::File, "src/state.rs");
+        let state = node(GraphNodeKind::Struct, "AppState");
+        let config = node(GraphNodeKind::SyncPrimitive, "AppState.config");
+        let running = node(GraphNodeKind::SyncPrimitive, "AppState.running");
+        let start = node(GraphNodeKind::Method, "start");
+        let task = node(GraphNodeKind::Task, "tokio::spawn in AppState::start");
+        let helper = node(GraphNodeKind::Function, "helper");
+        let snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            edges: vec![
+                edge(GraphEdgeKind::Defines, &file, &state),
+                edge(GraphEdgeKind::Defines, &state, &config),
+                edge(GraphEdgeKind::Defines, &state, &running),
+                edge(GraphEdgeKind::Spawns, &start, &task),
+                edge(GraphEdgeKind::Locks, &task, &config),
+                edge(GraphEdgeKind::References, &task, &running),
+                edge(GraphEdgeKind::Calls, &start, &helper),
+            ],
+            nodes: vec![
+                file,
+                state.clone(),
+                config.clone(),
+                running.clone(),
+                start.clone(),
+                task.clone(),
+                helper,
+            ],
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        let topology = concurrency_topology(&snapshot);
+
+        let labels = topology
+            .nodes
+            .iter()
+            .map(|node| node.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "AppState",
+                "AppState.config",
+                "AppState.running",
+                "start",
+                "tokio::spawn in AppState::start"
+            ]
+        );
+        let kinds = topology
+            .edges
+            .iter()
+            .map(|edge| edge.kind.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                GraphEdgeKind::Defines,
+                GraphEdgeKind::Defines,
+                GraphEdgeKind::Spawns,
+                GraphEdgeKind::Locks,
+                GraphEdgeKind::References,
+            ]
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! Line coverage imported from lcov, Cobertura XML, or `cargo llvm-cov`
+//! JSON reports, overlaid on file, function, and method nodes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::metrics::NodeMetricsDto;
+use super::{GraphNodeKind, GraphSnapshotDto, SourceRangeDto};
+use crate::source::ValidatedRepoPath;
+
+/// Instrumented and executed lines of a file, function, or method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageMetricsDto {
+    pub covered_lines: u32,
+    pub total_lines: u32,
+    /// `covered / total`, or `0` when no line is instrumented.
+    pub ratio: f64,
+}
+
+impl CoverageMetricsDto {
+    fn from_counts(covered_lines: u32, total_lines: u32) -> Self {
+        Self {
+            covered_lines,
+            total_lines,
+            ratio: if total_lines == 0 {
+                0.0
+            } else {
+                f64::from(covered_lines) / f64::from(total_lines)
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageFormatDto {
+    Lcov,
+    Cobertura,
+    LlvmCovJson,
+}
+
+/// Outcome of overlaying a coverage report on the current snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageImportDto {
+    pub report_path: String,
+    pub format: CoverageFormatDto,
+    pub matched_files: u32,
+    /// Report paths that match no file node, such as dependency sources.
+    pub unmatched_files: Vec<String>,
+    pub totals: CoverageMetricsDto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UncoveredFunctionsReportDto {
+    /// Sorted by source path and line.
+    pub functions: Vec<UncoveredFunctionDto>,
+}
+
+/// A public function or method with instrumented lines, none of them hit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UncoveredFunctionDto {
+    pub node_id: String,
+    pub kind: GraphNodeKind,
+    pub label: String,
+    pub public_paths: Vec<String>,
+    pub range: SourceRangeDto,
+    pub total_lines: u32,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CoverageError {
+    #[error("failed to read coverage report '{path}': {message}")]
+    Read { path: String, message: String },
+    #[error("coverage report is not lcov, Cobertura XML, or llvm-cov JSON")]
+    UnknownFormat,
+    #[error("failed to parse {format:?} coverage report: {message}")]
+    Parse {
+        format: CoverageFormatDto,
+        message: String,
+    },
+}
+
+/// Execution count of every instrumented line, keyed by report file path
+/// made relative to the source root where possible.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineCoverage {
+    files: BTreeMap<String, BTreeMap<u32, u64>>,
+}
+
+impl LineCoverage {
+    /// Reads a report at a path inside the repository and detects its
+    /// format from the contents.
+    pub fn load(
+        source_root: &ValidatedRepoPath,
+        report_path: &Path,
+    ) -> Result<(CoverageFormatDto, Self), CoverageError> {
+        let read_error = |message: String| CoverageError::Read {
+            path: report_path.to_string_lossy().into_owned(),
+            message,
+        };
+        let resolved = source_root
+            .resolve_existing_child(report_path)
+            .map_err(|error| read_error(error.to_string()))?;
+        let contents =
+            fs::read_to_string(resolved).map_err(|error| read_error(error.to_string()))?;
+        Self::parse(&contents, source_root.as_path())
+    }
+
+    pub fn parse(
+        contents: &str,
+        source_root: &Path,
+    ) -> Result<(CoverageFormatDto, Self), CoverageError> {
+        let trimmed = contents.trim_start();
+        let (format, files) = if trimmed.starts_with('{') {
+            (
+                CoverageFormatDto::LlvmCovJson,
+                parse_llvm_cov_json(contents)?,
+            )
+        } else if trimmed.starts_with('<') {
+            (CoverageFormatDto::Cobertura, parse_cobertura(contents)?)
+        } else if contents.lines().any(|line| line.starts_with("SF:")) {
+            (CoverageFormatDto::Lcov, parse_lcov(contents)?)
+        } else {
+            return Err(CoverageError::UnknownFormat);
+        };
+
+        let mut coverage = Self::default();
+        for (path, lines) in files {
+            let file = coverage
+                .files
+                .entry(relative_report_path(&path, source_root))
+                .or_default();
+            for (line, hits) in lines {
+                let entry = file.entry(line).or_default();
+                *entry = (*entry).max(hits);
+            }
+        }
+        Ok((format, coverage))
+    }
+}
+
+/// Stores coverage metrics on file, function, and method nodes. Report
+/// paths match file nodes exactly, or by a unique path suffix for reports
+/// written relative to a member crate.
+pub fn attach_coverage(
+    snapshot: &mut GraphSnapshotDto,
+    coverage: &LineCoverage,
+    report_path: &str,
+    format: CoverageFormatDto,
+) -> CoverageImportDto {
+    let file_paths = snapshot
+        .nodes
+        .iter()
+        .filter(|node| node.kind == GraphNodeKind::File)
+        .filter_map(|node| Some(node.source_range.as_ref()?.path.as_str()))
+        .collect::<BTreeSet<_>>();
+    let mut lines_by_path = BTreeMap::<String, &BTreeMap<u32, u64>>::new();
+    let mut unmatched_files = Vec::new();
+    for (report_file, lines) in &coverage.files {
+        let suffix = format!("/{report_file}");
+        let matched = if file_paths.contains(report_file.as_str()) {
+            Some(report_file.as_str())
+        } else {
+            let candidates = file_paths
+                .iter()
+                .filter(|path| path.ends_with(&suffix))
+                .collect::<Vec<_>>();
+            match candidates.as_slice() {
+                [path] => Some(**path),
+                _ => None,
+            }
+        };
+        match matched {
+            Some(path) => {
+                lines_by_path.insert(path.to_string(), lines);
+            }
+            None => unmatched_files.push(report_file.clone()),
+        }
+    }
+
+    let mut totals = (0, 0);
+    for node in &mut snapshot.nodes {
+        let Some(range) = &node.source_range else {
+            continue;
+        };
+        let Some(lines) = lines_by_path.get(&range.path) else {
+            continue;
+        };
+        let (covered, total) = match node.kind {
+            GraphNodeKind::File => {
+                let counts = line_counts(lines.iter());
+                totals = (totals.0 + counts.0, totals.1 + counts.1);
+                counts
+            }
+            GraphNodeKind::Function | GraphNodeKind::Method => {
+                line_counts(lines.range(range.start_line..=range.end_line))
+            }
+            _ => continue,
+        };
+        node.metrics
+            .get_or_insert_with(NodeMetricsDto::default)
+            .coverage = Some(CoverageMetricsDto::from_counts(covered, total));
+    }
+
+    CoverageImportDto {
+        report_path: report_path.to_string(),
+        format,
+        matched_files: lines_by_path.len() as u32,
+        unmatched_files,
+        totals: CoverageMetricsDto::from_counts(totals.0, totals.1),
+    }
+}
+
+/// Public functions and methods whose instrumented lines were never hit.
+/// Nodes without coverage metrics or instrumented lines are not listed.
+pub fn uncovered_public_functions(snapshot: &GraphSnapshotDto) -> UncoveredFunctionsReportDto {
+    let mut functions = snapshot
+        .nodes
+        .iter()
+        .filter(|node| {
+            matches!(node.kind, GraphNodeKind::Function | GraphNodeKind::Method)
+                && !node.public_paths.is_empty()
+        })
+        .filter_map(|node| {
+            let coverage = node.metrics.as_ref()?.coverage.as_ref()?;
+            if coverage.total_lines == 0 || coverage.covered_lines > 0 {
+                return None;
+            }
+            Some(UncoveredFunctionDto {
+                node_id: node.id.clone(),
+                kind: node.kind.clone(),
+                label: node.label.clone(),
+                public_paths: node.public_paths.clone(),
+                range: node.source_range.clone()?,
+                total_lines: coverage.total_lines,
+            })
+        })
+        .collect::<Vec<_>>();
+    functions.sort_by(|left, right| {
+        (&left.range.path, left.range.start_line).cmp(&(&right.range.path, right.range.start_line))
+    });
+
+    UncoveredFunctionsReportDto { functions }
+}
+
+fn line_counts<'a>(lines: impl Iterator<Item = (&'a u32, &'a u64)>) -> (u32, u32) {
+    lines.fold((0, 0), |(covered, total), (_, hits)| {
+        (covered + u32::from(*hits > 0), total + 1)
+    })
+}
+
+type ReportLines = Vec<(String, BTreeMap<u32, u64>)>;
+
+/// `SF:` file records with `DA:line,hits` entries.
+fn parse_lcov(contents: &str) -> Result<ReportLines, CoverageError> {
+    let parse_error = |message: String| CoverageError::Parse {
+        format: CoverageFormatDto::Lcov,
+        message,
+    };
+    let mut files = Vec::new();
+    let mut current: Option<(String, BTreeMap<u32, u64>)> = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            files.extend(current.take());
+            current = Some((path.to_string(), BTreeMap::new()));
+        } else if let Some(entry) = line.strip_prefix("DA:") {
+            let (_, lines) = current
+                .as_mut()
+                .ok_or_else(|| parse_error("DA record outside an SF record".to_string()))?;
+            let mut fields = entry.split(',');
+            let (Some(number), Some(hits)) = (fields.next(), fields.next()) else {
+                return Err(parse_error(format!("malformed DA record '{entry}'")));
+            };
+            let number = number
+                .parse::<u32>()
+                .map_err(|error| parse_error(format!("line number '{number}': {error}")))?;
+            // Some generators write negative or fractional counts for
+            // unreachable lines; anything that is not a count is a miss.
+            let hits = hits.parse::<u64>().unwrap_or_default();
+            lines.insert(number, hits);
+        } else if line == "end_of_record" {
+            files.extend(current.take());
+        }
+    }
+    files.extend(current);
+    Ok(files)
+}
+
+/// `<class filename="..">` elements with `<line number=".." hits="..">`
+/// children, resolved against the report's `<source>` directories.
+fn parse_cobertura(contents: &str) -> Result<ReportLines, CoverageError> {
+    let parse_error = |message: String| CoverageError::Parse {
+        format: CoverageFormatDto::Cobertura,
+        message,
+    };
+    let mut sources = Vec::new();
+    let mut files = BTreeMap::<String, BTreeMap<u32, u64>>::new();
+    let mut current_file = None;
+    for piece in contents.split('<').skip(1) {
+        let (tag, text) = piece
+            .split_once('>')
+            .ok_or_else(|| parse_error("unterminated tag".to_string()))?;
+        let name = tag
+            .split(|character: char| character.is_whitespace() || character == '/')
+            .next()
+            .unwrap_or_default();
+        match (name, tag.starts_with('/')) {
+            ("source", false) => sources.push(unescape_xml(text.trim())),
+            ("class", false) => current_file = xml_attribute(tag, "filename"),
+            ("", true) if tag == "/class" => current_file = None,
+            ("line", false) => {
+                let Some(file) = &current_file else {
+                    continue;
+                };
+                let number = xml_attribute(tag, "number")
+                    .and_then(|number| number.parse::<u32>().ok())
+                    .ok_or_else(|| parse_error(format!("line without a number in '{file}'")))?;
+                let hits = xml_attribute(tag, "hits")
+                    .and_then(|hits| hits.parse::<u64>().ok())
+                    .unwrap_or_default();
+                let entry = files
+                    .entry(file.clone())
+                    .or_default()
+                    .entry(number)
+                    .or_default();
+                *entry = (*entry).max(hits);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(files
+        .into_iter()
+        .map(|(file, lines)| {
+            let resolved = sources
+                .iter()
+                .map(|source| Path::new(source).join(&file))
+                .find(|path| path.is_file())
+                .map_or(file, |path| path.to_string_lossy().into_owned());
+            (resolved, lines)
+        })
+        .collect())
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!(" {name}=\""))? + name.len() + 3;
+    let end = start + tag[start..].find('"')?;
+    Some(unescape_xml(&tag[start..end]))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// `cargo llvm-cov --json` export. Line counts are derived from region
+/// segments the way `llvm-cov` computes its line view: a line is
+/// instrumented when a counted region starts on it or a counted region
+/// spans into it, and its count is the largest of those regions.
+fn parse_llvm_cov_json(contents: &str) -> Result<ReportLines, CoverageError> {
+    let parse_error = |message: String| CoverageError::Parse {
+        format: CoverageFormatDto::LlvmCovJson,
+        message,
+    };
+    let export: Value =
+        serde_json::from_str(contents).map_err(|error| parse_error(error.to_string()))?;
+    let data = export["data"]
+        .as_array()
+        .ok_or_else(|| parse_error("missing data array".to_string()))?;
+
+    let mut files = Vec::new();
+    for file in data
+        .iter()
+        .filter_map(|entry| entry["files"].as_array())
+        .flatten()
+    {
+        let filename = file["filename"]
+            .as_str()
+            .ok_or_else(|| parse_error("file without a filename".to_string()))?;
+        let segments = file["segments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Segment::from_json)
+            .collect::<Vec<_>>();
+        files.push((filename.to_string(), segment_line_counts(&segments)));
+    }
+    Ok(files)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    line: u32,
+    count: u64,
+    has_count: bool,
+    is_region_entry: bool,
+    is_gap_region: bool,
+}
+
+impl Segment {
+    /// `[line, column, count, has_count, is_region_entry, is_gap_region?]`.
+    fn from_json(value: &Value) -> Option<Self> {
+        let fields = value.as_array()?;
+        Some(Self {
+            line: u32::try_from(fields.first()?.as_u64()?).ok()?,
+            count: fields.get(2)?.as_u64()?,
+            has_count: fields.get(3)?.as_bool()?,
+            is_region_entry: fields.get(4)?.as_bool()?,
+            is_gap_region: fields.get(5).and_then(Value::as_bool).unwrap_or(false),
+        })
+    }
+
+    fn starts_region(&self) -> bool {
+        !self.is_gap_region && self.has_count && self.is_region_entry
+    }
+}
+
+fn segment_line_counts(segments: &[Segment]) -> BTreeMap<u32, u64> {
+    let mut lines = BTreeMap::new();
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return lines;
+    };
+    let mut wrapped: Option<&Segment> = None;
+    let mut next = 0;
+    for line in first.line..=last.line {
+        let start = next;
+        while next < segments.len() && segments[next].line == line {
+            next += 1;
+        }
+        let line_segments = &segments[start..next];
+        let region_starts = line_segments
+            .iter()
+            .filter(|segment| segment.starts_region())
+            .collect::<Vec<_>>();
+        let starts_skipped = line_segments
+            .first()
+            .is_some_and(|segment| !segment.has_count && segment.is_region_entry);
+        let mapped = !starts_skipped
+            && (wrapped.is_some_and(|segment| segment.has_count) || !region_starts.is_empty());
+        if mapped {
+            let count = wrapped
+                .map(|segment| segment.count)
+                .into_iter()
+                .chain(region_starts.iter().map(|segment| segment.count))
+                .max()
+                .unwrap_or_default();
+            lines.insert(line, count);
+        }
+        if let Some(segment) = line_segments.last() {
+            wrapped = Some(segment);
+        }
+    }
+    lines
+}
+
+/// Strips the source root from absolute report paths and `./` from
+/// relative ones.
+fn relative_report_path(path: &str, source_root: &Path) -> String {
+    let path = Path::new(path);
+    let relative = if path.is_absolute() {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        match canonical.strip_prefix(source_root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => return path.to_string_lossy().replace('\\', "/"),
+        }
+    } else {
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect::<PathBuf>()
+    };
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        attach_coverage, uncovered_public_functions, CoverageFormatDto, CoverageMetricsDto,
+        LineCoverage,
+    };
+    use crate::graph::{
+        stable_node_id, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+    use std::path::Path;
+
+    fn node(kind: GraphNodeKind, label: &str, lines: (u32, u32), public: bool) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &["fixture", label]),
+            kind,
+            label: label.to_string(),
+            source_range: Some(SourceRangeDto {
+                path: "crates/app/src/lib.rs".to_string(),
+                start_line: lines.0,
+                start_column: 1,
+                end_line: lines.1,
+                end_column: 1,
+            }),
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: if public {
+                vec![format!("app::{label}")]
+            } else {
+                Vec::new()
+            },
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn snapshot() -> GraphSnapshotDto {
+        GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/repo".to_string(),
+            generated_at: "test".to_string(),
+            nodes: vec![
+                node(GraphNodeKind::File, "crates/app/src/lib.rs", (1, 1), false),
+                node(GraphNodeKind::Function, "load", (1, 4), true),
+                node(GraphNodeKind::Function, "save", (6, 9), true),
+                node(GraphNodeKind::Method, "helper", (11, 12), false),
+            ],
+            edges: Vec::new(),
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    fn coverage_of(snapshot: &GraphSnapshotDto, label: &str) -> Option<CoverageMetricsDto> {
+        snapshot
+            .nodes
+            .iter()
+            .find(|node| node.label == label)?
+            .metrics
+            .as_ref()?
+            .coverage
+            .clone()
+    }
+
+    #[test]
+    fn parses_lcov_cobertura_and_llvm_cov_json_into_the_same_lines() {
+        let root = Path::new("/repo/crates/app");
+        let lcov = "TN:\nSF:/repo/crates/app/src/lib.rs\nDA:2,1\nDA:3,0\nDA:7,0\nend_of_record\n";
+        let cobertura = r#"<?xml version="1.0"?>
+<coverage><sources><source>/missing</source></sources><packages><package><classes>
+<class name="lib" filename="./src/lib.rs"><lines>
+<line number="2" hits="1"/><line number="3" hits="0"/><line number="7" hits="0"/>
+</lines></class></classes></package></packages></coverage>"#;
+        let llvm_cov = r#"{ "type": "llvm.coverage.json.export", "data": [{ "files": [{
+            "filename": "/repo/crates/app/src/lib.rs",
+            "segments": [[2, 1, 1, true, true], [2, 20, 0, false, false],
+                         [3, 5, 0, true, true], [3, 9, 0, false, false],
+                         [7, 1, 0, true, true], [7, 9, 0, false, false]]
+        }] }] }"#;
+
+        let parsed = [lcov, cobertura, llvm_cov]
+            .map(|contents| LineCoverage::parse(contents, root).expect("parse coverage report"));
+
+        assert_eq!(parsed[0].0, CoverageFormatDto::Lcov);
+        assert_eq!(parsed[1].0, CoverageFormatDto::Cobertura);
+        assert_eq!(parsed[2].0, CoverageFormatDto::LlvmCovJson);
+        assert_eq!(parsed[0].1, parsed[1].1);
+        assert_eq!(parsed[0].1, parsed[2].1);
+        assert_eq!(
+            parsed[0].1.files.keys().collect::<Vec<_>>(),
+            vec!["src/lib.rs"]
+        );
+        assert!(LineCoverage::parse("not coverage", root).is_err());
+    }
+
+    #[test]
+    fn overlays_line_ratios_and_lists_uncovered_public_functions() {
+        let mut snapshot = snapshot();
+        let (format, coverage) = LineCoverage::parse(
+            "SF:src/lib.rs\nDA:2,4\nDA:3,0\nDA:7,0\nDA:8,0\nDA:11,0\nend_of_record\nSF:/elsewhere/dep.rs\nDA:1,1\nend_of_record\n",
+            Path::new("/repo/crates/app"),
+        )
+        .expect("parse lcov");
+
+        let import = attach_coverage(&mut snapshot, &coverage, "lcov.info", format);
+
+        assert_eq!(import.matched_files, 1);
+        assert_eq!(import.unmatched_files, vec!["/elsewhere/dep.rs"]);
+        assert_eq!(
+            (import.totals.covered_lines, import.totals.total_lines),
+            (1, 5)
+        );
+        assert_eq!(
+            coverage_of(&snapshot, "load"),
+            Some(CoverageMetricsDto {
+                covered_lines: 1,
+                total_lines: 2,
+                ratio: 0.5
+            })
+        );
+        let uncovered = uncovered_public_functions(&snapshot);
+        assert_eq!(
+            uncovered
+                .functions
+                .iter()
+                .map(|function| (function.label.as_str(), function.total_lines))
+                .collect::<Vec<_>>(),
+            vec![("save", 2)]
+        );
+    }
+}
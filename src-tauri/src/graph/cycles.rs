@@ -0,0 +1,573 @@
+//! Dependency cycle detection over file, module, and crate projections of a
+//! graph snapshot.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use super::ownership::NodeOwnership;
+use super::{
+    stable_id, AnalyzerDiagnosticDto, GraphEdgeDto, GraphEdgeKind, GraphNodeDto, GraphSnapshotDto,
+    SourceRangeDto,
+};
+
+pub const DEPENDENCY_CYCLE_CODE: &str = "dependency_cycle";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyCycleGranularity {
+    File,
+    Module,
+    Crate,
+}
+
+impl DependencyCycleGranularity {
+    pub const ALL: [Self; 3] = [Self::File, Self::Module, Self::Crate];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Module => "module",
+            Self::Crate => "crate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyCycleReportDto {
+    pub granularity: DependencyCycleGranularity,
+    pub cycles: Vec<DependencyCycleDto>,
+}
+
+/// One strongly connected component with more than one member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyCycleDto {
+    pub id: String,
+    pub granularity: DependencyCycleGranularity,
+    /// Every member of the component, sorted by node ID.
+    pub member_ids: Vec<String>,
+    pub member_labels: Vec<String>,
+    /// The shortest loop through the first member, starting and ending there.
+    pub cycle_path: Vec<String>,
+    /// One representative graph edge for each hop of `cycle_path`.
+    pub closing_edges: Vec<DependencyCycleEdgeDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyCycleEdgeDto {
+    pub from_member_id: String,
+    pub to_member_id: String,
+    pub edge_id: String,
+    pub kind: GraphEdgeKind,
+    pub evidence: Option<SourceRangeDto>,
+}
+
+/// Projects Imports, Calls, and References edges onto the owning file,
+/// module, or crate and reports every strongly connected component.
+pub fn find_dependency_cycles(
+    snapshot: &GraphSnapshotDto,
+    granularity: DependencyCycleGranularity,
+) -> DependencyCycleReportDto {
+    let ownership = NodeOwnership::from_snapshot(snapshot);
+    let node_by_id = snapshot
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect::<BTreeMap<_, _>>();
+    let dependencies = projected_dependencies(snapshot, &ownership, granularity);
+    let cycles = strongly_connected_components(&dependencies)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|component| {
+            cycle_from_component(
+                component,
+                granularity,
+                &dependencies,
+                &ownership,
+                &node_by_id,
+            )
+        })
+        .collect();
+
+    DependencyCycleReportDto {
+        granularity,
+        cycles,
+    }
+}
+
+/// Reports cycles at every granularity as analysis diagnostics.
+pub fn dependency_cycle_diagnostics(snapshot: &GraphSnapshotDto) -> Vec<AnalyzerDiagnosticDto> {
+    DependencyCycleGranularity::ALL
+        .into_iter()
+        .flat_map(|granularity| find_dependency_cycles(snapshot, granularity).cycles)
+        .map(|cycle| {
+            let label_by_id = cycle
+                .member_ids
+                .iter()
+                .zip(&cycle.member_labels)
+                .collect::<BTreeMap<_, _>>();
+            let path = cycle
+                .cycle_path
+                .iter()
+                .map(|member_id| {
+                    label_by_id
+                        .get(member_id)
+                        .map_or(member_id.as_str(), |label| label.as_str())
+                })
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            let evidence = cycle
+                .closing_edges
+                .iter()
+                .find_map(|edge| edge.evidence.clone());
+
+            AnalyzerDiagnosticDto {
+                code: DEPENDENCY_CYCLE_CODE.to_string(),
+                message: format!("{} dependency cycle: {path}", cycle.granularity.as_str()),
+                source_path: evidence.as_ref().map(|range| range.path.clone()),
+                evidence,
+            }
+        })
+        .collect()
+}
+
+type ProjectedDependencies<'a> = BTreeMap<String, BTreeMap<String, Vec<&'a GraphEdgeDto>>>;
+
+fn projected_dependencies<'a>(
+    snapshot: &'a GraphSnapshotDto,
+    ownership: &NodeOwnership,
+    granularity: DependencyCycleGranularity,
+) -> ProjectedDependencies<'a> {
+    let mut dependencies = ProjectedDependencies::new();
+
+    for edge in &snapshot.edges {
+        if !matches!(
+            edge.kind,
+            GraphEdgeKind::Imports | GraphEdgeKind::Calls | GraphEdgeKind::References
+        ) {
+            continue;
+        }
+
+        let source_crate_id = ownership.crate_id(&edge.source_id);
+        let (Some(source_unit), Some(target_unit)) = (
+            unit_id(ownership, granularity, &edge.source_id, source_crate_id),
+            unit_id(ownership, granularity, &edge.target_id, source_crate_id),
+        ) else {
+            continue;
+        };
+        if source_unit == target_unit {
+            continue;
+        }
+
+        dependencies
+            .entry(source_unit.to_string())
+            .or_default()
+            .entry(target_unit.to_string())
+            .or_default()
+            .push(edge);
+        dependencies.entry(target_unit.to_string()).or_default();
+    }
+
+    dependencies
+}
+
+fn unit_id<'a>(
+    ownership: &'a NodeOwnership,
+    granularity: DependencyCycleGranularity,
+    node_id: &str,
+    fallback_crate_id: Option<&str>,
+) -> Option<&'a str> {
+    match granularity {
+        DependencyCycleGranularity::File => ownership.owning_file_id(node_id, fallback_crate_id),
+        DependencyCycleGranularity::Module => {
+            ownership.owning_module_id(node_id, fallback_crate_id)
+        }
+        DependencyCycleGranularity::Crate => ownership.owning_crate_id(node_id, fallback_crate_id),
+    }
+}
+
+/// Iterative Tarjan SCC. Components come out sorted by their first member.
+fn strongly_connected_components(dependencies: &ProjectedDependencies<'_>) -> Vec<Vec<String>> {
+    let mut index_by_unit = BTreeMap::<&str, usize>::new();
+    let mut low_link = BTreeMap::<&str, usize>::new();
+    let mut on_stack = BTreeSet::<&str>::new();
+    let mut stack = Vec::<&str>::new();
+    let mut components = Vec::new();
+
+    for root in dependencies.keys() {
+        if index_by_unit.contains_key(root.as_str()) {
+            continue;
+        }
+
+        let mut work = vec![(root.as_str(), 0_usize)];
+        while let Some((unit, next_child)) = work.pop() {
+            if next_child == 0 {
+                let index = index_by_unit.len();
+                index_by_unit.insert(unit, index);
+                low_link.insert(unit, index);
+                stack.push(unit);
+                on_stack.insert(unit);
+            }
+
+            let targets = dependencies
+                .get(unit)
+                .map(|targets| targets.keys().map(String::as_str).collect::<Vec<_>>())
+                .unwrap_or_default();
+            if let Some(&target) = targets.get(next_child) {
+                work.push((unit, next_child + 1));
+                if !index_by_unit.contains_key(target) {
+                    work.push((target, 0));
+                } else if on_stack.contains(target) {
+                    let lowest = low_link[unit].min(index_by_unit[target]);
+                    low_link.insert(unit, lowest);
+                }
+                continue;
+            }
+
+            if let Some(&(parent, _)) = work.last() {
+                let lowest = low_link[parent].min(low_link[unit]);
+                low_link.insert(parent, lowest);
+            }
+
+            if low_link[unit] == index_by_unit[unit] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(member);
+                    component.push(member.to_string());
+                    if member == unit {
+                        break;
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+        }
+    }
+
+    components.sort();
+    components
+}
+
+fn cycle_from_component(
+    member_ids: Vec<String>,
+    granularity: DependencyCycleGranularity,
+    dependencies: &ProjectedDependencies<'_>,
+    ownership: &NodeOwnership,
+    node_by_id: &BTreeMap<&str, &GraphNodeDto>,
+) -> DependencyCycleDto {
+    let cycle_path = shortest_cycle_path(&member_ids, dependencies);
+    let closing_edges = cycle_path
+        .windows(2)
+        .filter_map(|hop| {
+            let edges = dependencies.get(&hop[0])?.get(&hop[1])?;
+            let edge = edges
+                .iter()
+                .find(|edge| edge.evidence.is_some())
+                .or_else(|| edges.first())?;
+            Some(DependencyCycleEdgeDto {
+                from_member_id: hop[0].clone(),
+                to_member_id: hop[1].clone(),
+                edge_id: edge.id.clone(),
+                kind: edge.kind.clone(),
+                evidence: edge.evidence.clone(),
+            })
+        })
+        .collect();
+    let member_labels = member_ids
+        .iter()
+        .map(|member_id| match granularity {
+            DependencyCycleGranularity::Module => ownership.module_path(member_id),
+            _ => node_by_id
+                .get(member_id.as_str())
+                .map(|node| node.label.as_str()),
+        })
+        .map(|label| label.unwrap_or_default().to_string())
+        .zip(&member_ids)
+        .map(|(label, member_id)| {
+            if label.is_empty() {
+                member_id.clone()
+            } else {
+                label
+            }
+        })
+        .collect();
+
+    DependencyCycleDto {
+        id: stable_id("cycle", &[granularity.as_str(), member_ids[0].as_str()]),
+        granularity,
+        member_ids,
+        member_labels,
+        cycle_path,
+        closing_edges,
+    }
+}
+
+/// Breadth-first search from the first member back to itself, staying inside
+/// the component.
+fn shortest_cycle_path(
+    member_ids: &[String],
+    dependencies: &ProjectedDependencies<'_>,
+) -> Vec<String> {
+    let members = member_ids
+        .iter()
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>();
+    let start = member_ids[0].as_str();
+    let mut previous = BTreeMap::<&str, &str>::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(unit) = queue.pop_front() {
+        let Some(targets) = dependencies.get(unit) else {
+            continue;
+        };
+        for target in targets.keys().map(String::as_str) {
+            if target == start {
+                let mut path = vec![start.to_string()];
+                let mut current = unit;
+                while current != start {
+                    path.push(current.to_string());
+                    current = previous[current];
+                }
+                path.push(start.to_string());
+                path.reverse();
+                return path;
+            }
+            if members.contains(target) && !previous.contains_key(target) {
+                previous.insert(target, unit);
+                queue.push_back(target);
+            }
+        }
+    }
+
+    member_ids.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dependency_cycle_diagnostics, find_dependency_cycles, DependencyCycleGranularity,
+        DEPENDENCY_CYCLE_CODE,
+    };
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn range(path: &str, line: u32) -> SourceRangeDto {
+        SourceRangeDto {
+            path: path.to_string(),
+            start_line: line,
+            start_column: 1,
+            end_line: line,
+            end_column: 1,
+        }
+    }
+
+    fn node(kind: GraphNodeKind, id_part: &str, label: &str) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &[id_part]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn edge(
+        kind: GraphEdgeKind,
+        source: &GraphNodeDto,
+        target: &GraphNodeDto,
+        provenance: EdgeProvenanceDto,
+        evidence: Option<SourceRangeDto>,
+    ) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: stable_edge_id(kind.clone(), &source.id, &target.id),
+            kind,
+            source_id: source.id.clone(),
+            target_id: target.id.clone(),
+            provenance,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    /// `graph` imports `commands`, which calls back into `graph`; `util` is
+    /// only a dependency and never part of the cycle.
+    fn cyclic_snapshot() -> GraphSnapshotDto {
+        let krate = node(GraphNodeKind::Crate, "fixture", "fixture");
+        let files = ["src/graph.rs", "src/commands.rs", "src/util.rs"]
+            .map(|path| node(GraphNodeKind::File, path, path));
+        let modules = ["graph", "commands", "util"]
+            .map(|label| node(GraphNodeKind::Module, &format!("src/{label}.rs"), label));
+        let build = node(GraphNodeKind::Function, "graph::build", "build");
+        let handle = node(GraphNodeKind::Function, "commands::handle", "handle");
+        let import_target = GraphNodeDto {
+            id: "module:import:crate::commands::handle".to_string(),
+            kind: GraphNodeKind::Module,
+            label: "crate::commands::handle".to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        };
+        let util_import = GraphNodeDto {
+            id: "module:import:crate::util".to_string(),
+            kind: GraphNodeKind::Module,
+            label: "crate::util".to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        };
+
+        let mut edges = Vec::new();
+        for (file, module) in files.iter().zip(&modules) {
+            edges.push(edge(
+                GraphEdgeKind::Contains,
+                &krate,
+                file,
+                EdgeProvenanceDto::Syn,
+                None,
+            ));
+            edges.push(edge(
+                GraphEdgeKind::Defines,
+                file,
+                module,
+                EdgeProvenanceDto::Normalized,
+                None,
+            ));
+        }
+        edges.push(edge(
+            GraphEdgeKind::Defines,
+            &files[0],
+            &build,
+            EdgeProvenanceDto::Syn,
+            None,
+        ));
+        edges.push(edge(
+            GraphEdgeKind::Defines,
+            &files[1],
+            &handle,
+            EdgeProvenanceDto::Syn,
+            None,
+        ));
+        edges.push(edge(
+            GraphEdgeKind::Imports,
+            &files[0],
+            &import_target,
+            EdgeProvenanceDto::Syn,
+            Some(range("src/graph.rs", 1)),
+        ));
+        edges.push(edge(
+            GraphEdgeKind::Imports,
+            &files[0],
+            &util_import,
+            EdgeProvenanceDto::Syn,
+            Some(range("src/graph.rs", 2)),
+        ));
+        edges.push(edge(
+            GraphEdgeKind::Calls,
+            &handle,
+            &build,
+            EdgeProvenanceDto::Syn,
+            Some(range("src/commands.rs", 4)),
+        ));
+
+        let mut nodes = vec![krate, build, handle, import_target, util_import];
+        nodes.extend(files);
+        nodes.extend(modules);
+        GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes,
+            edges,
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    #[test]
+    fn reports_module_and_file_cycles_with_closing_evidence() {
+        let snapshot = cyclic_snapshot();
+
+        let modules = find_dependency_cycles(&snapshot, DependencyCycleGranularity::Module);
+        assert_eq!(modules.cycles.len(), 1);
+        let cycle = &modules.cycles[0];
+        assert_eq!(cycle.member_labels, vec!["commands", "graph"]);
+        assert_eq!(cycle.cycle_path.len(), 3);
+        assert_eq!(cycle.cycle_path.first(), cycle.cycle_path.last());
+        assert_eq!(cycle.closing_edges.len(), 2);
+        assert!(cycle
+            .closing_edges
+            .iter()
+            .any(|edge| edge.kind == GraphEdgeKind::Imports
+                && edge.evidence == Some(range("src/graph.rs", 1))));
+        assert!(cycle
+            .closing_edges
+            .iter()
+            .any(|edge| edge.kind == GraphEdgeKind::Calls));
+
+        let files = find_dependency_cycles(&snapshot, DependencyCycleGranularity::File);
+        assert_eq!(files.cycles.len(), 1);
+        assert_eq!(
+            files.cycles[0].member_ids,
+            vec![
+                stable_node_id(GraphNodeKind::File, &["src/commands.rs"]),
+                stable_node_id(GraphNodeKind::File, &["src/graph.rs"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_crate_has_no_crate_cycle_and_diagnostics_name_the_loop() {
+        let snapshot = cyclic_snapshot();
+
+        assert!(
+            find_dependency_cycles(&snapshot, DependencyCycleGranularity::Crate)
+                .cycles
+                .is_empty()
+        );
+
+        let diagnostics = dependency_cycle_diagnostics(&snapshot);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.code == DEPENDENCY_CYCLE_CODE
+                && diagnostic.evidence.is_some()));
+        assert!(diagnostics.iter().any(|diagnostic| diagnostic.message
+            == "module dependency cycle: commands -> graph -> commands"));
+    }
+}
@@ -0,0 +1,520 @@
+//! Reachability-based dead code report rooted at real entry points.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AnalyzerDiagnosticDto, EdgeProvenanceDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind,
+    GraphSnapshotDto, NodeVisibilityDto, SourceRangeDto,
+};
+
+pub const UNREACHABLE_ITEM_CODE: &str = "unreachable_item";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadCodeReportDto {
+    pub root_count: u32,
+    pub items: Vec<DeadCodeItemDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadCodeItemDto {
+    pub node_id: String,
+    pub kind: GraphNodeKind,
+    pub label: String,
+    pub source_range: Option<SourceRangeDto>,
+    pub confidence: DeadCodeConfidenceDto,
+    pub reason: String,
+}
+
+/// How much to trust an unreachable verdict. Syn call edges resolve by final
+/// name only and never see calls inside macro bodies, so nothing is certain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadCodeConfidenceDto {
+    Low,
+    Medium,
+    High,
+}
+
+impl DeadCodeConfidenceDto {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Walks Calls and References edges from `main` in a target root, Tauri
+/// commands, `#[test]` functions, trait impl methods, and items with a
+/// public library path, then lists every syn-extracted function, method,
+/// and type left unvisited.
+pub fn find_dead_code(snapshot: &GraphSnapshotDto) -> DeadCodeReportDto {
+    let node_by_id = snapshot
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect::<BTreeMap<_, _>>();
+    // Crates contain exactly the modules of their target root files.
+    let crate_root_modules = snapshot
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == GraphEdgeKind::Contains)
+        .filter(|edge| {
+            node_by_id
+                .get(edge.source_id.as_str())
+                .is_some_and(|node| node.kind == GraphNodeKind::Crate)
+                && node_by_id
+                    .get(edge.target_id.as_str())
+                    .is_some_and(|node| node.kind == GraphNodeKind::Module)
+        })
+        .map(|edge| edge.target_id.as_str())
+        .collect::<BTreeSet<_>>();
+
+    let mut syn_definitions = BTreeSet::new();
+    let mut crate_root_items = BTreeSet::new();
+    let mut impl_by_method = BTreeMap::new();
+    let mut trait_impls = BTreeSet::new();
+    for edge in &snapshot.edges {
+        match edge.kind {
+            GraphEdgeKind::Defines | GraphEdgeKind::DefinesMethod
+                if edge.provenance == EdgeProvenanceDto::Syn =>
+            {
+                syn_definitions.insert(edge.target_id.as_str());
+                if crate_root_modules.contains(edge.source_id.as_str()) {
+                    crate_root_items.insert(edge.target_id.as_str());
+                }
+                if edge.kind == GraphEdgeKind::DefinesMethod {
+                    impl_by_method.insert(edge.target_id.as_str(), edge.source_id.as_str());
+                }
+            }
+            GraphEdgeKind::Implements => {
+                trait_impls.insert(edge.source_id.as_str());
+            }
+            _ => {}
+        }
+    }
+
+    let mut roots = BTreeSet::new();
+    for node in &snapshot.nodes {
+        let is_root = match node.kind {
+            GraphNodeKind::TauriCommand => true,
+            GraphNodeKind::Function => {
+                (node.label == "main" && crate_root_items.contains(node.id.as_str()))
+                    || is_test(node)
+                    || is_library_export(node)
+            }
+            GraphNodeKind::Method => {
+                is_test(node)
+                    || impl_by_method
+                        .get(node.id.as_str())
+                        .is_some_and(|impl_id| trait_impls.contains(impl_id))
+                    || is_library_export(node)
+            }
+            GraphNodeKind::Struct | GraphNodeKind::Enum | GraphNodeKind::Trait => {
+                is_library_export(node)
+            }
+            _ => false,
+        };
+        if is_root {
+            roots.insert(node.id.as_str());
+        }
+    }
+
+    let mut outgoing = BTreeMap::<&str, Vec<&str>>::new();
+    for edge in &snapshot.edges {
+        if matches!(edge.kind, GraphEdgeKind::Calls | GraphEdgeKind::References) {
+            outgoing
+                .entry(edge.source_id.as_str())
+                .or_default()
+                .push(edge.target_id.as_str());
+        }
+    }
+    let mut reached = roots.clone();
+    let mut queue = roots.iter().copied().collect::<VecDeque<_>>();
+    while let Some(node_id) = queue.pop_front() {
+        for target_id in outgoing.get(node_id).into_iter().flatten() {
+            if reached.insert(target_id) {
+                queue.push_back(target_id);
+            }
+        }
+    }
+
+    // Types have no reference edges yet, so a type counts as used when one of
+    // its impls has a reached method or implements a trait.
+    let mut used_type_names = BTreeSet::new();
+    for (method_id, impl_id) in &impl_by_method {
+        if reached.contains(method_id) {
+            if let Some(impl_node) = node_by_id.get(impl_id) {
+                used_type_names.extend(impl_type_names(&impl_node.label));
+            }
+        }
+    }
+    for impl_id in &trait_impls {
+        if let Some(impl_node) = node_by_id.get(impl_id) {
+            used_type_names.extend(impl_type_names(&impl_node.label));
+        }
+    }
+
+    let mut function_name_counts = BTreeMap::<&str, u32>::new();
+    for node in &snapshot.nodes {
+        if is_function_like(&node.kind) && syn_definitions.contains(node.id.as_str()) {
+            *function_name_counts.entry(node.label.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut items = snapshot
+        .nodes
+        .iter()
+        .filter(|node| syn_definitions.contains(node.id.as_str()))
+        .filter(|node| !reached.contains(node.id.as_str()))
+        .filter_map(|node| {
+            let (confidence, reason) = if is_function_like(&node.kind) {
+                let shares_name = function_name_counts
+                    .get(node.label.as_str())
+                    .is_some_and(|count| *count > 1);
+                if shares_name {
+                    (
+                        DeadCodeConfidenceDto::Low,
+                        "no reachable call resolves here, but another function shares its name"
+                            .to_string(),
+                    )
+                } else if node.visibility == Some(NodeVisibilityDto::Private) {
+                    (
+                        DeadCodeConfidenceDto::High,
+                        "private and never called from a reachable function".to_string(),
+                    )
+                } else {
+                    (
+                        DeadCodeConfidenceDto::Medium,
+                        "never called from a reachable function".to_string(),
+                    )
+                }
+            } else if matches!(
+                node.kind,
+                GraphNodeKind::Struct | GraphNodeKind::Enum | GraphNodeKind::Trait
+            ) {
+                if used_type_names.contains(node.label.as_str()) {
+                    return None;
+                }
+                (
+                    DeadCodeConfidenceDto::Low,
+                    "no reachable method or trait impl uses this type".to_string(),
+                )
+            } else {
+                return None;
+            };
+
+            Some(DeadCodeItemDto {
+                node_id: node.id.clone(),
+                kind: node.kind.clone(),
+                label: node.label.clone(),
+                source_range: node.source_range.clone(),
+                confidence,
+                reason,
+            })
+        })
+        .collect::<Vec<_>>();
+    items.sort_by(|left, right| {
+        let position = |item: &DeadCodeItemDto| {
+            item.source_range
+                .as_ref()
+                .map(|range| (range.path.clone(), range.start_line))
+        };
+        position(left)
+            .cmp(&position(right))
+            .then_with(|| left.node_id.cmp(&right.node_id))
+    });
+
+    DeadCodeReportDto {
+        root_count: roots.len() as u32,
+        items,
+    }
+}
+
+/// Reports unreachable items as diagnostics.
+pub fn dead_code_diagnostics(snapshot: &GraphSnapshotDto) -> Vec<AnalyzerDiagnosticDto> {
+    find_dead_code(snapshot)
+        .items
+        .into_iter()
+        .map(|item| {
+            let kind = match item.kind {
+                GraphNodeKind::Method => "method",
+                GraphNodeKind::Struct => "struct",
+                GraphNodeKind::Enum => "enum",
+                GraphNodeKind::Trait => "trait",
+                _ => "function",
+            };
+            AnalyzerDiagnosticDto {
+                code: UNREACHABLE_ITEM_CODE.to_string(),
+                message: format!(
+                    "{kind} '{}' is never reached from an entry point ({} confidence): {}",
+                    item.label,
+                    item.confidence.as_str(),
+                    item.reason
+                ),
+                source_path: item.source_range.as_ref().map(|range| range.path.clone()),
+                evidence: item.source_range,
+            }
+        })
+        .collect()
+}
+
+fn is_function_like(kind: &GraphNodeKind) -> bool {
+    matches!(kind, GraphNodeKind::Function | GraphNodeKind::Method)
+}
+
+fn is_test(node: &GraphNodeDto) -> bool {
+    node.attributes.iter().any(|attribute| {
+        let name = attribute.rsplit("::").next().unwrap_or(attribute);
+        matches!(name, "test" | "bench")
+    })
+}
+
+/// Items a library crate exports, including through `pub use`; a `pub`
+/// item inside a private module has no public path.
+fn is_library_export(node: &GraphNodeDto) -> bool {
+    !node.public_paths.is_empty()
+}
+
+/// Type names an impl label mentions: `impl Run for Worker` yields `Run`
+/// and `Worker`.
+fn impl_type_names(label: &str) -> Vec<String> {
+    let Some(rest) = label.strip_prefix("impl ") else {
+        return Vec::new();
+    };
+    rest.split(" for ")
+        .filter_map(|path| path.rsplit("::").next())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dead_code_diagnostics, find_dead_code, DeadCodeConfidenceDto, UNREACHABLE_ITEM_CODE,
+    };
+    use crate::analyzer::RustGraphExtractor;
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, NodeVisibilityDto,
+        SourceRangeDto, GRAPH_SCHEMA_VERSION,
+    };
+    use crate::source::ValidatedRepoPath;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn node(
+        kind: GraphNodeKind,
+        label: &str,
+        visibility: Option<NodeVisibilityDto>,
+        attributes: &[&str],
+        line: u32,
+    ) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &[label]),
+            kind,
+            label: label.to_string(),
+            source_range: Some(SourceRangeDto {
+                path: "src/main.rs".to_string(),
+                start_line: line,
+                start_column: 1,
+                end_line: line,
+                end_column: 1,
+            }),
+            metrics: None,
+            visibility,
+            attributes: attributes
+                .iter()
+                .map(|attribute| attribute.to_string())
+                .collect(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn edge(kind: GraphEdgeKind, source: &GraphNodeDto, target: &GraphNodeDto) -> GraphEdgeDto {
+        let provenance = if kind == GraphEdgeKind::Contains {
+            EdgeProvenanceDto::Normalized
+        } else {
+            EdgeProvenanceDto::Syn
+        };
+        GraphEdgeDto {
+            id: stable_edge_id(kind.clone(), &source.id, &target.id),
+            kind,
+            source_id: source.id.clone(),
+            target_id: target.id.clone(),
+            provenance,
+            confidence: EdgeConfidenceDto::Partial,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    fn binary_snapshot() -> GraphSnapshotDto {
+        let private = Some(NodeVisibilityDto::Private);
+        let krate = node(GraphNodeKind::Crate, "fixture", None, &[], 1);
+        let root = node(GraphNodeKind::Module, "main", None, &[], 1);
+        let module = node(GraphNodeKind::Module, "worker", None, &[], 1);
+        let main = node(GraphNodeKind::Function, "main", private, &[], 1);
+        let helper = node(GraphNodeKind::Function, "helper", private, &[], 2);
+        let orphan = node(GraphNodeKind::Function, "orphan", private, &[], 3);
+        let exported = node(
+            GraphNodeKind::Function,
+            "exported",
+            Some(NodeVisibilityDto::Public),
+            &[],
+            4,
+        );
+        let checks = node(GraphNodeKind::Function, "checks", private, &["test"], 5);
+        let fixture = node(GraphNodeKind::Function, "fixture", private, &[], 6);
+        let worker = node(
+            GraphNodeKind::Struct,
+            "Worker",
+            Some(NodeVisibilityDto::Public),
+            &[],
+            7,
+        );
+        let unused = node(GraphNodeKind::Enum, "Unused", private, &[], 8);
+        let run_impl = node(GraphNodeKind::Impl, "impl Run for Worker", None, &[], 9);
+        let run = node(GraphNodeKind::Method, "run", private, &[], 10);
+
+        let mut nested_main = node(GraphNodeKind::Function, "main", private, &[], 11);
+        nested_main.id = stable_node_id(GraphNodeKind::Function, &["worker", "main"]);
+
+        let mut edges = vec![
+            edge(GraphEdgeKind::Contains, &krate, &root),
+            edge(GraphEdgeKind::Defines, &root, &module),
+            edge(GraphEdgeKind::Calls, &main, &helper),
+            edge(GraphEdgeKind::Calls, &checks, &fixture),
+            edge(GraphEdgeKind::Implements, &run_impl, &module),
+            edge(GraphEdgeKind::DefinesMethod, &run_impl, &run),
+        ];
+        for item in [
+            &main, &helper, &orphan, &exported, &checks, &fixture, &worker, &unused, &run_impl,
+        ] {
+            edges.push(edge(GraphEdgeKind::Defines, &root, item));
+        }
+        edges.push(edge(GraphEdgeKind::Defines, &module, &nested_main));
+
+        GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes: vec![
+                krate,
+                root,
+                module,
+                main,
+                helper,
+                orphan,
+                exported,
+                checks,
+                fixture,
+                worker,
+                unused,
+                run_impl,
+                run,
+                nested_main,
+            ],
+            edges,
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    #[test]
+    fn reports_items_unreachable_from_main_tests_and_trait_impls() {
+        let report = find_dead_code(&binary_snapshot());
+
+        assert_eq!(report.root_count, 3);
+        assert_eq!(
+            report
+                .items
+                .iter()
+                .map(|item| (item.label.as_str(), item.confidence))
+                .collect::<Vec<_>>(),
+            vec![
+                ("orphan", DeadCodeConfidenceDto::High),
+                ("exported", DeadCodeConfidenceDto::Medium),
+                ("Unused", DeadCodeConfidenceDto::Low),
+                ("main", DeadCodeConfidenceDto::Low),
+            ]
+        );
+
+        let diagnostics = dead_code_diagnostics(&binary_snapshot());
+        assert_eq!(diagnostics.len(), 4);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.code == UNREACHABLE_ITEM_CODE
+                && diagnostic.evidence.is_some()));
+    }
+
+    #[test]
+    fn items_with_public_library_paths_are_roots() {
+        let mut snapshot = binary_snapshot();
+        for node in &mut snapshot.nodes {
+            if node.label == "exported" {
+                node.public_paths = vec!["fixture::exported".to_string()];
+            }
+        }
+
+        let report = find_dead_code(&snapshot);
+        assert_eq!(report.root_count, 4);
+        assert!(!report.items.iter().any(|item| item.label == "exported"));
+    }
+
+    #[test]
+    fn extracted_crates_root_main_and_library_exports() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        let repo = std::env::temp_dir().join(format!(
+            "whip-docs-dead-code-{}-{timestamp}",
+            std::process::id()
+        ));
+        fs::create_dir_all(repo.join("src")).expect("create fixture src");
+        fs::write(
+            repo.join("Cargo.toml"),
+            "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .expect("write manifest");
+        fs::write(
+            repo.join("src/main.rs"),
+            "fn main() {\n    run();\n}\n\nfn run() {}\n\nfn orphan() {}\n",
+        )
+        .expect("write main");
+        fs::write(
+            repo.join("src/lib.rs"),
+            "mod internal;\n\npub fn api() {}\n",
+        )
+        .expect("write lib");
+        fs::write(repo.join("src/internal.rs"), "pub fn hidden() {}\n").expect("write module");
+        let source_root = ValidatedRepoPath::parse_existing_cargo_repo(&repo).expect("valid repo");
+
+        let snapshot = RustGraphExtractor
+            .extract(&source_root)
+            .expect("extract fixture");
+        let mut unreachable = find_dead_code(&snapshot)
+            .items
+            .into_iter()
+            .map(|item| item.label)
+            .collect::<Vec<_>>();
+        unreachable.sort();
+        assert_eq!(unreachable, ["hidden", "orphan"]);
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+}
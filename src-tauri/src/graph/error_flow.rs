@@ -0,0 +1,496 @@
+//! Error propagation: per-item error facts and the error-flow relation
+//! between error types, with the sites where error detail is lost.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{GraphNodeKind, GraphSnapshotDto, SourceRangeDto};
+
+/// Error facts of one function, method, `From` impl, or error enum.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorFlowFactsDto {
+    /// `E` of a `Result<T, E>` return type, or `io::Error` for a one-argument
+    /// `io::Result<T>` style alias; absent for other return types.
+    pub error_type: Option<String>,
+    /// `?` operators in the body outside closures and async blocks, which
+    /// return to their own caller.
+    pub try_sites: Vec<TrySiteDto>,
+    /// `map_err` calls in the body, the impl's own `From` conversion, or the
+    /// enum's `#[from]` variant fields.
+    pub conversions: Vec<ErrorConversionDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrySiteDto {
+    pub range: SourceRangeDto,
+    /// Function or method whose error the `?` forwards, such as `load` in
+    /// `load(path).await?`; absent for other expressions and for `map_err`
+    /// results, whose conversion is recorded on its own.
+    pub callee: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorConversionDto {
+    pub kind: ErrorConversionKindDto,
+    /// Converted type; known for `From` impls and `#[from]` fields.
+    pub source_type: Option<String>,
+    /// Type produced, such as `CommandErrorDto` for
+    /// `map_err(|error| CommandErrorDto::internal(error.to_string()))`;
+    /// absent when the closure body does not name it.
+    pub target_type: Option<String>,
+    /// Function or method whose error `map_err` converts.
+    pub callee: Option<String>,
+    pub detail: ErrorDetailDto,
+    pub range: SourceRangeDto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorConversionKindDto {
+    /// Implicit `From` conversion at a `?` site.
+    Question,
+    MapErr,
+    FromImpl,
+    /// thiserror `#[from]` on an enum variant field.
+    FromAttribute,
+}
+
+/// What a conversion keeps of the source error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorDetailDto {
+    Preserved,
+    /// The source error only survives as text, through `to_string()` or a
+    /// formatting macro.
+    Stringified,
+    /// The source error is ignored, as in `map_err(|_| ..)`.
+    Discarded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorFlowReportDto {
+    /// Sorted by error type.
+    pub error_types: Vec<ErrorTypeUsageDto>,
+    /// Sorted by target type, then source type.
+    pub relations: Vec<ErrorFlowRelationDto>,
+    /// Stringified and discarded conversion sites in source order.
+    pub lossy_sites: Vec<ErrorFlowSiteDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorTypeUsageDto {
+    pub error_type: String,
+    /// Functions and methods returning `Result<_, error_type>`.
+    pub returned_by: u32,
+    pub try_sites: u32,
+}
+
+/// Conversions from one error type into another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorFlowRelationDto {
+    /// Absent when a `map_err` converts the error of a call that does not
+    /// resolve to a workspace function with a known error type.
+    pub source_type: Option<String>,
+    pub target_type: String,
+    /// Sites that stringify or discard the source error.
+    pub lossy: u32,
+    pub sites: Vec<ErrorFlowSiteDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorFlowSiteDto {
+    pub node_id: String,
+    pub node_label: String,
+    pub kind: ErrorConversionKindDto,
+    pub detail: ErrorDetailDto,
+    pub range: SourceRangeDto,
+}
+
+/// Builds the error-flow relation. `?` and `map_err` sources resolve by
+/// callee name to the error type of the workspace functions with that name,
+/// and only when they all agree. `From` impls count when their target is an
+/// error type: one some function returns, one with `#[from]` fields, or one
+/// whose name contains `Error`.
+pub fn error_flow_report(snapshot: &GraphSnapshotDto) -> ErrorFlowReportDto {
+    let mut error_type_by_name = BTreeMap::<&str, Option<&str>>::new();
+    let mut usages = BTreeMap::<&str, ErrorTypeUsageDto>::new();
+    let mut error_types = BTreeSet::<&str>::new();
+    for node in &snapshot.nodes {
+        let Some(facts) = &node.error_flow else {
+            continue;
+        };
+        if let (Some(error_type), GraphNodeKind::Function | GraphNodeKind::Method) =
+            (facts.error_type.as_deref(), &node.kind)
+        {
+            error_type_by_name
+                .entry(node.label.as_str())
+                .and_modify(|known| {
+                    if *known != Some(error_type) {
+                        *known = None;
+                    }
+                })
+                .or_insert(Some(error_type));
+            let usage = usages
+                .entry(error_type)
+                .or_insert_with(|| ErrorTypeUsageDto {
+                    error_type: error_type.to_string(),
+                    returned_by: 0,
+                    try_sites: 0,
+                });
+            usage.returned_by += 1;
+            usage.try_sites += facts.try_sites.len() as u32;
+            error_types.insert(error_type);
+        }
+        for conversion in &facts.conversions {
+            if conversion.kind == ErrorConversionKindDto::FromAttribute {
+                error_types.extend(conversion.target_type.as_deref());
+            }
+        }
+    }
+    let callee_error_type = |callee: Option<&String>| {
+        callee
+            .and_then(|name| error_type_by_name.get(name.as_str()).copied())
+            .flatten()
+    };
+
+    let mut relations = BTreeMap::<(String, Option<String>), Vec<ErrorFlowSiteDto>>::new();
+    for node in &snapshot.nodes {
+        let Some(facts) = &node.error_flow else {
+            continue;
+        };
+        let site = |kind, detail, range: &SourceRangeDto| ErrorFlowSiteDto {
+            node_id: node.id.clone(),
+            node_label: node.label.clone(),
+            kind,
+            detail,
+            range: range.clone(),
+        };
+
+        for try_site in &facts.try_sites {
+            let source = callee_error_type(try_site.callee.as_ref());
+            if let (Some(source), Some(target)) = (source, facts.error_type.as_deref()) {
+                if source != target {
+                    relations
+                        .entry((target.to_string(), Some(source.to_string())))
+                        .or_default()
+                        .push(site(
+                            ErrorConversionKindDto::Question,
+                            ErrorDetailDto::Preserved,
+                            &try_site.range,
+                        ));
+                }
+            }
+        }
+
+        for conversion in &facts.conversions {
+            let (source, target) = match conversion.kind {
+                ErrorConversionKindDto::MapErr => (
+                    callee_error_type(conversion.callee.as_ref()),
+                    conversion
+                        .target_type
+                        .as_deref()
+                        .or(facts.error_type.as_deref()),
+                ),
+                _ => (
+                    conversion.source_type.as_deref(),
+                    conversion.target_type.as_deref(),
+                ),
+            };
+            let Some(target) = target else {
+                continue;
+            };
+            if conversion.kind == ErrorConversionKindDto::FromImpl
+                && !error_types.contains(target)
+                && !target.contains("Error")
+            {
+                continue;
+            }
+            relations
+                .entry((target.to_string(), source.map(str::to_string)))
+                .or_default()
+                .push(site(conversion.kind, conversion.detail, &conversion.range));
+        }
+    }
+
+    let mut lossy_sites = relations
+        .values()
+        .flatten()
+        .filter(|site| site.detail != ErrorDetailDto::Preserved)
+        .cloned()
+        .collect::<Vec<_>>();
+    lossy_sites.sort_by(|left, right| {
+        let position = |site: &ErrorFlowSiteDto| {
+            (
+                site.range.path.clone(),
+                site.range.start_line,
+                site.range.start_column,
+            )
+        };
+        position(left).cmp(&position(right))
+    });
+
+    ErrorFlowReportDto {
+        error_types: usages.into_values().collect(),
+        relations: relations
+            .into_iter()
+            .map(|((target_type, source_type), sites)| ErrorFlowRelationDto {
+                source_type,
+                target_type,
+                lossy: sites
+                    .iter()
+                    .filter(|site| site.detail != ErrorDetailDto::Preserved)
+                    .count() as u32,
+                sites,
+            })
+            .collect(),
+        lossy_sites,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        error_flow_report, ErrorConversionDto, ErrorConversionKindDto, ErrorDetailDto,
+        ErrorFlowFactsDto, TrySiteDto,
+    };
+    use crate::graph::{
+        stable_node_id, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn range(line: u32) -> SourceRangeDto {
+        SourceRangeDto {
+            path: "src/commands.rs".to_string(),
+            start_line: line,
+            start_column: 9,
+            end_line: line,
+            end_column: 16,
+        }
+    }
+
+    fn node(kind: GraphNodeKind, label: &str, error_flow: ErrorFlowFactsDto) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &["fixture", label]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: Some(error_flow),
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn conversion(
+        kind: ErrorConversionKindDto,
+        source_type: Option<&str>,
+        target_type: Option<&str>,
+        callee: Option<&str>,
+        detail: ErrorDetailDto,
+        line: u32,
+    ) -> ErrorConversionDto {
+        ErrorConversionDto {
+            kind,
+            source_type: source_type.map(str::to_string),
+            target_type: target_type.map(str::to_string),
+            callee: callee.map(str::to_string),
+            detail,
+            range: range(line),
+        }
+    }
+
+    #[test]
+    fn relates_error_types_through_question_marks_map_err_and_from() {
+        let load = node(
+            GraphNodeKind::Function,
+            "load",
+            ErrorFlowFactsDto {
+                error_type: Some("ConfigError".to_string()),
+                try_sites: Vec::new(),
+                conversions: Vec::new(),
+            },
+        );
+        let config_error = node(
+            GraphNodeKind::Enum,
+            "ConfigError",
+            ErrorFlowFactsDto {
+                error_type: None,
+                try_sites: Vec::new(),
+                conversions: vec![conversion(
+                    ErrorConversionKindDto::FromAttribute,
+                    Some("std::io::Error"),
+                    Some("ConfigError"),
+                    None,
+                    ErrorDetailDto::Preserved,
+                    3,
+                )],
+            },
+        );
+        let command = node(
+            GraphNodeKind::Function,
+            "get_config",
+            ErrorFlowFactsDto {
+                error_type: Some("CommandErrorDto".to_string()),
+                try_sites: vec![TrySiteDto {
+                    range: range(20),
+                    callee: None,
+                }],
+                conversions: vec![
+                    conversion(
+                        ErrorConversionKindDto::MapErr,
+                        None,
+                        Some("CommandErrorDto"),
+                        Some("load"),
+                        ErrorDetailDto::Stringified,
+                        20,
+                    ),
+                    conversion(
+                        ErrorConversionKindDto::MapErr,
+                        None,
+                        None,
+                        Some("spawn_blocking"),
+                        ErrorDetailDto::Discarded,
+                        24,
+                    ),
+                ],
+            },
+        );
+        let app = node(
+            GraphNodeKind::Function,
+            "run",
+            ErrorFlowFactsDto {
+                error_type: Some("AppError".to_string()),
+                try_sites: vec![
+                    TrySiteDto {
+                        range: range(30),
+                        callee: Some("load".to_string()),
+                    },
+                    TrySiteDto {
+                        range: range(31),
+                        callee: Some("unknown".to_string()),
+                    },
+                ],
+                conversions: Vec::new(),
+            },
+        );
+        let unrelated_from = node(
+            GraphNodeKind::Impl,
+            "impl From<Config> for ConfigDto",
+            ErrorFlowFactsDto {
+                error_type: None,
+                try_sites: Vec::new(),
+                conversions: vec![conversion(
+                    ErrorConversionKindDto::FromImpl,
+                    Some("Config"),
+                    Some("ConfigDto"),
+                    None,
+                    ErrorDetailDto::Preserved,
+                    40,
+                )],
+            },
+        );
+        let snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes: vec![load, config_error, command, app, unrelated_from],
+            edges: Vec::new(),
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        let report = error_flow_report(&snapshot);
+
+        let usages = report
+            .error_types
+            .iter()
+            .map(|usage| {
+                (
+                    usage.error_type.as_str(),
+                    usage.returned_by,
+                    usage.try_sites,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            usages,
+            vec![
+                ("AppError", 1, 2),
+                ("CommandErrorDto", 1, 1),
+                ("ConfigError", 1, 0)
+            ]
+        );
+        let relations = report
+            .relations
+            .iter()
+            .map(|relation| {
+                (
+                    relation.source_type.as_deref(),
+                    relation.target_type.as_str(),
+                    relation.lossy,
+                    relation
+                        .sites
+                        .iter()
+                        .map(|site| site.kind)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relations,
+            vec![
+                (
+                    Some("ConfigError"),
+                    "AppError",
+                    0,
+                    vec![ErrorConversionKindDto::Question]
+                ),
+                (
+                    None,
+                    "CommandErrorDto",
+                    1,
+                    vec![ErrorConversionKindDto::MapErr]
+                ),
+                (
+                    Some("ConfigError"),
+                    "CommandErrorDto",
+                    1,
+                    vec![ErrorConversionKindDto::MapErr]
+                ),
+                (
+                    Some("std::io::Error"),
+                    "ConfigError",
+                    0,
+                    vec![ErrorConversionKindDto::FromAttribute]
+                ),
+            ]
+        );
+        let lossy = report
+            .lossy_sites
+            .iter()
+            .map(|site| (site.range.start_line, site.detail))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lossy,
+            vec![
+                (20, ErrorDetailDto::Stringified),
+                (24, ErrorDetailDto::Discarded)
+            ]
+        );
+    }
+}
@@ -0,0 +1,769 @@
+//! Architectural health metrics derived from graph snapshot edges.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::coverage::CoverageMetricsDto;
+use super::ownership::NodeOwnership;
+use super::unsafe_code::UnsafeRollupDto;
+use super::{
+    EdgeProvenanceDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+};
+
+/// Computed metrics attached to a graph node. Each group is absent when it
+/// does not apply to the node kind.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeMetricsDto {
+    pub coupling: Option<CouplingMetricsDto>,
+    pub complexity: Option<FunctionComplexityDto>,
+    pub complexity_rollup: Option<ComplexityRollupDto>,
+    pub unsafe_rollup: Option<UnsafeRollupDto>,
+    /// Imported line coverage; cleared when the repository is re-analyzed.
+    pub coverage: Option<CoverageMetricsDto>,
+}
+
+/// Martin package metrics for a module or crate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CouplingMetricsDto {
+    /// Distinct peers that depend on this unit.
+    pub afferent_coupling: u32,
+    /// Distinct peers this unit depends on.
+    pub efferent_coupling: u32,
+    /// `Ce / (Ca + Ce)`, or `0` for an isolated unit.
+    pub instability: f64,
+    pub abstract_types: u32,
+    pub concrete_types: u32,
+    /// Traits over all traits, structs, and enums defined in the unit.
+    pub abstractness: f64,
+    /// `|A + I - 1|`.
+    pub distance_from_main_sequence: f64,
+}
+
+/// Size and complexity of one function or method body.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionComplexityDto {
+    /// Non-blank, non-comment lines from the signature to the closing brace.
+    pub lines_of_code: u32,
+    /// One plus each `if`, loop, extra `match` arm, and `&&`/`||` operator.
+    pub cyclomatic_complexity: u32,
+    pub max_nesting_depth: u32,
+    pub parameter_count: u32,
+    pub question_mark_count: u32,
+    pub unwrap_count: u32,
+    pub expect_count: u32,
+    pub await_count: u32,
+}
+
+/// Function complexity summed over a file or module.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexityRollupDto {
+    pub function_count: u32,
+    pub lines_of_code: u32,
+    pub total_cyclomatic_complexity: u32,
+    pub max_cyclomatic_complexity: u32,
+    pub max_nesting_depth: u32,
+    pub question_mark_count: u32,
+    pub unwrap_count: u32,
+    pub expect_count: u32,
+    pub await_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionComplexityMetric {
+    LinesOfCode,
+    CyclomaticComplexity,
+    MaxNestingDepth,
+    ParameterCount,
+    QuestionMarkCount,
+    UnwrapCount,
+    ExpectCount,
+    AwaitCount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexityOffenderDto {
+    pub node_id: String,
+    pub kind: GraphNodeKind,
+    pub label: String,
+    pub source_range: Option<SourceRangeDto>,
+    pub complexity: FunctionComplexityDto,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphMetricsReportDto {
+    pub source_root: String,
+    pub generated_at: String,
+    pub metrics: BTreeMap<String, NodeMetricsDto>,
+}
+
+impl GraphMetricsReportDto {
+    pub fn from_snapshot(snapshot: &GraphSnapshotDto) -> Self {
+        Self {
+            source_root: snapshot.source_root.clone(),
+            generated_at: snapshot.generated_at.clone(),
+            metrics: snapshot
+                .nodes
+                .iter()
+                .filter_map(|node| Some((node.id.clone(), node.metrics.clone()?)))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CouplingUnit {
+    Module,
+    Crate,
+}
+
+/// Computes module and crate coupling metrics and stores them on the module
+/// and crate nodes of the snapshot.
+pub fn attach_coupling_metrics(snapshot: &mut GraphSnapshotDto) {
+    let coupling = coupling_metrics(snapshot);
+
+    for node in &mut snapshot.nodes {
+        if let Some(metrics) = coupling.get(&node.id) {
+            node.metrics
+                .get_or_insert_with(NodeMetricsDto::default)
+                .coupling = Some(metrics.clone());
+        }
+    }
+}
+
+impl FunctionComplexityMetric {
+    fn value(self, complexity: &FunctionComplexityDto) -> u32 {
+        match self {
+            Self::LinesOfCode => complexity.lines_of_code,
+            Self::CyclomaticComplexity => complexity.cyclomatic_complexity,
+            Self::MaxNestingDepth => complexity.max_nesting_depth,
+            Self::ParameterCount => complexity.parameter_count,
+            Self::QuestionMarkCount => complexity.question_mark_count,
+            Self::UnwrapCount => complexity.unwrap_count,
+            Self::ExpectCount => complexity.expect_count,
+            Self::AwaitCount => complexity.await_count,
+        }
+    }
+}
+
+impl ComplexityRollupDto {
+    fn add(&mut self, complexity: &FunctionComplexityDto) {
+        self.function_count += 1;
+        self.lines_of_code += complexity.lines_of_code;
+        self.total_cyclomatic_complexity += complexity.cyclomatic_complexity;
+        self.max_cyclomatic_complexity = self
+            .max_cyclomatic_complexity
+            .max(complexity.cyclomatic_complexity);
+        self.max_nesting_depth = self.max_nesting_depth.max(complexity.max_nesting_depth);
+        self.question_mark_count += complexity.question_mark_count;
+        self.unwrap_count += complexity.unwrap_count;
+        self.expect_count += complexity.expect_count;
+        self.await_count += complexity.await_count;
+    }
+}
+
+/// Sums function complexity into the owning file and module nodes.
+pub fn attach_complexity_rollups(snapshot: &mut GraphSnapshotDto) {
+    let ownership = NodeOwnership::from_snapshot(snapshot);
+    let mut rollups = BTreeMap::<String, ComplexityRollupDto>::new();
+
+    for node in &snapshot.nodes {
+        let Some(complexity) = node
+            .metrics
+            .as_ref()
+            .and_then(|metrics| metrics.complexity.as_ref())
+        else {
+            continue;
+        };
+
+        let owners = [
+            ownership.owning_file_id(&node.id, None),
+            ownership.owning_module_id(&node.id, None),
+        ];
+        for owner_id in owners.into_iter().flatten() {
+            rollups
+                .entry(owner_id.to_string())
+                .or_default()
+                .add(complexity);
+        }
+    }
+
+    for node in &mut snapshot.nodes {
+        if let Some(rollup) = rollups.remove(&node.id) {
+            node.metrics
+                .get_or_insert_with(NodeMetricsDto::default)
+                .complexity_rollup = Some(rollup);
+        }
+    }
+}
+
+/// Functions and methods ranked by `metric`, highest first. Ties keep the
+/// larger function first, then node ID order.
+pub fn complexity_offenders(
+    snapshot: &GraphSnapshotDto,
+    metric: FunctionComplexityMetric,
+    limit: usize,
+) -> Vec<ComplexityOffenderDto> {
+    let mut offenders = snapshot
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let complexity = node.metrics.as_ref()?.complexity.clone()?;
+            Some(ComplexityOffenderDto {
+                node_id: node.id.clone(),
+                kind: node.kind.clone(),
+                label: node.label.clone(),
+                source_range: node.source_range.clone(),
+                complexity,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    offenders.sort_by(|left, right| {
+        metric
+            .value(&right.complexity)
+            .cmp(&metric.value(&left.complexity))
+            .then(
+                right
+                    .complexity
+                    .lines_of_code
+                    .cmp(&left.complexity.lines_of_code),
+            )
+            .then_with(|| left.node_id.cmp(&right.node_id))
+    });
+    offenders.truncate(limit);
+    offenders
+}
+
+/// Coupling metrics keyed by stable module and crate node ID.
+pub fn coupling_metrics(snapshot: &GraphSnapshotDto) -> BTreeMap<String, CouplingMetricsDto> {
+    let ownership = NodeOwnership::from_snapshot(snapshot);
+    let implemented_traits = implemented_traits(snapshot, &ownership);
+    let counted_types = counted_types(snapshot);
+    let mut metrics = BTreeMap::new();
+
+    for unit in [CouplingUnit::Module, CouplingUnit::Crate] {
+        metrics.extend(unit_metrics(
+            snapshot,
+            &ownership,
+            &implemented_traits,
+            &counted_types,
+            unit,
+        ));
+    }
+
+    metrics
+}
+
+fn unit_metrics(
+    snapshot: &GraphSnapshotDto,
+    ownership: &NodeOwnership,
+    implemented_traits: &BTreeMap<&str, Vec<&str>>,
+    counted_types: &[&GraphNodeDto],
+    unit: CouplingUnit,
+) -> BTreeMap<String, CouplingMetricsDto> {
+    let unit_kind = match unit {
+        CouplingUnit::Module => GraphNodeKind::Module,
+        CouplingUnit::Crate => GraphNodeKind::Crate,
+    };
+    let owning_unit = |node_id: &str, fallback_crate_id: Option<&str>| match unit {
+        CouplingUnit::Module => ownership
+            .owning_module_id(node_id, fallback_crate_id)
+            .map(str::to_string),
+        CouplingUnit::Crate => ownership
+            .owning_crate_id(node_id, fallback_crate_id)
+            .map(str::to_string),
+    };
+
+    // Implements edges point at the impl's own module, so trait impls
+    // depend on the unit their resolved trait lives in instead.
+    let mut unit_edges = snapshot
+        .edges
+        .iter()
+        .filter(|edge| matches!(edge.kind, GraphEdgeKind::Imports | GraphEdgeKind::Calls))
+        .filter_map(|edge| {
+            let source_crate_id = ownership.crate_id(&edge.source_id);
+            Some((
+                owning_unit(&edge.source_id, source_crate_id)?,
+                owning_unit(&edge.target_id, source_crate_id)?,
+            ))
+        })
+        .collect::<Vec<_>>();
+    for (impl_id, trait_ids) in implemented_traits {
+        let mut trait_units = trait_ids
+            .iter()
+            .filter_map(|trait_id| owning_unit(trait_id, None))
+            .collect::<BTreeSet<_>>();
+        // A name shared by traits in several units stays unresolved.
+        if trait_units.len() != 1 {
+            continue;
+        }
+        if let (Some(impl_unit), Some(trait_unit)) =
+            (owning_unit(impl_id, None), trait_units.pop_first())
+        {
+            unit_edges.push((impl_unit, trait_unit));
+        }
+    }
+
+    let mut dependencies = BTreeMap::<String, BTreeSet<String>>::new();
+    let mut dependents = BTreeMap::<String, BTreeSet<String>>::new();
+    for (source_unit, target_unit) in unit_edges {
+        if source_unit == target_unit {
+            continue;
+        }
+
+        dependencies
+            .entry(source_unit.clone())
+            .or_default()
+            .insert(target_unit.clone());
+        dependents
+            .entry(target_unit)
+            .or_default()
+            .insert(source_unit);
+    }
+
+    let mut type_counts = BTreeMap::<String, (u32, u32)>::new();
+    for node in counted_types {
+        let is_abstract = node.kind == GraphNodeKind::Trait;
+        let Some(owner) = owning_unit(&node.id, None) else {
+            continue;
+        };
+        let counts = type_counts.entry(owner).or_default();
+        if is_abstract {
+            counts.0 += 1;
+        } else {
+            counts.1 += 1;
+        }
+    }
+
+    snapshot
+        .nodes
+        .iter()
+        .filter(|node| {
+            node.kind == unit_kind && owning_unit(&node.id, None).as_ref() == Some(&node.id)
+        })
+        .map(|node| {
+            let efferent_coupling = dependencies.get(&node.id).map_or(0, BTreeSet::len) as u32;
+            let afferent_coupling = dependents.get(&node.id).map_or(0, BTreeSet::len) as u32;
+            let (abstract_types, concrete_types) =
+                type_counts.get(&node.id).copied().unwrap_or_default();
+
+            let instability = ratio(efferent_coupling, afferent_coupling + efferent_coupling);
+            let abstractness = ratio(abstract_types, abstract_types + concrete_types);
+            (
+                node.id.clone(),
+                CouplingMetricsDto {
+                    afferent_coupling,
+                    efferent_coupling,
+                    instability,
+                    abstract_types,
+                    concrete_types,
+                    abstractness,
+                    distance_from_main_sequence: (abstractness + instability - 1.0).abs(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Candidate trait nodes for every trait impl, resolved from the trait path
+/// in the impl label. Candidates are narrowed to traits whose module path
+/// ends in the written qualifier, then to traits in the impl's file or a
+/// file it imports. Traits outside the workspace have no candidates.
+fn implemented_traits<'a>(
+    snapshot: &'a GraphSnapshotDto,
+    ownership: &NodeOwnership,
+) -> BTreeMap<&'a str, Vec<&'a str>> {
+    let node_by_id = snapshot
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect::<BTreeMap<_, _>>();
+    let mut traits_by_name = BTreeMap::<&str, Vec<&str>>::new();
+    for node in &snapshot.nodes {
+        if node.kind == GraphNodeKind::Trait {
+            traits_by_name
+                .entry(node.label.as_str())
+                .or_default()
+                .push(node.id.as_str());
+        }
+    }
+    let mut imported_files = BTreeMap::<&str, BTreeSet<&str>>::new();
+    for edge in &snapshot.edges {
+        if edge.kind == GraphEdgeKind::Imports {
+            imported_files
+                .entry(edge.source_id.as_str())
+                .or_default()
+                .insert(edge.target_id.as_str());
+        }
+    }
+
+    let mut implemented = BTreeMap::new();
+    for edge in &snapshot.edges {
+        if edge.kind != GraphEdgeKind::Implements {
+            continue;
+        }
+        let Some(trait_path) = node_by_id
+            .get(edge.source_id.as_str())
+            .and_then(|node| node.label.strip_prefix("impl "))
+            .and_then(|label| label.split_once(" for "))
+            .map(|(trait_path, _)| trait_path.split('<').next().unwrap_or(trait_path))
+        else {
+            continue;
+        };
+        let mut segments = trait_path
+            .trim_start_matches("::")
+            .split("::")
+            .filter(|segment| !matches!(*segment, "crate" | "self" | "super"))
+            .collect::<Vec<_>>();
+        let Some(name) = segments.pop() else {
+            continue;
+        };
+        let mut candidates = traits_by_name.get(name).cloned().unwrap_or_default();
+
+        if !segments.is_empty() {
+            let qualifier = segments.join("::");
+            narrow(&mut candidates, |trait_id| {
+                ownership.module_path(trait_id).is_some_and(|module_path| {
+                    module_path == qualifier || module_path.ends_with(&format!("::{qualifier}"))
+                })
+            });
+        }
+        if let Some(impl_file_id) = ownership.file_id(&edge.source_id) {
+            let imports = imported_files.get(impl_file_id);
+            narrow(&mut candidates, |trait_id| {
+                ownership.file_id(trait_id).is_some_and(|trait_file_id| {
+                    trait_file_id == impl_file_id
+                        || imports.is_some_and(|imports| imports.contains(trait_file_id))
+                })
+            });
+        }
+        if !candidates.is_empty() {
+            implemented.insert(edge.source_id.as_str(), candidates);
+        }
+    }
+    implemented
+}
+
+/// Keeps the candidates that pass `keep`, unless none do.
+fn narrow(candidates: &mut Vec<&str>, keep: impl Fn(&str) -> bool) {
+    let narrowed = candidates
+        .iter()
+        .copied()
+        .filter(|candidate| keep(candidate))
+        .collect::<Vec<_>>();
+    if !narrowed.is_empty() {
+        *candidates = narrowed;
+    }
+}
+
+/// Traits and concrete types, with a rust-analyzer symbol left out when a
+/// syn node of the same name already covers its source range.
+fn counted_types(snapshot: &GraphSnapshotDto) -> Vec<&GraphNodeDto> {
+    let rust_analyzer_ids = snapshot
+        .edges
+        .iter()
+        .filter(|edge| edge.provenance == EdgeProvenanceDto::RustAnalyzer)
+        .map(|edge| edge.target_id.as_str())
+        .collect::<BTreeSet<_>>();
+    let (rust_analyzer_types, syn_types) = snapshot
+        .nodes
+        .iter()
+        .filter(|node| {
+            matches!(
+                node.kind,
+                GraphNodeKind::Trait
+                    | GraphNodeKind::Struct
+                    | GraphNodeKind::Enum
+                    | GraphNodeKind::Union
+            )
+        })
+        .partition::<Vec<_>, _>(|node| rust_analyzer_ids.contains(node.id.as_str()));
+
+    let mut types = syn_types.clone();
+    types.extend(rust_analyzer_types.into_iter().filter(|symbol| {
+        !syn_types.iter().any(|node| {
+            node.label == symbol.label
+                && match (&node.source_range, &symbol.source_range) {
+                    (Some(item), Some(symbol)) => covers(item, symbol),
+                    _ => false,
+                }
+        })
+    }));
+    types
+}
+
+fn covers(outer: &SourceRangeDto, inner: &SourceRangeDto) -> bool {
+    outer.path == inner.path
+        && (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (inner.end_line, inner.end_column) <= (outer.end_line, outer.end_column)
+}
+
+fn ratio(numerator: u32, denominator: u32) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        f64::from(numerator) / f64::from(denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        attach_complexity_rollups, attach_coupling_metrics, complexity_offenders, coupling_metrics,
+        FunctionComplexityDto, FunctionComplexityMetric, GraphMetricsReportDto, NodeMetricsDto,
+    };
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn node(kind: GraphNodeKind, id_part: &str, label: &str) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &[id_part]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn edge(
+        kind: GraphEdgeKind,
+        source: &GraphNodeDto,
+        target: &GraphNodeDto,
+        provenance: EdgeProvenanceDto,
+    ) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: stable_edge_id(kind.clone(), &source.id, &target.id),
+            kind,
+            source_id: source.id.clone(),
+            target_id: target.id.clone(),
+            provenance,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    fn range(start_line: u32, start_column: u32, end_line: u32, end_column: u32) -> SourceRangeDto {
+        SourceRangeDto {
+            path: "src/store.rs".to_string(),
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+
+    /// `api` defines one trait; `store` defines a struct implementing it and
+    /// is called from `app`. rust-analyzer reports `Memory` a second time.
+    fn layered_snapshot() -> GraphSnapshotDto {
+        let krate = node(GraphNodeKind::Crate, "fixture", "fixture");
+        let files = ["src/api.rs", "src/store.rs", "src/app.rs"]
+            .map(|path| node(GraphNodeKind::File, path, path));
+        let modules = ["api", "store", "app"]
+            .map(|label| node(GraphNodeKind::Module, &format!("src/{label}.rs"), label));
+        let repository = node(GraphNodeKind::Trait, "api::Repository", "Repository");
+        let mut memory = node(GraphNodeKind::Struct, "store::Memory", "Memory");
+        memory.source_range = Some(range(1, 1, 1, 19));
+        let mut memory_symbol = node(GraphNodeKind::Struct, "store::ra::Memory", "Memory");
+        memory_symbol.source_range = Some(range(1, 12, 1, 18));
+        let memory_impl = node(
+            GraphNodeKind::Impl,
+            "store::impl",
+            "impl Repository for Memory",
+        );
+        let open = node(GraphNodeKind::Function, "store::open", "open");
+        let run = node(GraphNodeKind::Function, "app::run", "run");
+
+        let mut edges = Vec::new();
+        for (file, module) in files.iter().zip(&modules) {
+            edges.push(edge(
+                GraphEdgeKind::Contains,
+                &krate,
+                file,
+                EdgeProvenanceDto::Syn,
+            ));
+            edges.push(edge(
+                GraphEdgeKind::Defines,
+                file,
+                module,
+                EdgeProvenanceDto::Normalized,
+            ));
+        }
+        for (file, item) in [
+            (&files[0], &repository),
+            (&files[1], &memory),
+            (&files[1], &memory_impl),
+            (&files[1], &open),
+            (&files[2], &run),
+        ] {
+            edges.push(edge(
+                GraphEdgeKind::Defines,
+                file,
+                item,
+                EdgeProvenanceDto::Syn,
+            ));
+        }
+        edges.push(edge(
+            GraphEdgeKind::Defines,
+            &files[1],
+            &memory_symbol,
+            EdgeProvenanceDto::RustAnalyzer,
+        ));
+        edges.push(edge(
+            GraphEdgeKind::Implements,
+            &memory_impl,
+            &modules[1],
+            EdgeProvenanceDto::Syn,
+        ));
+        edges.push(edge(
+            GraphEdgeKind::Calls,
+            &run,
+            &open,
+            EdgeProvenanceDto::Syn,
+        ));
+
+        let mut nodes = vec![
+            krate,
+            repository,
+            memory,
+            memory_symbol,
+            memory_impl,
+            open,
+            run,
+        ];
+        nodes.extend(files);
+        nodes.extend(modules);
+        GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes,
+            edges,
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    #[test]
+    fn computes_martin_metrics_per_module_and_crate() {
+        let metrics = coupling_metrics(&layered_snapshot());
+        let module = |path: &str| &metrics[&stable_node_id(GraphNodeKind::Module, &[path])];
+
+        let api = module("src/api.rs");
+        assert_eq!((api.afferent_coupling, api.efferent_coupling), (1, 0));
+        assert_eq!(api.instability, 0.0);
+        assert_eq!(api.abstractness, 1.0);
+        assert_eq!(api.distance_from_main_sequence, 0.0);
+
+        let store = module("src/store.rs");
+        assert_eq!((store.afferent_coupling, store.efferent_coupling), (1, 1));
+        assert_eq!(store.instability, 0.5);
+        assert_eq!((store.abstract_types, store.concrete_types), (0, 1));
+        assert_eq!(store.distance_from_main_sequence, 0.5);
+
+        let app = module("src/app.rs");
+        assert_eq!(app.instability, 1.0);
+        assert_eq!(app.distance_from_main_sequence, 0.0);
+
+        let krate = &metrics[&stable_node_id(GraphNodeKind::Crate, &["fixture"])];
+        assert_eq!((krate.afferent_coupling, krate.efferent_coupling), (0, 0));
+        assert_eq!(krate.abstractness, 0.5);
+    }
+
+    #[test]
+    fn attached_metrics_round_trip_through_the_report() {
+        let mut snapshot = layered_snapshot();
+        attach_coupling_metrics(&mut snapshot);
+
+        let report = GraphMetricsReportDto::from_snapshot(&snapshot);
+        assert_eq!(report.metrics.len(), 4);
+        assert!(report
+            .metrics
+            .values()
+            .all(|metrics| metrics.coupling.is_some()));
+
+        let json = serde_json::to_value(&report).expect("serialize metrics report");
+        let api_id = stable_node_id(GraphNodeKind::Module, &["src/api.rs"]);
+        assert_eq!(json["metrics"][&api_id]["coupling"]["afferentCoupling"], 1);
+        assert_eq!(
+            json["metrics"][&api_id]["coupling"]["distanceFromMainSequence"],
+            0.0
+        );
+    }
+
+    #[test]
+    fn rolls_up_function_complexity_and_ranks_offenders() {
+        let mut snapshot = layered_snapshot();
+        for (label, cyclomatic_complexity, lines_of_code) in [("open", 4, 12), ("run", 2, 30)] {
+            let node = snapshot
+                .nodes
+                .iter_mut()
+                .find(|node| node.label == label)
+                .expect("function node");
+            node.metrics = Some(NodeMetricsDto {
+                complexity: Some(FunctionComplexityDto {
+                    lines_of_code,
+                    cyclomatic_complexity,
+                    unwrap_count: 1,
+                    ..FunctionComplexityDto::default()
+                }),
+                ..NodeMetricsDto::default()
+            });
+        }
+
+        attach_complexity_rollups(&mut snapshot);
+
+        let rollup = |id: &str| {
+            snapshot
+                .nodes
+                .iter()
+                .find(|node| node.id == id)
+                .and_then(|node| node.metrics.as_ref()?.complexity_rollup.clone())
+                .expect("complexity rollup")
+        };
+        let store_file = rollup(&stable_node_id(GraphNodeKind::File, &["src/store.rs"]));
+        let store_module = rollup(&stable_node_id(GraphNodeKind::Module, &["src/store.rs"]));
+        assert_eq!(store_file, store_module);
+        assert_eq!(store_file.function_count, 1);
+        assert_eq!(store_file.max_cyclomatic_complexity, 4);
+        assert_eq!(store_file.unwrap_count, 1);
+
+        let by_complexity = complexity_offenders(
+            &snapshot,
+            FunctionComplexityMetric::CyclomaticComplexity,
+            10,
+        );
+        assert_eq!(
+            by_complexity
+                .iter()
+                .map(|offender| offender.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["open", "run"]
+        );
+        let by_size = complexity_offenders(&snapshot, FunctionComplexityMetric::LinesOfCode, 1);
+        assert_eq!(by_size.len(), 1);
+        assert_eq!(by_size[0].label, "run");
+    }
+}
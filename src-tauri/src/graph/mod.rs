@@ -1,15 +1,33 @@
 //! Versioned architecture graph contracts and normalization.
 
+pub mod api_diff;
+pub mod churn;
+pub mod codeowners;
+pub mod concurrency;
+pub mod coverage;
+pub mod cycles;
+pub mod dead_code;
+pub mod error_flow;
+pub mod metrics;
+mod ownership;
+pub mod public_api;
+pub mod query;
 pub mod relations;
+pub mod rules;
+pub mod search;
+pub mod state_machine;
+pub mod unsafe_code;
+pub mod wire_contract;
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
 use crate::source::ValidatedRepoPath;
 
-pub const GRAPH_SCHEMA_VERSION: u32 = 1;
-pub const DIRECTORY_GRAPH_SCHEMA_VERSION: u32 = 1;
+pub const GRAPH_SCHEMA_VERSION: u32 = 2;
+pub const DIRECTORY_GRAPH_SCHEMA_VERSION: u32 = 2;
 
 const DIRECTORY_GRAPH_ROOT_ID: &str = "repo:.";
 const DIRECTORY_GRAPH_ROOT_PATH: &str = ".";
@@ -28,7 +46,7 @@ const IGNORED_DIRECTORY_NAMES: &[&str] = &[
     "venv",
 ];
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphSnapshotDto {
     pub schema_version: u32,
@@ -37,15 +55,107 @@ pub struct GraphSnapshotDto {
     pub nodes: Vec<GraphNodeDto>,
     pub edges: Vec<GraphEdgeDto>,
     pub diagnostics: Vec<AnalyzerDiagnosticDto>,
+    /// Build configuration the snapshot was extracted for; absent for
+    /// snapshots that were not produced by cfg-aware extraction.
+    pub configuration: Option<ActiveBuildConfigurationDto>,
+}
+
+/// Requested build configuration for analysis, mirroring Cargo's feature
+/// flags plus the cfg values a target would set. Unset target fields use the
+/// host target.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BuildConfigurationDto {
+    /// Feature names enabled on every workspace crate that declares them, or
+    /// `crate/feature` for a single crate.
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    pub target_os: Option<String>,
+    pub target_family: Option<String>,
+    pub target_arch: Option<String>,
+    pub target_pointer_width: Option<String>,
+    pub target_endian: Option<String>,
+    pub target_env: Option<String>,
+    pub test: bool,
+    /// Disables `debug_assertions`.
+    pub release: bool,
+    /// Extra `--cfg` values such as `tokio_unstable` or `key="value"`.
+    pub cfgs: Vec<String>,
+    /// Drops items the configuration compiles out instead of keeping them
+    /// with their `cfg` predicate.
+    pub prune_inactive: bool,
 }
 
+/// Resolved configuration an analysis snapshot was evaluated against.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct ActiveBuildConfigurationDto {
+    /// Enabled features per workspace crate after default and transitive
+    /// feature expansion.
+    pub features_by_crate: BTreeMap<String, Vec<String>>,
+    pub target_os: String,
+    pub target_family: String,
+    pub target_arch: String,
+    pub target_pointer_width: String,
+    pub target_endian: String,
+    pub target_env: String,
+    pub test: bool,
+    pub debug_assertions: bool,
+    pub cfgs: Vec<String>,
+    pub prune_inactive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GraphNodeDto {
     pub id: String,
     pub kind: GraphNodeKind,
     pub label: String,
     pub source_range: Option<SourceRangeDto>,
+    pub metrics: Option<metrics::NodeMetricsDto>,
+    /// Declared visibility for items; absent for files, crates, and synthetic
+    /// import targets.
+    pub visibility: Option<NodeVisibilityDto>,
+    /// Outer attribute paths such as `test` or `tauri::command`, excluding doc
+    /// comments.
+    pub attributes: Vec<String>,
+    /// Trait paths named in `#[derive(...)]` on structs, enums, and unions.
+    pub derives: Vec<String>,
+    /// Normalized declaration without bodies, attributes, or private fields,
+    /// such as `pub fn load(path: &str) -> Result<Config, Error>`.
+    pub signature: Option<String>,
+    /// Paths such as `engine::config::Config` under which the item is
+    /// reachable from outside its library crate, through `pub` modules or
+    /// `pub use` re-exports; empty for internal items.
+    pub public_paths: Vec<String>,
+    /// `unsafe` declarations and blocks owned by the item; empty for safe
+    /// items and for nodes that are not functions, impls, or traits.
+    pub unsafe_sites: Vec<unsafe_code::UnsafeSiteDto>,
+    /// Error type, `?` sites, and conversions of functions and methods, the
+    /// conversion of `From` impls, and `#[from]` fields of error enums.
+    pub error_flow: Option<error_flow::ErrorFlowFactsDto>,
+    /// Serde wire shape of structs and enums deriving `Serialize` or
+    /// `Deserialize`.
+    pub wire_contract: Option<wire_contract::WireContractDto>,
+    /// State enum variants the function or method assigns to, or constructs
+    /// into, a field.
+    pub state_assignments: Vec<state_machine::StateAssignmentDto>,
+    /// Effective `cfg` predicate including enclosing items and file-level
+    /// `#![cfg]`, such as `all(feature = "cli", not(test))`; absent when
+    /// unconditional.
+    pub cfg: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeVisibilityDto {
+    Public,
+    Crate,
+    Super,
+    /// `pub(in path)` naming a module other than `crate` or `super`.
+    Restricted,
+    Private,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -57,6 +167,42 @@ pub struct GraphEdgeDto {
     pub target_id: String,
     pub provenance: EdgeProvenanceDto,
     pub confidence: EdgeConfidenceDto,
+    pub evidence: Option<SourceRangeDto>,
+    /// Cargo dependency facts; present only on `DependsOn` edges.
+    pub dependency: Option<CrateDependencyDto>,
+    /// Effective `cfg` predicate of the item or statement that produced the
+    /// edge; absent when unconditional.
+    pub cfg: Option<String>,
+}
+
+/// One resolved Cargo dependency between a workspace crate and another
+/// package. Declarations of the same package under several dependency
+/// sections are merged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrateDependencyDto {
+    pub kinds: Vec<DependencyKindDto>,
+    pub version_req: String,
+    pub resolved_version: String,
+    pub optional: bool,
+    pub default_features: bool,
+    /// Features requested by the dependent crate's manifest.
+    pub requested_features: Vec<String>,
+    /// Features enabled on the dependency after workspace feature
+    /// unification.
+    pub enabled_features: Vec<String>,
+    /// Extern crate name when the manifest renames the dependency.
+    pub rename: Option<String>,
+    /// Platform predicates such as `cfg(windows)`; empty when unconditional.
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKindDto {
+    Normal,
+    Dev,
+    Build,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -64,6 +210,7 @@ pub struct GraphEdgeDto {
 pub enum GraphNodeKind {
     Workspace,
     Crate,
+    ExternalCrate,
     Module,
     File,
     Struct,
@@ -72,7 +219,18 @@ pub enum GraphNodeKind {
     Impl,
     Function,
     Method,
+    Macro,
+    Const,
+    Static,
+    TypeAlias,
+    Union,
     TauriCommand,
+    /// Closure or future handed to `tokio::spawn`, `spawn_blocking`, or
+    /// `std::thread::spawn`.
+    Task,
+    /// Lock, atomic, channel, or signal shared between tasks: a struct field
+    /// such as `AppState.config` or a channel created in a function body.
+    SyncPrimitive,
 }
 
 impl GraphNodeKind {
@@ -80,6 +238,7 @@ impl GraphNodeKind {
         match self {
             Self::Workspace => "workspace",
             Self::Crate => "crate",
+            Self::ExternalCrate => "external_crate",
             Self::Module => "module",
             Self::File => "file",
             Self::Struct => "struct",
@@ -88,7 +247,14 @@ impl GraphNodeKind {
             Self::Impl => "impl",
             Self::Function => "function",
             Self::Method => "method",
+            Self::Macro => "macro",
+            Self::Const => "const",
+            Self::Static => "static",
+            Self::TypeAlias => "type_alias",
+            Self::Union => "union",
             Self::TauriCommand => "tauri_command",
+            Self::Task => "task",
+            Self::SyncPrimitive => "sync_primitive",
         }
     }
 }
@@ -104,6 +270,14 @@ pub enum GraphEdgeKind {
     Implements,
     References,
     ExposesCommand,
+    DependsOn,
+    InvokesMacro,
+    Spawns,
+    Sends,
+    Receives,
+    /// `lock`, `read`, or `write` on a mutex or read-write lock. Atomic loads
+    /// and stores are `References` edges to the atomic.
+    Locks,
 }
 
 impl GraphEdgeKind {
@@ -117,6 +291,12 @@ impl GraphEdgeKind {
             Self::Implements => "implements",
             Self::References => "references",
             Self::ExposesCommand => "exposes_command",
+            Self::DependsOn => "depends_on",
+            Self::InvokesMacro => "invokes_macro",
+            Self::Spawns => "spawns",
+            Self::Sends => "sends",
+            Self::Receives => "receives",
+            Self::Locks => "locks",
         }
     }
 }
@@ -153,6 +333,7 @@ pub struct AnalyzerDiagnosticDto {
     pub code: String,
     pub message: String,
     pub source_path: Option<String>,
+    pub evidence: Option<SourceRangeDto>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -175,6 +356,8 @@ pub struct DirectoryGraphNodeDto {
     pub parent_id: Option<String>,
     pub child_ids: Vec<String>,
     pub expanded: bool,
+    /// Git churn of file nodes, present when a churn window was requested.
+    pub churn: Option<churn::FileChurnDto>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -260,6 +443,7 @@ impl DirectoryGraphBuilder {
                 parent_id: None,
                 child_ids,
                 expanded: true,
+                churn: None,
             },
         );
 
@@ -331,6 +515,7 @@ impl DirectoryGraphBuilder {
                 parent_id: Some(parent_id.to_owned()),
                 child_ids: nested_child_ids,
                 expanded: false,
+                churn: None,
             });
         }
 
@@ -507,6 +692,17 @@ mod tests {
             kind: GraphNodeKind::Function,
             label: "main".to_string(),
             source_range: Some(source_range),
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
         };
         let edge = GraphEdgeDto {
             id: "contains:crate:function".to_string(),
@@ -515,6 +711,9 @@ mod tests {
             target_id: "function:main".to_string(),
             provenance: EdgeProvenanceDto::Normalized,
             confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: None,
+            cfg: None,
         };
         let snapshot = GraphSnapshotDto {
             schema_version: GRAPH_SCHEMA_VERSION,
@@ -526,7 +725,9 @@ mod tests {
                 code: "partial_macro".to_string(),
                 message: "macro expansion skipped".to_string(),
                 source_path: Some("src/main.rs".to_string()),
+                evidence: None,
             }],
+            configuration: None,
         };
 
         let serialized = serde_json::to_string(&snapshot).expect("serialize graph snapshot");
@@ -534,7 +735,7 @@ mod tests {
             serde_json::from_str(&serialized).expect("deserialize graph snapshot");
 
         assert_eq!(decoded, snapshot);
-        assert!(serialized.contains(r#""schemaVersion":1"#));
+        assert!(serialized.contains(r#""schemaVersion":2"#));
         assert!(serialized.contains(r#""kind":"function""#));
         assert!(serialized.contains(r#""provenance":"normalized""#));
     }
@@ -562,6 +763,10 @@ mod tests {
             .iter()
             .any(|edge| edge.id == "tree:repo:.:dir:src"));
 
+        let serialized = serde_json::to_string(&snapshot).expect("serialize directory graph");
+        assert!(serialized.contains(r#""schemaVersion":2"#));
+        assert!(serialized.contains(r#""churn":null"#));
+
         fs::remove_dir_all(repo).expect("cleanup fixture");
     }
 
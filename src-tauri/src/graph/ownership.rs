@@ -0,0 +1,238 @@
+//! Node ownership projections shared by graph-level analyses.
+
+use std::collections::BTreeMap;
+
+use super::{EdgeProvenanceDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto};
+
+const IMPORT_NODE_ID_PREFIX: &str = "module:import:";
+
+/// Owning file, module path, and crate for every node in a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct NodeOwnership {
+    file_ids: BTreeMap<String, String>,
+    module_paths: BTreeMap<String, String>,
+    crate_ids: BTreeMap<String, String>,
+    module_ids_by_path: BTreeMap<(String, String), String>,
+}
+
+impl NodeOwnership {
+    pub(super) fn from_snapshot(snapshot: &GraphSnapshotDto) -> Self {
+        let node_by_id = snapshot
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect::<BTreeMap<_, _>>();
+        let file_ids = propagate_owner(snapshot, &node_by_id, GraphNodeKind::File);
+        let crate_ids = propagate_owner(snapshot, &node_by_id, GraphNodeKind::Crate);
+        let module_paths = module_paths_by_node_id(snapshot, &node_by_id);
+        let mut module_ids_by_path = BTreeMap::new();
+
+        for node in &snapshot.nodes {
+            if node.kind != GraphNodeKind::Module || node.id.starts_with(IMPORT_NODE_ID_PREFIX) {
+                continue;
+            }
+            if let Some(module_path) = module_paths.get(&node.id) {
+                let crate_id = crate_ids.get(&node.id).cloned().unwrap_or_default();
+                module_ids_by_path
+                    .entry((crate_id, module_path.clone()))
+                    .or_insert_with(|| node.id.clone());
+            }
+        }
+
+        Self {
+            file_ids,
+            module_paths,
+            crate_ids,
+            module_ids_by_path,
+        }
+    }
+
+    pub(super) fn file_id(&self, node_id: &str) -> Option<&str> {
+        self.file_ids.get(node_id).map(String::as_str)
+    }
+
+    pub(super) fn crate_id(&self, node_id: &str) -> Option<&str> {
+        self.crate_ids.get(node_id).map(String::as_str)
+    }
+
+    /// Crate-relative module path such as `graph::rules`.
+    pub(super) fn module_path(&self, node_id: &str) -> Option<&str> {
+        self.module_paths.get(node_id).map(String::as_str)
+    }
+
+    /// Deepest extracted module node whose path owns the node. Unresolved
+    /// `use` targets such as `graph::rules::Rule` belong to no crate, so they
+    /// resolve to `graph::rules` inside `fallback_crate_id`.
+    pub(super) fn module_id(&self, node_id: &str, fallback_crate_id: Option<&str>) -> Option<&str> {
+        let crate_id = self
+            .crate_id(node_id)
+            .or(fallback_crate_id)
+            .unwrap_or_default()
+            .to_string();
+        let mut module_path = self.module_path(node_id)?;
+        loop {
+            if let Some(module_id) = self
+                .module_ids_by_path
+                .get(&(crate_id.clone(), module_path.to_string()))
+            {
+                return Some(module_id);
+            }
+            module_path = module_path.rsplit_once("::")?.0;
+        }
+    }
+
+    pub(super) fn owning_file_id(
+        &self,
+        node_id: &str,
+        fallback_crate_id: Option<&str>,
+    ) -> Option<&str> {
+        self.file_id(self.anchor_id(node_id, fallback_crate_id)?)
+    }
+
+    pub(super) fn owning_module_id(
+        &self,
+        node_id: &str,
+        fallback_crate_id: Option<&str>,
+    ) -> Option<&str> {
+        self.module_id(
+            self.anchor_id(node_id, fallback_crate_id)?,
+            fallback_crate_id,
+        )
+    }
+
+    pub(super) fn owning_crate_id(
+        &self,
+        node_id: &str,
+        fallback_crate_id: Option<&str>,
+    ) -> Option<&str> {
+        self.crate_id(self.anchor_id(node_id, fallback_crate_id)?)
+    }
+
+    /// Nodes outside any file or crate, such as unresolved `use` targets, are
+    /// anchored on the extracted module they name.
+    fn anchor_id<'a>(
+        &'a self,
+        node_id: &'a str,
+        fallback_crate_id: Option<&str>,
+    ) -> Option<&'a str> {
+        if self.file_ids.contains_key(node_id) || self.crate_ids.contains_key(node_id) {
+            Some(node_id)
+        } else {
+            self.module_id(node_id, fallback_crate_id)
+        }
+    }
+}
+
+/// Assigns every node reachable from a `kind` node over containment edges to
+/// that node. The first owner found wins.
+fn propagate_owner(
+    snapshot: &GraphSnapshotDto,
+    node_by_id: &BTreeMap<&str, &GraphNodeDto>,
+    kind: GraphNodeKind,
+) -> BTreeMap<String, String> {
+    let mut owners = snapshot
+        .nodes
+        .iter()
+        .filter(|node| node.kind == kind)
+        .map(|node| (node.id.clone(), node.id.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for edge in &snapshot.edges {
+            if !is_containment_edge(&edge.kind)
+                || owners.contains_key(&edge.target_id)
+                || !node_by_id.contains_key(edge.target_id.as_str())
+            {
+                continue;
+            }
+
+            if let Some(owner_id) = owners.get(&edge.source_id).cloned() {
+                owners.insert(edge.target_id.clone(), owner_id);
+                changed = true;
+            }
+        }
+    }
+
+    owners
+}
+
+/// Assigns every node the crate-relative module path that owns it. File nodes
+/// take the path of the module they define, symbols inherit their owner's
+/// path, and unresolved `use` targets keep their import path.
+fn module_paths_by_node_id(
+    snapshot: &GraphSnapshotDto,
+    node_by_id: &BTreeMap<&str, &GraphNodeDto>,
+) -> BTreeMap<String, String> {
+    let mut module_paths = BTreeMap::new();
+
+    for node in &snapshot.nodes {
+        let is_import_target =
+            node.kind == GraphNodeKind::Module && node.id.starts_with(IMPORT_NODE_ID_PREFIX);
+        let is_relative_import = matches!(node.label.split("::").next(), Some("self" | "super"));
+        if is_import_target && !is_relative_import {
+            module_paths.insert(node.id.clone(), normalized_module_path(&node.label));
+        }
+    }
+
+    for edge in &snapshot.edges {
+        if edge.kind != GraphEdgeKind::Defines || edge.provenance != EdgeProvenanceDto::Normalized {
+            continue;
+        }
+
+        let (Some(source), Some(target)) = (
+            node_by_id.get(edge.source_id.as_str()),
+            node_by_id.get(edge.target_id.as_str()),
+        ) else {
+            continue;
+        };
+
+        if source.kind == GraphNodeKind::File && target.kind == GraphNodeKind::Module {
+            let module_path = normalized_module_path(&target.label);
+            module_paths.insert(source.id.clone(), module_path.clone());
+            module_paths.insert(target.id.clone(), module_path);
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for edge in &snapshot.edges {
+            if !is_containment_edge(&edge.kind) || module_paths.contains_key(&edge.target_id) {
+                continue;
+            }
+
+            let Some(owner_path) = module_paths.get(&edge.source_id).cloned() else {
+                continue;
+            };
+            let Some(target) = node_by_id.get(edge.target_id.as_str()) else {
+                continue;
+            };
+
+            let module_path = if target.kind == GraphNodeKind::Module {
+                format!("{owner_path}::{}", target.label)
+            } else {
+                owner_path
+            };
+            module_paths.insert(edge.target_id.clone(), module_path);
+            changed = true;
+        }
+    }
+
+    module_paths
+}
+
+fn is_containment_edge(kind: &GraphEdgeKind) -> bool {
+    matches!(
+        kind,
+        GraphEdgeKind::Contains | GraphEdgeKind::Defines | GraphEdgeKind::DefinesMethod
+    )
+}
+
+pub(super) fn normalized_module_path(path: &str) -> String {
+    let path = path.trim();
+    path.strip_prefix("crate::").unwrap_or(path).to_string()
+}
@@ -0,0 +1,181 @@
+//! Per-crate public API surface from extracted public paths and signatures.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::ownership::NodeOwnership;
+use super::{GraphNodeKind, GraphSnapshotDto, SourceRangeDto};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicApiReportDto {
+    pub crates: Vec<CratePublicApiDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CratePublicApiDto {
+    pub crate_id: String,
+    pub crate_name: String,
+    /// One entry per public path, sorted by path, so an item re-exported
+    /// under several names is listed once for each.
+    pub items: Vec<PublicApiItemDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicApiItemDto {
+    pub path: String,
+    pub node_id: String,
+    pub kind: GraphNodeKind,
+    pub signature: Option<String>,
+    pub source_range: Option<SourceRangeDto>,
+}
+
+/// Lists every node with a public path, grouped by owning crate. Crate root
+/// modules are left out since their path is just the crate name.
+pub fn public_api_report(snapshot: &GraphSnapshotDto) -> PublicApiReportDto {
+    let ownership = NodeOwnership::from_snapshot(snapshot);
+    let crate_names = snapshot
+        .nodes
+        .iter()
+        .filter(|node| node.kind == GraphNodeKind::Crate)
+        .map(|node| (node.id.as_str(), node.label.as_str()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut items_by_crate = BTreeMap::<&str, Vec<PublicApiItemDto>>::new();
+    for node in &snapshot.nodes {
+        let Some(crate_id) = ownership.crate_id(&node.id) else {
+            continue;
+        };
+        for path in &node.public_paths {
+            if !path.contains("::") {
+                continue;
+            }
+            items_by_crate
+                .entry(crate_id)
+                .or_default()
+                .push(PublicApiItemDto {
+                    path: path.clone(),
+                    node_id: node.id.clone(),
+                    kind: node.kind.clone(),
+                    signature: node.signature.clone(),
+                    source_range: node.source_range.clone(),
+                });
+        }
+    }
+
+    PublicApiReportDto {
+        crates: items_by_crate
+            .into_iter()
+            .map(|(crate_id, mut items)| {
+                items.sort_by(|left, right| {
+                    left.path
+                        .cmp(&right.path)
+                        .then_with(|| left.node_id.cmp(&right.node_id))
+                });
+                CratePublicApiDto {
+                    crate_id: crate_id.to_string(),
+                    crate_name: crate_names
+                        .get(crate_id)
+                        .copied()
+                        .unwrap_or(crate_id)
+                        .to_string(),
+                    items,
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::public_api_report;
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, NodeVisibilityDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn node(kind: GraphNodeKind, label: &str, public_paths: &[&str]) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &["engine", label]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: Some(NodeVisibilityDto::Public),
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: Some(format!("pub {label}")),
+            public_paths: public_paths.iter().map(|path| path.to_string()).collect(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn contains(source: &GraphNodeDto, target: &GraphNodeDto) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: stable_edge_id(GraphEdgeKind::Contains, &source.id, &target.id),
+            kind: GraphEdgeKind::Contains,
+            source_id: source.id.clone(),
+            target_id: target.id.clone(),
+            provenance: EdgeProvenanceDto::Normalized,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    #[test]
+    fn groups_public_paths_by_crate_and_sorts_by_path() {
+        let engine = node(GraphNodeKind::Crate, "engine", &[]);
+        let root = node(GraphNodeKind::Module, "lib", &["engine"]);
+        let config = node(
+            GraphNodeKind::Struct,
+            "Config",
+            &["engine::Config", "engine::config::Config"],
+        );
+        let internal = node(GraphNodeKind::Function, "internal", &[]);
+        let snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/repo".to_string(),
+            generated_at: "0".to_string(),
+            edges: vec![
+                contains(&engine, &root),
+                contains(&root, &config),
+                contains(&root, &internal),
+            ],
+            nodes: vec![engine.clone(), root, config.clone(), internal],
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        let report = public_api_report(&snapshot);
+
+        assert_eq!(report.crates.len(), 1);
+        assert_eq!(report.crates[0].crate_id, engine.id);
+        assert_eq!(report.crates[0].crate_name, "engine");
+        let paths = report.crates[0]
+            .items
+            .iter()
+            .map(|item| (item.path.as_str(), item.signature.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                ("engine::Config", Some("pub Config")),
+                ("engine::config::Config", Some("pub Config")),
+            ]
+        );
+        assert!(report.crates[0]
+            .items
+            .iter()
+            .all(|item| item.node_id == config.id));
+    }
+}
@@ -0,0 +1,671 @@
+//! Backend graph queries that return reduced snapshots for the UI and CLI.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use super::{GraphEdgeDto, GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto};
+
+pub const MAX_QUERY_DEPTH: u32 = 16;
+pub const MAX_SHORTEST_PATHS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "operation",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum GraphQueryDto {
+    /// Nodes within `hops` edges of `node_id`.
+    Neighborhood {
+        node_id: String,
+        hops: u32,
+        #[serde(default)]
+        edge_kinds: Vec<GraphEdgeKind>,
+        #[serde(default)]
+        direction: GraphQueryDirectionDto,
+    },
+    /// Every shortest path from `from_node_id` to `to_node_id`, capped at
+    /// `MAX_SHORTEST_PATHS`.
+    ShortestPaths {
+        from_node_id: String,
+        to_node_id: String,
+        #[serde(default)]
+        edge_kinds: Vec<GraphEdgeKind>,
+        #[serde(default)]
+        direction: GraphQueryDirectionDto,
+    },
+    /// Nodes matching the filter and the edges between them.
+    Filter { filter: GraphNodeFilterDto },
+    /// Nodes reachable from the roots within `depth` edges, then filtered.
+    /// Roots are always kept.
+    Subgraph {
+        root_node_ids: Vec<String>,
+        depth: u32,
+        #[serde(default)]
+        edge_kinds: Vec<GraphEdgeKind>,
+        #[serde(default)]
+        direction: GraphQueryDirectionDto,
+        #[serde(default)]
+        filter: GraphNodeFilterDto,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphQueryDirectionDto {
+    Outgoing,
+    Incoming,
+    #[default]
+    Both,
+}
+
+/// Empty fields match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GraphNodeFilterDto {
+    pub kinds: Vec<GraphNodeKind>,
+    /// Glob over the node source path: `*` and `?` stay within one path
+    /// segment, `**` spans segments.
+    pub path_glob: Option<String>,
+    /// Case-insensitive label substring.
+    pub label: Option<String>,
+    /// Keeps only nodes reachable from outside their library crate, meaning
+    /// nodes with at least one public path.
+    pub public_api_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQueryResultDto {
+    pub snapshot: GraphSnapshotDto,
+    /// Node ID sequences for path queries; empty for other operations.
+    pub paths: Vec<Vec<String>>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum GraphQueryError {
+    #[error("graph node '{0}' was not found")]
+    UnknownNode(String),
+    #[error("query depth {depth} exceeds the limit of {max}")]
+    DepthTooLarge { depth: u32, max: u32 },
+}
+
+impl GraphQueryDto {
+    pub fn execute(
+        &self,
+        snapshot: &GraphSnapshotDto,
+    ) -> Result<GraphQueryResultDto, GraphQueryError> {
+        let index = GraphIndex::new(snapshot);
+
+        match self {
+            Self::Neighborhood {
+                node_id,
+                hops,
+                edge_kinds,
+                direction,
+            } => {
+                check_depth(*hops)?;
+                index.require(node_id)?;
+                let distances = index.distances(&[node_id.as_str()], *hops, edge_kinds, *direction);
+                let node_ids = distances.into_keys().collect();
+                Ok(index.result(&node_ids, edge_kinds))
+            }
+            Self::ShortestPaths {
+                from_node_id,
+                to_node_id,
+                edge_kinds,
+                direction,
+            } => {
+                index.require(from_node_id)?;
+                index.require(to_node_id)?;
+                let paths = index.shortest_paths(from_node_id, to_node_id, edge_kinds, *direction);
+                let node_ids = paths
+                    .iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect::<BTreeSet<_>>();
+                let hops = paths
+                    .iter()
+                    .flat_map(|path| path.windows(2))
+                    .map(|hop| (hop[0].as_str(), hop[1].as_str()))
+                    .collect::<BTreeSet<_>>();
+                let mut result = index.result(&node_ids, edge_kinds);
+                result.snapshot.edges.retain(|edge| {
+                    hops.contains(&(edge.source_id.as_str(), edge.target_id.as_str()))
+                        || (*direction != GraphQueryDirectionDto::Outgoing
+                            && hops.contains(&(edge.target_id.as_str(), edge.source_id.as_str())))
+                });
+                result.paths = paths;
+                Ok(result)
+            }
+            Self::Filter { filter } => {
+                let node_ids = snapshot
+                    .nodes
+                    .iter()
+                    .filter(|node| filter.matches(node))
+                    .map(|node| node.id.as_str())
+                    .collect();
+                Ok(index.result(&node_ids, &[]))
+            }
+            Self::Subgraph {
+                root_node_ids,
+                depth,
+                edge_kinds,
+                direction,
+                filter,
+            } => {
+                check_depth(*depth)?;
+                for root_node_id in root_node_ids {
+                    index.require(root_node_id)?;
+                }
+                let roots = root_node_ids.iter().map(String::as_str).collect::<Vec<_>>();
+                let node_ids = index
+                    .distances(&roots, *depth, edge_kinds, *direction)
+                    .into_keys()
+                    .filter(|node_id| {
+                        roots.contains(node_id)
+                            || index
+                                .node_by_id
+                                .get(node_id)
+                                .is_some_and(|node| filter.matches(node))
+                    })
+                    .collect();
+                Ok(index.result(&node_ids, edge_kinds))
+            }
+        }
+    }
+}
+
+impl GraphNodeFilterDto {
+    pub fn matches(&self, node: &GraphNodeDto) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&node.kind) {
+            return false;
+        }
+
+        if self.public_api_only && node.public_paths.is_empty() {
+            return false;
+        }
+
+        if let Some(label) = &self.label {
+            if !node.label.to_lowercase().contains(&label.to_lowercase()) {
+                return false;
+            }
+        }
+
+        match &self.path_glob {
+            Some(pattern) => node
+                .source_range
+                .as_ref()
+                .is_some_and(|range| path_glob_matches(pattern, &range.path)),
+            None => true,
+        }
+    }
+}
+
+struct GraphIndex<'a> {
+    snapshot: &'a GraphSnapshotDto,
+    node_by_id: BTreeMap<&'a str, &'a GraphNodeDto>,
+    outgoing: BTreeMap<&'a str, Vec<&'a GraphEdgeDto>>,
+    incoming: BTreeMap<&'a str, Vec<&'a GraphEdgeDto>>,
+}
+
+impl<'a> GraphIndex<'a> {
+    fn new(snapshot: &'a GraphSnapshotDto) -> Self {
+        let mut outgoing = BTreeMap::<_, Vec<_>>::new();
+        let mut incoming = BTreeMap::<_, Vec<_>>::new();
+        for edge in &snapshot.edges {
+            outgoing
+                .entry(edge.source_id.as_str())
+                .or_default()
+                .push(edge);
+            incoming
+                .entry(edge.target_id.as_str())
+                .or_default()
+                .push(edge);
+        }
+
+        Self {
+            snapshot,
+            node_by_id: snapshot
+                .nodes
+                .iter()
+                .map(|node| (node.id.as_str(), node))
+                .collect(),
+            outgoing,
+            incoming,
+        }
+    }
+
+    fn require(&self, node_id: &str) -> Result<(), GraphQueryError> {
+        if self.node_by_id.contains_key(node_id) {
+            Ok(())
+        } else {
+            Err(GraphQueryError::UnknownNode(node_id.to_string()))
+        }
+    }
+
+    /// Neighbours of `node_id` over allowed edge kinds, in edge order.
+    fn neighbors(
+        &self,
+        node_id: &str,
+        edge_kinds: &[GraphEdgeKind],
+        direction: GraphQueryDirectionDto,
+    ) -> Vec<&'a str> {
+        let allowed =
+            |edge: &&&GraphEdgeDto| edge_kinds.is_empty() || edge_kinds.contains(&edge.kind);
+        let mut neighbors = Vec::new();
+
+        if direction != GraphQueryDirectionDto::Incoming {
+            neighbors.extend(
+                self.outgoing
+                    .get(node_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(allowed)
+                    .map(|edge| edge.target_id.as_str()),
+            );
+        }
+        if direction != GraphQueryDirectionDto::Outgoing {
+            neighbors.extend(
+                self.incoming
+                    .get(node_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(allowed)
+                    .map(|edge| edge.source_id.as_str()),
+            );
+        }
+
+        neighbors.retain(|neighbor| self.node_by_id.contains_key(neighbor));
+        neighbors
+    }
+
+    /// Breadth-first distances from the roots, stopping at `max_depth`.
+    fn distances(
+        &self,
+        roots: &[&str],
+        max_depth: u32,
+        edge_kinds: &[GraphEdgeKind],
+        direction: GraphQueryDirectionDto,
+    ) -> BTreeMap<&'a str, u32> {
+        let mut distances: BTreeMap<&'a str, u32> = BTreeMap::new();
+        let mut queue: VecDeque<&'a str> = VecDeque::new();
+        for root in roots {
+            if let Some((&root, _)) = self.node_by_id.get_key_value(*root) {
+                distances.insert(root, 0);
+                queue.push_back(root);
+            }
+        }
+
+        while let Some(node_id) = queue.pop_front() {
+            let depth = distances[node_id];
+            if depth == max_depth {
+                continue;
+            }
+            for neighbor in self.neighbors(node_id, edge_kinds, direction) {
+                if !distances.contains_key(neighbor) {
+                    distances.insert(neighbor, depth + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        distances
+    }
+
+    fn shortest_paths(
+        &self,
+        from_node_id: &str,
+        to_node_id: &str,
+        edge_kinds: &[GraphEdgeKind],
+        direction: GraphQueryDirectionDto,
+    ) -> Vec<Vec<String>> {
+        let Some((&from_node_id, _)) = self.node_by_id.get_key_value(from_node_id) else {
+            return Vec::new();
+        };
+        let mut distances = BTreeMap::from([(from_node_id, 0_u32)]);
+        let mut predecessors = BTreeMap::<&str, BTreeSet<&str>>::new();
+        let mut queue = VecDeque::from([from_node_id]);
+
+        while let Some(node_id) = queue.pop_front() {
+            let depth = distances[node_id];
+            if distances
+                .get(to_node_id)
+                .is_some_and(|target_depth| depth >= *target_depth)
+            {
+                break;
+            }
+            for neighbor in self.neighbors(node_id, edge_kinds, direction) {
+                match distances.get(neighbor) {
+                    None => {
+                        distances.insert(neighbor, depth + 1);
+                        predecessors.entry(neighbor).or_default().insert(node_id);
+                        queue.push_back(neighbor);
+                    }
+                    Some(neighbor_depth) if *neighbor_depth == depth + 1 => {
+                        predecessors.entry(neighbor).or_default().insert(node_id);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        if !distances.contains_key(to_node_id) {
+            return Vec::new();
+        }
+
+        let mut paths = Vec::new();
+        let mut partial = vec![vec![to_node_id.to_string()]];
+        while let Some(path) = partial.pop() {
+            if paths.len() == MAX_SHORTEST_PATHS {
+                break;
+            }
+            let head = path.last().map(String::as_str).unwrap_or_default();
+            if head == from_node_id {
+                paths.push(path.into_iter().rev().collect::<Vec<_>>());
+                continue;
+            }
+            for predecessor in predecessors.get(head).into_iter().flatten().rev() {
+                let mut extended = path.clone();
+                extended.push(predecessor.to_string());
+                partial.push(extended);
+            }
+        }
+
+        paths.sort();
+        paths
+    }
+
+    fn result(
+        &self,
+        node_ids: &BTreeSet<&str>,
+        edge_kinds: &[GraphEdgeKind],
+    ) -> GraphQueryResultDto {
+        GraphQueryResultDto {
+            snapshot: GraphSnapshotDto {
+                schema_version: self.snapshot.schema_version,
+                source_root: self.snapshot.source_root.clone(),
+                generated_at: self.snapshot.generated_at.clone(),
+                nodes: self
+                    .snapshot
+                    .nodes
+                    .iter()
+                    .filter(|node| node_ids.contains(node.id.as_str()))
+                    .cloned()
+                    .collect(),
+                edges: self
+                    .snapshot
+                    .edges
+                    .iter()
+                    .filter(|edge| edge_kinds.is_empty() || edge_kinds.contains(&edge.kind))
+                    .filter(|edge| {
+                        node_ids.contains(edge.source_id.as_str())
+                            && node_ids.contains(edge.target_id.as_str())
+                    })
+                    .cloned()
+                    .collect(),
+                diagnostics: Vec::new(),
+                configuration: None,
+            },
+            paths: Vec::new(),
+        }
+    }
+}
+
+fn check_depth(depth: u32) -> Result<(), GraphQueryError> {
+    if depth > MAX_QUERY_DEPTH {
+        Err(GraphQueryError::DepthTooLarge {
+            depth,
+            max: MAX_QUERY_DEPTH,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn path_glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    segments_match(&pattern, &path)
+}
+
+pub(super) fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(first, path_rest)| {
+            segment_matches(segment.as_bytes(), first.as_bytes()) && segments_match(rest, path_rest)
+        }),
+    }
+}
+
+fn segment_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| segment_matches(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && segment_matches(rest, &text[1..]),
+        Some((byte, rest)) => text.first() == Some(byte) && segment_matches(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        path_glob_matches, GraphNodeFilterDto, GraphQueryDirectionDto, GraphQueryDto,
+        GraphQueryError,
+    };
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn function(name: &str, path: &str) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(GraphNodeKind::Function, &[name]),
+            kind: GraphNodeKind::Function,
+            label: name.to_string(),
+            source_range: Some(SourceRangeDto {
+                path: path.to_string(),
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 1,
+            }),
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn calls(source: &str, target: &str) -> GraphEdgeDto {
+        let source_id = stable_node_id(GraphNodeKind::Function, &[source]);
+        let target_id = stable_node_id(GraphNodeKind::Function, &[target]);
+        GraphEdgeDto {
+            id: stable_edge_id(GraphEdgeKind::Calls, &source_id, &target_id),
+            kind: GraphEdgeKind::Calls,
+            source_id,
+            target_id,
+            provenance: EdgeProvenanceDto::Syn,
+            confidence: EdgeConfidenceDto::Partial,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    fn id(name: &str) -> String {
+        stable_node_id(GraphNodeKind::Function, &[name])
+    }
+
+    /// A diamond `entry -> {left, right} -> sink` plus a tail `sink -> log`.
+    fn diamond_snapshot() -> GraphSnapshotDto {
+        GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes: vec![
+                function("entry", "src/main.rs"),
+                function("left", "src/graph/left.rs"),
+                function("right", "src/graph/right.rs"),
+                function("sink", "src/store.rs"),
+                function("log", "src/util/log.rs"),
+            ],
+            edges: vec![
+                calls("entry", "left"),
+                calls("entry", "right"),
+                calls("left", "sink"),
+                calls("right", "sink"),
+                calls("sink", "log"),
+            ],
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    fn labels(snapshot: &GraphSnapshotDto) -> Vec<&str> {
+        snapshot
+            .nodes
+            .iter()
+            .map(|node| node.label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn neighborhood_respects_hops_and_direction() {
+        let snapshot = diamond_snapshot();
+        let query = |hops, direction| GraphQueryDto::Neighborhood {
+            node_id: id("sink"),
+            hops,
+            edge_kinds: vec![GraphEdgeKind::Calls],
+            direction,
+        };
+
+        let outgoing = query(1, GraphQueryDirectionDto::Outgoing)
+            .execute(&snapshot)
+            .expect("outgoing neighborhood");
+        assert_eq!(labels(&outgoing.snapshot), vec!["sink", "log"]);
+        assert_eq!(outgoing.snapshot.edges.len(), 1);
+
+        let both = query(2, GraphQueryDirectionDto::Both)
+            .execute(&snapshot)
+            .expect("two hop neighborhood");
+        assert_eq!(labels(&both.snapshot).len(), 5);
+        assert_eq!(both.snapshot.edges.len(), 5);
+    }
+
+    #[test]
+    fn shortest_paths_returns_every_equal_length_route() {
+        let result = GraphQueryDto::ShortestPaths {
+            from_node_id: id("entry"),
+            to_node_id: id("log"),
+            edge_kinds: Vec::new(),
+            direction: GraphQueryDirectionDto::Outgoing,
+        }
+        .execute(&diamond_snapshot())
+        .expect("shortest paths");
+
+        assert_eq!(
+            result.paths,
+            vec![
+                vec![id("entry"), id("left"), id("sink"), id("log")],
+                vec![id("entry"), id("right"), id("sink"), id("log")],
+            ]
+        );
+        assert_eq!(result.snapshot.edges.len(), 5);
+
+        let unreachable = GraphQueryDto::ShortestPaths {
+            from_node_id: id("log"),
+            to_node_id: id("entry"),
+            edge_kinds: Vec::new(),
+            direction: GraphQueryDirectionDto::Outgoing,
+        }
+        .execute(&diamond_snapshot())
+        .expect("empty path result");
+        assert!(unreachable.paths.is_empty());
+        assert!(unreachable.snapshot.nodes.is_empty());
+    }
+
+    #[test]
+    fn filters_and_subgraphs_deserialize_from_camel_case_json() {
+        let filter: GraphQueryDto = serde_json::from_str(
+            r#"{ "operation": "filter", "filter": { "pathGlob": "src/graph/**", "label": "LEFT" } }"#,
+        )
+        .expect("deserialize filter query");
+        let filtered = filter.execute(&diamond_snapshot()).expect("filter query");
+        assert_eq!(labels(&filtered.snapshot), vec!["left"]);
+
+        let subgraph: GraphQueryDto = serde_json::from_str(&format!(
+            r#"{{ "operation": "subgraph", "rootNodeIds": ["{}"], "depth": 2,
+                "direction": "outgoing", "filter": {{ "pathGlob": "src/*.rs" }} }}"#,
+            id("entry")
+        ))
+        .expect("deserialize subgraph query");
+        let subgraph = subgraph
+            .execute(&diamond_snapshot())
+            .expect("subgraph query");
+        assert_eq!(labels(&subgraph.snapshot), vec!["entry", "sink"]);
+        assert!(subgraph.snapshot.edges.is_empty());
+
+        let mut public_snapshot = diamond_snapshot();
+        public_snapshot.nodes[3].public_paths = vec!["fixture::store::sink".to_string()];
+        let public_only: GraphQueryDto = serde_json::from_str(
+            r#"{ "operation": "filter", "filter": { "publicApiOnly": true } }"#,
+        )
+        .expect("deserialize public API filter");
+        let public_only = public_only
+            .execute(&public_snapshot)
+            .expect("public API filter query");
+        assert_eq!(labels(&public_only.snapshot), vec!["sink"]);
+
+        assert_eq!(
+            GraphQueryDto::Filter {
+                filter: GraphNodeFilterDto::default()
+            }
+            .execute(&diamond_snapshot())
+            .expect("match all")
+            .snapshot
+            .nodes
+            .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_nodes_and_deep_queries() {
+        let snapshot = diamond_snapshot();
+
+        assert_eq!(
+            GraphQueryDto::Neighborhood {
+                node_id: "function:missing".to_string(),
+                hops: 1,
+                edge_kinds: Vec::new(),
+                direction: GraphQueryDirectionDto::Both,
+            }
+            .execute(&snapshot),
+            Err(GraphQueryError::UnknownNode("function:missing".to_string()))
+        );
+        assert!(matches!(
+            GraphQueryDto::Neighborhood {
+                node_id: id("entry"),
+                hops: 99,
+                edge_kinds: Vec::new(),
+                direction: GraphQueryDirectionDto::Both,
+            }
+            .execute(&snapshot),
+            Err(GraphQueryError::DepthTooLarge { depth: 99, .. })
+        ));
+        assert!(path_glob_matches("src/**/log.rs", "src/util/log.rs"));
+        assert!(path_glob_matches("src/**", "src/main.rs"));
+        assert!(!path_glob_matches("src/*.rs", "src/util/log.rs"));
+    }
+}
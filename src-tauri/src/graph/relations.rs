@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use super::churn::{CoChangeOptionsDto, FileChurnDto};
+use super::codeowners::CodeOwnershipDto;
 use super::{
     stable_id, AnalyzerDiagnosticDto, DirectoryGraphBuilder, DirectoryGraphError,
     DirectoryGraphNodeKind, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeKind, GraphNodeKind,
@@ -9,10 +12,12 @@ use super::{
 use crate::analyzer::rust_relations::{
     RustImportRelationSnapshotDto, RustImportResolutionStatusDto,
 };
+use crate::source::git::GitCommit;
 use crate::source::ValidatedRepoPath;
 
-pub const FILE_RELATION_GRAPH_SCHEMA_VERSION: u32 = 1;
+pub const FILE_RELATION_GRAPH_SCHEMA_VERSION: u32 = 3;
 const FILE_RELATION_EVIDENCE_SAMPLE_LIMIT: usize = 10;
+const CO_CHANGE_ANALYZER: &str = "git-co-change";
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -37,6 +42,11 @@ pub struct FileRelationNodeDto {
     pub parent_id: Option<String>,
     pub child_ids: Vec<String>,
     pub language: Option<SourceLanguageDto>,
+    /// Git churn of file nodes, present when a churn window was requested.
+    pub churn: Option<FileChurnDto>,
+    /// CODEOWNERS rule of file and directory nodes, present when the source
+    /// root has a CODEOWNERS file with a matching rule.
+    pub ownership: Option<CodeOwnershipDto>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -78,6 +88,8 @@ pub enum FileRelationEdgeKind {
     Tests,
     Configures,
     ImplementsContract,
+    /// Files changed together in enough commits of the local git history.
+    CoChanges,
 }
 
 impl FileRelationEdgeKind {
@@ -96,6 +108,7 @@ impl FileRelationEdgeKind {
             Self::Tests => "tests",
             Self::Configures => "configures",
             Self::ImplementsContract => "implements_contract",
+            Self::CoChanges => "co_changes",
         }
     }
 }
@@ -117,6 +130,19 @@ pub struct FileRelationEvidenceDto {
     pub target_label: Option<String>,
     pub access: Option<FileRelationAccessDto>,
     pub analyzer: String,
+    /// Re-exports an import passed through before reaching the target file.
+    pub reexport_hops: Vec<FileRelationReexportHopDto>,
+    /// Commit hash for evidence read from git history.
+    pub commit: Option<String>,
+}
+
+/// One `pub use` on an import's way to the defining file: the re-exported
+/// path as written and where the re-export sits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRelationReexportHopDto {
+    pub export_path: String,
+    pub source_range: SourceRangeDto,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -134,6 +160,7 @@ pub enum FileRelationEvidenceKind {
     TestCoverage,
     Configuration,
     ContractImplementation,
+    CoChange,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -207,6 +234,8 @@ impl FileRelationGraphBuilder {
                     parent_id: node.parent_id,
                     child_ids: node.child_ids,
                     language,
+                    churn: node.churn,
+                    ownership: None,
                 }
             })
             .collect::<Vec<_>>();
@@ -278,6 +307,8 @@ impl FileRelationGraphBuilder {
                 target_label: Some(target_path),
                 access: None,
                 analyzer: import_snapshot.analyzer.clone(),
+                reexport_hops: fact.reexport_hops,
+                commit: None,
             };
 
             upsert_relation_edge(
@@ -396,11 +427,111 @@ impl FileRelationGraphBuilder {
                         target_label: Some(target_node.label.clone()),
                         access: None,
                         analyzer: "syn-rust-call-relations".to_string(),
+                        reexport_hops: Vec::new(),
+                        commit: None,
                     }],
                 },
             );
         }
     }
+
+    /// Adds an undirected co-change edge between every two file nodes that
+    /// changed together in at least `min_support` commits, when that is at
+    /// least `min_confidence` of the commits changing the less frequently
+    /// changed file. Commits are expected newest first, so the evidence
+    /// sample lists the most recent shared commits.
+    pub fn add_co_change_relations(
+        snapshot: &mut FileRelationGraphSnapshotDto,
+        commits: &[GitCommit],
+        options: &CoChangeOptionsDto,
+    ) {
+        snapshot.analyzers.push(AnalyzerRunDto {
+            analyzer: CO_CHANGE_ANALYZER.to_string(),
+            language: SourceLanguageDto::Unknown,
+            version: None,
+        });
+
+        let file_paths = snapshot
+            .nodes
+            .iter()
+            .filter(|node| node.kind == FileRelationNodeKind::File)
+            .map(|node| node.path.as_str())
+            .collect::<BTreeSet<_>>();
+        let mut commit_counts = BTreeMap::<&str, u32>::new();
+        let mut shared_commits = BTreeMap::<(&str, &str), Vec<&str>>::new();
+        for commit in commits {
+            let paths = commit
+                .files
+                .iter()
+                .filter_map(|change| file_paths.get(change.path.as_str()).copied())
+                .collect::<BTreeSet<_>>();
+            if paths.len() > options.max_files_per_commit {
+                continue;
+            }
+            for path in &paths {
+                *commit_counts.entry(path).or_default() += 1;
+            }
+            let paths = paths.into_iter().collect::<Vec<_>>();
+            for (index, from_path) in paths.iter().enumerate() {
+                for to_path in &paths[index + 1..] {
+                    shared_commits
+                        .entry((from_path, to_path))
+                        .or_default()
+                        .push(commit.hash.as_str());
+                }
+            }
+        }
+
+        for ((from_path, to_path), hashes) in shared_commits {
+            let support = hashes.len() as u32;
+            let changes = commit_counts[from_path].min(commit_counts[to_path]);
+            if support < options.min_support
+                || f64::from(support) < options.min_confidence * f64::from(changes)
+            {
+                continue;
+            }
+
+            let from_node_id = file_relation_file_id(from_path);
+            let to_node_id = file_relation_file_id(to_path);
+            let file_range = |path: &str| SourceRangeDto {
+                path: path.to_string(),
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 1,
+            };
+            snapshot.edges.push(FileRelationEdgeDto {
+                id: stable_file_relation_edge_id(
+                    FileRelationEdgeKind::CoChanges,
+                    &from_node_id,
+                    &to_node_id,
+                ),
+                kind: FileRelationEdgeKind::CoChanges,
+                from_node_id,
+                to_node_id,
+                weight: support,
+                direction: FileRelationDirectionDto::Undirected,
+                confidence: EdgeConfidenceDto::Inferred,
+                provenance: EdgeProvenanceDto::Normalized,
+                evidence_count: support,
+                evidence_sample: hashes
+                    .into_iter()
+                    .take(FILE_RELATION_EVIDENCE_SAMPLE_LIMIT)
+                    .map(|hash| FileRelationEvidenceDto {
+                        kind: FileRelationEvidenceKind::CoChange,
+                        source_range: file_range(from_path),
+                        target_range: Some(file_range(to_path)),
+                        source_label: None,
+                        target_label: None,
+                        access: None,
+                        analyzer: CO_CHANGE_ANALYZER.to_string(),
+                        reexport_hops: Vec::new(),
+                        commit: Some(hash.to_string()),
+                    })
+                    .collect(),
+            });
+        }
+    }
 }
 
 fn upsert_relation_edge(edges: &mut Vec<FileRelationEdgeDto>, new_edge: FileRelationEdgeDto) {
@@ -472,10 +603,12 @@ mod tests {
     use crate::analyzer::rust_relations::{
         RustImportRelationFactDto, RustImportRelationSnapshotDto, RustImportResolutionStatusDto,
     };
+    use crate::graph::churn::CoChangeOptionsDto;
     use crate::graph::{
         AnalyzerDiagnosticDto, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto, GraphEdgeKind,
         GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
     };
+    use crate::source::git::{GitCommit, GitFileChange};
     use crate::source::ValidatedRepoPath;
     use std::fs;
     use std::path::PathBuf;
@@ -524,6 +657,8 @@ mod tests {
                 parent_id: Some("dir:src".to_string()),
                 child_ids: Vec::new(),
                 language: Some(SourceLanguageDto::Rust),
+                churn: None,
+                ownership: None,
             }],
             edges: vec![FileRelationEdgeDto {
                 id: "calls:file:src/main.rs:file:src/lib.rs".to_string(),
@@ -543,6 +678,8 @@ mod tests {
                     target_label: Some("run".to_string()),
                     access: None,
                     analyzer: "syn".to_string(),
+                    reexport_hops: Vec::new(),
+                    commit: None,
                 }],
             }],
             analyzers: vec![AnalyzerRunDto {
@@ -554,6 +691,7 @@ mod tests {
                 code: "partial_call_resolution".to_string(),
                 message: "call target was inferred".to_string(),
                 source_path: Some("src/main.rs".to_string()),
+                evidence: None,
             }],
         };
 
@@ -563,7 +701,7 @@ mod tests {
             serde_json::from_str(&serialized).expect("deserialize relation graph snapshot");
 
         assert_eq!(decoded, snapshot);
-        assert!(serialized.contains(r#""schemaVersion":1"#));
+        assert!(serialized.contains(r#""schemaVersion":3"#));
         assert!(serialized.contains(r#""kind":"calls""#));
         assert!(serialized.contains(r#""direction":"directed""#));
         assert!(serialized.contains(r#""language":"rust""#));
@@ -648,6 +786,7 @@ mod tests {
                         target_path: Some("src/lib.rs".to_string()),
                         status: RustImportResolutionStatusDto::Resolved,
                         evidence: test_source_range("src/main.rs", 1),
+                        reexport_hops: Vec::new(),
                     },
                     RustImportRelationFactDto {
                         source_path: "src/main.rs".to_string(),
@@ -655,6 +794,7 @@ mod tests {
                         target_path: Some("src/lib.rs".to_string()),
                         status: RustImportResolutionStatusDto::Resolved,
                         evidence: test_source_range("src/main.rs", 2),
+                        reexport_hops: Vec::new(),
                     },
                     RustImportRelationFactDto {
                         source_path: "src/main.rs".to_string(),
@@ -662,12 +802,14 @@ mod tests {
                         target_path: None,
                         status: RustImportResolutionStatusDto::Unresolved,
                         evidence: test_source_range("src/main.rs", 3),
+                        reexport_hops: Vec::new(),
                     },
                 ],
                 diagnostics: vec![AnalyzerDiagnosticDto {
                     code: "rust_import_unresolved".to_string(),
                     message: "unresolved Rust import 'crate::missing::Thing'".to_string(),
                     source_path: Some("src/main.rs".to_string()),
+                    evidence: None,
                 }],
             },
         );
@@ -724,24 +866,68 @@ mod tests {
                         kind: GraphNodeKind::File,
                         label: "main.rs".to_string(),
                         source_range: Some(test_source_range("src/main.rs", 1)),
+                        metrics: None,
+                        visibility: None,
+                        attributes: Vec::new(),
+                        derives: Vec::new(),
+                        signature: None,
+                        public_paths: Vec::new(),
+                        unsafe_sites: Vec::new(),
+                        error_flow: None,
+                        wire_contract: None,
+                        state_assignments: Vec::new(),
+                        cfg: None,
                     },
                     GraphNodeDto {
                         id: "file:src/lib.rs".to_string(),
                         kind: GraphNodeKind::File,
                         label: "lib.rs".to_string(),
                         source_range: Some(test_source_range("src/lib.rs", 1)),
+                        metrics: None,
+                        visibility: None,
+                        attributes: Vec::new(),
+                        derives: Vec::new(),
+                        signature: None,
+                        public_paths: Vec::new(),
+                        unsafe_sites: Vec::new(),
+                        error_flow: None,
+                        wire_contract: None,
+                        state_assignments: Vec::new(),
+                        cfg: None,
                     },
                     GraphNodeDto {
                         id: "function:entry".to_string(),
                         kind: GraphNodeKind::Function,
                         label: "entry".to_string(),
                         source_range: Some(test_source_range("src/main.rs", 1)),
+                        metrics: None,
+                        visibility: None,
+                        attributes: Vec::new(),
+                        derives: Vec::new(),
+                        signature: None,
+                        public_paths: Vec::new(),
+                        unsafe_sites: Vec::new(),
+                        error_flow: None,
+                        wire_contract: None,
+                        state_assignments: Vec::new(),
+                        cfg: None,
                     },
                     GraphNodeDto {
                         id: "function:run".to_string(),
                         kind: GraphNodeKind::Function,
                         label: "run".to_string(),
                         source_range: Some(test_source_range("src/lib.rs", 1)),
+                        metrics: None,
+                        visibility: None,
+                        attributes: Vec::new(),
+                        derives: Vec::new(),
+                        signature: None,
+                        public_paths: Vec::new(),
+                        unsafe_sites: Vec::new(),
+                        error_flow: None,
+                        wire_contract: None,
+                        state_assignments: Vec::new(),
+                        cfg: None,
                     },
                 ],
                 edges: vec![
@@ -752,6 +938,9 @@ mod tests {
                         target_id: "function:entry".to_string(),
                         provenance: EdgeProvenanceDto::Syn,
                         confidence: EdgeConfidenceDto::Exact,
+                        evidence: None,
+                        dependency: None,
+                        cfg: None,
                     },
                     GraphEdgeDto {
                         id: "defines:file:src/lib.rs:function:run".to_string(),
@@ -760,6 +949,9 @@ mod tests {
                         target_id: "function:run".to_string(),
                         provenance: EdgeProvenanceDto::Syn,
                         confidence: EdgeConfidenceDto::Exact,
+                        evidence: None,
+                        dependency: None,
+                        cfg: None,
                     },
                     GraphEdgeDto {
                         id: "calls:function:entry:function:run".to_string(),
@@ -768,9 +960,13 @@ mod tests {
                         target_id: "function:run".to_string(),
                         provenance: EdgeProvenanceDto::Syn,
                         confidence: EdgeConfidenceDto::Partial,
+                        evidence: None,
+                        dependency: None,
+                        cfg: None,
                     },
                 ],
                 diagnostics: Vec::new(),
+                configuration: None,
             },
         );
 
@@ -799,4 +995,84 @@ mod tests {
 
         fs::remove_dir_all(repo).expect("cleanup temp repo");
     }
+
+    #[test]
+    fn co_change_edges_require_support_and_confidence_and_skip_bulk_commits() {
+        let repo = unique_temp_dir("co-change");
+        fs::create_dir_all(repo.join("src")).expect("create src");
+        for file in ["a.rs", "b.rs", "c.rs"] {
+            fs::write(repo.join("src").join(file), "").expect("write source");
+        }
+        fs::write(repo.join("README.md"), "").expect("write readme");
+        let source_root =
+            ValidatedRepoPath::parse_existing_source_root(&repo).expect("valid source root");
+        let structure =
+            FileRelationGraphBuilder::build_structure(&source_root).expect("relation graph");
+        let commit = |hash: &str, paths: &[&str]| GitCommit {
+            hash: hash.to_string(),
+            timestamp: 0,
+            author_name: "Fixture".to_string(),
+            author_email: "fixture@example.com".to_string(),
+            files: paths
+                .iter()
+                .map(|path| GitFileChange {
+                    path: path.to_string(),
+                    added: 1,
+                    removed: 0,
+                })
+                .collect(),
+        };
+        let commits = vec![
+            commit("c5", &["src/a.rs", "src/b.rs"]),
+            commit("c4", &["src/a.rs", "src/b.rs"]),
+            commit("c3", &["src/a.rs", "src/b.rs", "src/c.rs"]),
+            commit("c2", &["src/c.rs"]),
+            commit("c1", &["src/a.rs", "src/removed.rs"]),
+            commit("bulk", &["src/a.rs", "src/b.rs", "src/c.rs", "README.md"]),
+        ];
+        let options = CoChangeOptionsDto {
+            max_files_per_commit: 3,
+            ..CoChangeOptionsDto::default()
+        };
+
+        let mut snapshot = structure.clone();
+        FileRelationGraphBuilder::add_co_change_relations(&mut snapshot, &commits, &options);
+
+        let co_changes = snapshot
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == FileRelationEdgeKind::CoChanges)
+            .collect::<Vec<_>>();
+        assert_eq!(co_changes.len(), 1);
+        assert_eq!(co_changes[0].id, "co_changes:file:src/a.rs:file:src/b.rs");
+        assert_eq!(co_changes[0].weight, 3);
+        assert_eq!(
+            co_changes[0].direction,
+            FileRelationDirectionDto::Undirected
+        );
+        assert_eq!(
+            co_changes[0]
+                .evidence_sample
+                .iter()
+                .map(|evidence| evidence.commit.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("c5"), Some("c4"), Some("c3")]
+        );
+
+        let mut strict = structure;
+        FileRelationGraphBuilder::add_co_change_relations(
+            &mut strict,
+            &commits,
+            &CoChangeOptionsDto {
+                min_support: 4,
+                ..options
+            },
+        );
+        assert!(strict
+            .edges
+            .iter()
+            .all(|edge| edge.kind != FileRelationEdgeKind::CoChanges));
+
+        fs::remove_dir_all(repo).expect("cleanup temp repo");
+    }
 }
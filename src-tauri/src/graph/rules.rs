@@ -0,0 +1,547 @@
+//! Declarative architecture layering rules evaluated against graph snapshots.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::ownership::{normalized_module_path, NodeOwnership};
+use super::{AnalyzerDiagnosticDto, GraphEdgeKind, GraphSnapshotDto};
+use crate::source::ValidatedRepoPath;
+
+pub const ARCHITECTURE_RULES_SCHEMA_VERSION: u32 = 1;
+pub const ARCHITECTURE_RULES_PATH: &str = ".whip-docs/architecture-rules.json";
+pub const ARCHITECTURE_RULE_VIOLATION_CODE: &str = "architecture_rule_violation";
+const ARCHITECTURE_RULES_INVALID_CODE: &str = "architecture_rules_invalid";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchitectureRuleSetDto {
+    pub schema_version: u32,
+    pub rules: Vec<ArchitectureRuleDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchitectureRuleDto {
+    pub id: String,
+    pub kind: ArchitectureRuleKind,
+    pub from: Vec<String>,
+    pub to: Vec<String>,
+    #[serde(default = "default_rule_edge_kinds")]
+    pub edge_kinds: Vec<GraphEdgeKind>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// `forbid` rejects edges from `from` modules into `to` modules.
+/// `allow_only` rejects edges into `to` modules unless they start in `from`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchitectureRuleKind {
+    Forbid,
+    AllowOnly,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ArchitectureRuleError {
+    #[error("failed to read architecture rules '{path}': {message}")]
+    Read { path: String, message: String },
+    #[error("failed to parse architecture rules: {0}")]
+    Parse(String),
+    #[error(
+        "unsupported architecture rules schema version {found}; supported version is {supported}"
+    )]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+    #[error("architecture rule '{rule_id}' has an empty module pattern")]
+    EmptyPattern { rule_id: String },
+    #[error("architecture rule '{rule_id}' cannot be evaluated against {edge_kind:?} edges")]
+    UnsupportedEdgeKind {
+        rule_id: String,
+        edge_kind: GraphEdgeKind,
+    },
+}
+
+impl ArchitectureRuleSetDto {
+    pub fn load_from_repo(
+        source_root: &ValidatedRepoPath,
+    ) -> Result<Option<Self>, ArchitectureRuleError> {
+        let rules_path = source_root.as_path().join(ARCHITECTURE_RULES_PATH);
+        if !rules_path.is_file() {
+            return Ok(None);
+        }
+
+        let contents =
+            fs::read_to_string(&rules_path).map_err(|error| ArchitectureRuleError::Read {
+                path: ARCHITECTURE_RULES_PATH.to_string(),
+                message: error.to_string(),
+            })?;
+        Self::parse(&contents).map(Some)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, ArchitectureRuleError> {
+        let rule_set: Self = serde_json::from_str(contents)
+            .map_err(|error| ArchitectureRuleError::Parse(error.to_string()))?;
+        rule_set.validate()?;
+        Ok(rule_set)
+    }
+
+    fn validate(&self) -> Result<(), ArchitectureRuleError> {
+        if self.schema_version != ARCHITECTURE_RULES_SCHEMA_VERSION {
+            return Err(ArchitectureRuleError::UnsupportedSchemaVersion {
+                found: self.schema_version,
+                supported: ARCHITECTURE_RULES_SCHEMA_VERSION,
+            });
+        }
+
+        for rule in &self.rules {
+            if rule
+                .from
+                .iter()
+                .chain(&rule.to)
+                .any(|pattern| normalized_module_path(pattern).is_empty())
+            {
+                return Err(ArchitectureRuleError::EmptyPattern {
+                    rule_id: rule.id.clone(),
+                });
+            }
+
+            if let Some(edge_kind) = rule
+                .edge_kinds
+                .iter()
+                .find(|kind| !matches!(kind, GraphEdgeKind::Imports | GraphEdgeKind::Calls))
+            {
+                return Err(ArchitectureRuleError::UnsupportedEdgeKind {
+                    rule_id: rule.id.clone(),
+                    edge_kind: edge_kind.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn evaluate(&self, snapshot: &GraphSnapshotDto) -> Vec<AnalyzerDiagnosticDto> {
+        let ownership = NodeOwnership::from_snapshot(snapshot);
+        let node_by_id = snapshot
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect::<BTreeMap<_, _>>();
+        let mut reported = BTreeSet::new();
+        let mut diagnostics = Vec::new();
+
+        for rule in &self.rules {
+            for edge in &snapshot.edges {
+                if !rule.edge_kinds.contains(&edge.kind) {
+                    continue;
+                }
+
+                let (Some(source_module), Some(target_module)) = (
+                    ownership.module_path(&edge.source_id),
+                    ownership.module_path(&edge.target_id),
+                ) else {
+                    continue;
+                };
+
+                if source_module == target_module
+                    || !rule.is_violated_by(source_module, target_module)
+                {
+                    continue;
+                }
+
+                if !reported.insert((rule.id.as_str(), edge.id.as_str())) {
+                    continue;
+                }
+
+                let evidence = edge.evidence.clone().or_else(|| {
+                    node_by_id
+                        .get(edge.source_id.as_str())
+                        .and_then(|node| node.source_range.clone())
+                });
+                let relation = match edge.kind {
+                    GraphEdgeKind::Calls => "calls into",
+                    _ => "imports",
+                };
+                let mut message = format!(
+                    "architecture rule '{}' violated: '{source_module}' {relation} '{target_module}'",
+                    rule.id
+                );
+                if let Some(rule_message) = &rule.message {
+                    message.push_str(": ");
+                    message.push_str(rule_message);
+                }
+
+                diagnostics.push(AnalyzerDiagnosticDto {
+                    code: ARCHITECTURE_RULE_VIOLATION_CODE.to_string(),
+                    message,
+                    source_path: evidence.as_ref().map(|range| range.path.clone()),
+                    evidence,
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+impl ArchitectureRuleDto {
+    fn is_violated_by(&self, source_module: &str, target_module: &str) -> bool {
+        let source_matches = matches_any_module_pattern(&self.from, source_module);
+        let target_matches = matches_any_module_pattern(&self.to, target_module);
+
+        match self.kind {
+            ArchitectureRuleKind::Forbid => source_matches && target_matches,
+            ArchitectureRuleKind::AllowOnly => target_matches && !source_matches,
+        }
+    }
+}
+
+/// Loads the repo-local rule file and evaluates it, reporting load failures as
+/// a diagnostic so analysis still returns a snapshot.
+pub fn architecture_rule_diagnostics(
+    source_root: &ValidatedRepoPath,
+    snapshot: &GraphSnapshotDto,
+) -> Vec<AnalyzerDiagnosticDto> {
+    match ArchitectureRuleSetDto::load_from_repo(source_root) {
+        Ok(Some(rule_set)) => rule_set.evaluate(snapshot),
+        Ok(None) => Vec::new(),
+        Err(error) => vec![AnalyzerDiagnosticDto {
+            code: ARCHITECTURE_RULES_INVALID_CODE.to_string(),
+            message: error.to_string(),
+            source_path: Some(ARCHITECTURE_RULES_PATH.to_string()),
+            evidence: None,
+        }],
+    }
+}
+
+fn default_rule_edge_kinds() -> Vec<GraphEdgeKind> {
+    vec![GraphEdgeKind::Imports, GraphEdgeKind::Calls]
+}
+
+fn matches_any_module_pattern(patterns: &[String], module_path: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| module_pattern_matches(pattern, module_path))
+}
+
+/// Matches `::`-separated module globs. `*` matches within one segment, `**`
+/// matches any number of segments, and a pattern that matches an ancestor
+/// module also matches everything nested under it.
+fn module_pattern_matches(pattern: &str, module_path: &str) -> bool {
+    let pattern = normalized_module_path(pattern);
+    let pattern_segments = pattern.split("::").collect::<Vec<_>>();
+    let path_segments = module_path.split("::").collect::<Vec<_>>();
+
+    (1..=path_segments.len())
+        .any(|length| segments_match(&pattern_segments, &path_segments[..length]))
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(path_segment, path_rest)| {
+            segment_matches(segment, path_segment) && segments_match(rest, path_rest)
+        }),
+    }
+}
+
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let Some((prefix, suffix_pattern)) = pattern.split_once('*') else {
+        return pattern == segment;
+    };
+
+    let Some(rest) = segment.strip_prefix(prefix) else {
+        return false;
+    };
+
+    if suffix_pattern.is_empty() {
+        return true;
+    }
+
+    (0..=rest.len())
+        .filter(|index| rest.is_char_boundary(*index))
+        .any(|index| segment_matches(suffix_pattern, &rest[index..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        architecture_rule_diagnostics, module_pattern_matches, ArchitectureRuleError,
+        ArchitectureRuleSetDto, ARCHITECTURE_RULES_PATH, ARCHITECTURE_RULE_VIOLATION_CODE,
+    };
+    use crate::graph::{
+        EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto, GraphEdgeKind, GraphNodeDto,
+        GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+    };
+    use crate::source::ValidatedRepoPath;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "whip-docs-rules-{name}-{}-{timestamp}",
+            std::process::id()
+        ))
+    }
+
+    fn test_source_range(path: &str, line: u32) -> SourceRangeDto {
+        SourceRangeDto {
+            path: path.to_string(),
+            start_line: line,
+            start_column: 1,
+            end_line: line,
+            end_column: 1,
+        }
+    }
+
+    fn node(id: &str, kind: GraphNodeKind, label: &str, path: &str) -> GraphNodeDto {
+        GraphNodeDto {
+            id: id.to_string(),
+            kind,
+            label: label.to_string(),
+            source_range: Some(test_source_range(path, 1)),
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn edge(
+        kind: GraphEdgeKind,
+        source_id: &str,
+        target_id: &str,
+        provenance: EdgeProvenanceDto,
+        evidence: Option<SourceRangeDto>,
+    ) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: format!("{kind:?}:{source_id}:{target_id}"),
+            kind,
+            source_id: source_id.to_string(),
+            target_id: target_id.to_string(),
+            provenance,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    fn layered_snapshot() -> GraphSnapshotDto {
+        GraphSnapshotDto {
+            schema_version: 1,
+            source_root: "/repo".to_string(),
+            generated_at: "unix:1".to_string(),
+            nodes: vec![
+                node(
+                    "file:src/graph/mod.rs",
+                    GraphNodeKind::File,
+                    "src/graph/mod.rs",
+                    "src/graph/mod.rs",
+                ),
+                node(
+                    "module:fixture:graph",
+                    GraphNodeKind::Module,
+                    "graph",
+                    "src/graph/mod.rs",
+                ),
+                node(
+                    "file:src/commands/mod.rs",
+                    GraphNodeKind::File,
+                    "src/commands/mod.rs",
+                    "src/commands/mod.rs",
+                ),
+                node(
+                    "module:fixture:commands",
+                    GraphNodeKind::Module,
+                    "commands",
+                    "src/commands/mod.rs",
+                ),
+                node(
+                    "function:build",
+                    GraphNodeKind::Function,
+                    "build",
+                    "src/graph/mod.rs",
+                ),
+                node(
+                    "function:handle",
+                    GraphNodeKind::Function,
+                    "handle",
+                    "src/commands/mod.rs",
+                ),
+                node(
+                    "module:import:tokio::process::Command",
+                    GraphNodeKind::Module,
+                    "tokio::process::Command",
+                    "src/graph/mod.rs",
+                ),
+            ],
+            edges: vec![
+                edge(
+                    GraphEdgeKind::Defines,
+                    "file:src/graph/mod.rs",
+                    "module:fixture:graph",
+                    EdgeProvenanceDto::Normalized,
+                    None,
+                ),
+                edge(
+                    GraphEdgeKind::Defines,
+                    "file:src/commands/mod.rs",
+                    "module:fixture:commands",
+                    EdgeProvenanceDto::Normalized,
+                    None,
+                ),
+                edge(
+                    GraphEdgeKind::Defines,
+                    "module:fixture:graph",
+                    "function:build",
+                    EdgeProvenanceDto::Syn,
+                    None,
+                ),
+                edge(
+                    GraphEdgeKind::Defines,
+                    "module:fixture:commands",
+                    "function:handle",
+                    EdgeProvenanceDto::Syn,
+                    None,
+                ),
+                edge(
+                    GraphEdgeKind::Calls,
+                    "function:build",
+                    "function:handle",
+                    EdgeProvenanceDto::Syn,
+                    Some(test_source_range("src/graph/mod.rs", 12)),
+                ),
+                edge(
+                    GraphEdgeKind::Calls,
+                    "function:handle",
+                    "function:build",
+                    EdgeProvenanceDto::Syn,
+                    Some(test_source_range("src/commands/mod.rs", 4)),
+                ),
+                edge(
+                    GraphEdgeKind::Imports,
+                    "file:src/graph/mod.rs",
+                    "module:import:tokio::process::Command",
+                    EdgeProvenanceDto::Syn,
+                    Some(test_source_range("src/graph/mod.rs", 2)),
+                ),
+            ],
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    #[test]
+    fn module_patterns_match_subtrees_and_globs() {
+        assert!(module_pattern_matches("graph", "graph"));
+        assert!(module_pattern_matches("graph", "graph::relations"));
+        assert!(module_pattern_matches("crate::graph", "graph::relations"));
+        assert!(module_pattern_matches("*::relations", "graph::relations"));
+        assert!(module_pattern_matches(
+            "**::tests",
+            "graph::relations::tests"
+        ));
+        assert!(module_pattern_matches("analy*", "analyzer::extraction"));
+        assert!(!module_pattern_matches("graph", "commands"));
+        assert!(!module_pattern_matches("graph::rules", "graph::relations"));
+    }
+
+    #[test]
+    fn forbid_and_allow_only_rules_report_violations_with_evidence() {
+        let rule_set = ArchitectureRuleSetDto::parse(
+            r#"{
+                "schemaVersion": 1,
+                "rules": [
+                    { "id": "graph-independent", "kind": "forbid", "from": ["graph"], "to": ["commands"] },
+                    {
+                        "id": "only-analyzer-spawns",
+                        "kind": "allow_only",
+                        "from": ["analyzer"],
+                        "to": ["std::process", "tokio::process"],
+                        "edgeKinds": ["imports"],
+                        "message": "process ownership belongs to the analyzer"
+                    }
+                ]
+            }"#,
+        )
+        .expect("parse rules");
+
+        let diagnostics = rule_set.evaluate(&layered_snapshot());
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.code == ARCHITECTURE_RULE_VIOLATION_CODE));
+        assert!(diagnostics.iter().any(|diagnostic| {
+            diagnostic.message.contains("'graph-independent'")
+                && diagnostic.evidence == Some(test_source_range("src/graph/mod.rs", 12))
+        }));
+        assert!(diagnostics.iter().any(|diagnostic| {
+            diagnostic.message.contains("'only-analyzer-spawns'")
+                && diagnostic
+                    .message
+                    .contains("process ownership belongs to the analyzer")
+                && diagnostic.source_path.as_deref() == Some("src/graph/mod.rs")
+        }));
+    }
+
+    #[test]
+    fn rule_set_rejects_unsupported_schema_and_edge_kinds() {
+        assert_eq!(
+            ArchitectureRuleSetDto::parse(r#"{ "schemaVersion": 2, "rules": [] }"#),
+            Err(ArchitectureRuleError::UnsupportedSchemaVersion {
+                found: 2,
+                supported: 1
+            })
+        );
+        assert!(matches!(
+            ArchitectureRuleSetDto::parse(
+                r#"{ "schemaVersion": 1, "rules": [
+                    { "id": "r", "kind": "forbid", "from": ["a"], "to": ["b"], "edgeKinds": ["contains"] }
+                ] }"#
+            ),
+            Err(ArchitectureRuleError::UnsupportedEdgeKind { .. })
+        ));
+    }
+
+    #[test]
+    fn repo_rule_file_is_optional_and_invalid_files_become_diagnostics() {
+        let repo = unique_temp_dir("repo-rules");
+        fs::create_dir_all(repo.join(".whip-docs")).expect("create rules dir");
+        let source_root =
+            ValidatedRepoPath::parse_existing_source_root(&repo).expect("valid source root");
+
+        assert_eq!(
+            ArchitectureRuleSetDto::load_from_repo(&source_root),
+            Ok(None)
+        );
+        assert!(architecture_rule_diagnostics(&source_root, &layered_snapshot()).is_empty());
+
+        fs::write(repo.join(ARCHITECTURE_RULES_PATH), "{ not json").expect("write rules");
+        let diagnostics = architecture_rule_diagnostics(&source_root, &layered_snapshot());
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "architecture_rules_invalid");
+        assert_eq!(
+            diagnostics[0].source_path.as_deref(),
+            Some(ARCHITECTURE_RULES_PATH)
+        );
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+}
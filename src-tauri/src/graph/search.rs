@@ -0,0 +1,432 @@
+//! In-memory fuzzy symbol search over analyzer graph nodes.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::ownership::NodeOwnership;
+use super::{EdgeProvenanceDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto};
+
+pub const DEFAULT_SYMBOL_SEARCH_LIMIT: usize = 50;
+pub const MAX_SYMBOL_SEARCH_LIMIT: usize = 500;
+
+const IMPORT_NODE_ID_PREFIX: &str = "module:import:";
+const EXACT_SCORE: u32 = 1_000;
+const PREFIX_SCORE: u32 = 850;
+const SUBSTRING_SCORE: u32 = 650;
+const SUBSEQUENCE_SCORE: u32 = 300;
+const QUALIFIED_NAME_PENALTY: u32 = 100;
+const PATH_PENALTY: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolSearchFieldDto {
+    Label,
+    QualifiedName,
+    Path,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolSearchMatchDto {
+    pub node_id: String,
+    pub kind: GraphNodeKind,
+    pub label: String,
+    /// Crate-relative module path joined with the label, such as
+    /// `graph::rules::RuleSet`.
+    pub qualified_name: String,
+    pub source_range: Option<SourceRangeDto>,
+    pub matched_field: SymbolSearchFieldDto,
+    /// Higher is better. Only meaningful relative to other matches of the
+    /// same query.
+    pub score: u32,
+}
+
+/// Searchable projection of one snapshot. Build it once per analysis and
+/// reuse it for every keystroke.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolSearchIndex {
+    entries: Vec<SymbolSearchEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SymbolSearchEntry {
+    node_id: String,
+    kind: GraphNodeKind,
+    label: String,
+    qualified_name: String,
+    path: Option<String>,
+    source_range: Option<SourceRangeDto>,
+}
+
+impl SymbolSearchIndex {
+    /// Indexes every named node except synthetic `use` targets and
+    /// rust-analyzer symbols that duplicate a syn node with the same kind,
+    /// label, and file.
+    pub fn from_snapshot(snapshot: &GraphSnapshotDto) -> Self {
+        let ownership = NodeOwnership::from_snapshot(snapshot);
+        let rust_analyzer_node_ids = snapshot
+            .edges
+            .iter()
+            .filter(|edge| edge.provenance == EdgeProvenanceDto::RustAnalyzer)
+            .map(|edge| edge.target_id.as_str())
+            .collect::<BTreeSet<_>>();
+        let syn_symbols = snapshot
+            .nodes
+            .iter()
+            .filter(|node| !rust_analyzer_node_ids.contains(node.id.as_str()))
+            .map(|node| {
+                (
+                    node.kind.id_prefix(),
+                    node.label.as_str(),
+                    source_path(node),
+                )
+            })
+            .collect::<BTreeSet<_>>();
+
+        let entries = snapshot
+            .nodes
+            .iter()
+            .filter(|node| !node.id.starts_with(IMPORT_NODE_ID_PREFIX))
+            .filter(|node| {
+                !rust_analyzer_node_ids.contains(node.id.as_str())
+                    || !syn_symbols.contains(&(
+                        node.kind.id_prefix(),
+                        node.label.as_str(),
+                        source_path(node),
+                    ))
+            })
+            .map(|node| SymbolSearchEntry {
+                node_id: node.id.clone(),
+                kind: node.kind.clone(),
+                label: node.label.clone(),
+                qualified_name: qualified_name(&ownership, &node.id, &node.kind, &node.label),
+                path: source_path(node).map(str::to_string),
+                source_range: node.source_range.clone(),
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Case-insensitive fuzzy search. Labels rank above qualified names and
+    /// qualified names above file paths; within a field, exact matches beat
+    /// prefixes, substrings, then subsequences. Ties prefer types and
+    /// functions over containers, then shorter labels.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SymbolSearchMatchDto> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut matches = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let (matched_field, score) = best_field_score(&query, entry)?;
+                Some(SymbolSearchMatchDto {
+                    node_id: entry.node_id.clone(),
+                    kind: entry.kind.clone(),
+                    label: entry.label.clone(),
+                    qualified_name: entry.qualified_name.clone(),
+                    source_range: entry.source_range.clone(),
+                    matched_field,
+                    score: score + kind_bonus(&entry.kind),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|left, right| {
+            Reverse(left.score)
+                .cmp(&Reverse(right.score))
+                .then_with(|| left.label.len().cmp(&right.label.len()))
+                .then_with(|| left.qualified_name.cmp(&right.qualified_name))
+                .then_with(|| left.node_id.cmp(&right.node_id))
+        });
+        matches.truncate(limit.min(MAX_SYMBOL_SEARCH_LIMIT));
+        matches
+    }
+}
+
+fn source_path(node: &super::GraphNodeDto) -> Option<&str> {
+    node.source_range.as_ref().map(|range| range.path.as_str())
+}
+
+fn qualified_name(
+    ownership: &NodeOwnership,
+    node_id: &str,
+    kind: &GraphNodeKind,
+    label: &str,
+) -> String {
+    match ownership.module_path(node_id) {
+        Some(module_path)
+            if !module_path.is_empty()
+                && !matches!(kind, GraphNodeKind::Module | GraphNodeKind::File) =>
+        {
+            format!("{module_path}::{label}")
+        }
+        Some(module_path) if !module_path.is_empty() => module_path.to_string(),
+        _ => label.to_string(),
+    }
+}
+
+fn best_field_score(query: &str, entry: &SymbolSearchEntry) -> Option<(SymbolSearchFieldDto, u32)> {
+    let label = fuzzy_score(query, &entry.label).map(|score| (SymbolSearchFieldDto::Label, score));
+    let qualified_name = fuzzy_score(query, &entry.qualified_name).map(|score| {
+        (
+            SymbolSearchFieldDto::QualifiedName,
+            score.saturating_sub(QUALIFIED_NAME_PENALTY).max(1),
+        )
+    });
+    let path = entry
+        .path
+        .as_deref()
+        .and_then(|path| fuzzy_score(query, path))
+        .map(|score| {
+            (
+                SymbolSearchFieldDto::Path,
+                score.saturating_sub(PATH_PENALTY).max(1),
+            )
+        });
+
+    // `max_by_key` keeps the last maximum, so ties resolve toward the label.
+    [path, qualified_name, label]
+        .into_iter()
+        .flatten()
+        .max_by_key(|(_, score)| *score)
+}
+
+/// Scores `candidate` against an already lowercased query, or `None` when
+/// the query is not a subsequence of the candidate.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let lowered = candidate.to_lowercase();
+    if lowered == query {
+        return Some(EXACT_SCORE);
+    }
+    let length_penalty = (lowered.len().saturating_sub(query.len())).min(100) as u32;
+    if lowered.starts_with(query) {
+        return Some(PREFIX_SCORE - length_penalty);
+    }
+    if let Some(position) = lowered.find(query) {
+        let boundary_bonus = if is_word_boundary(candidate, position) {
+            50
+        } else {
+            0
+        };
+        return Some(SUBSTRING_SCORE + boundary_bonus - length_penalty);
+    }
+
+    subsequence_score(query, candidate)
+}
+
+/// Greedy left-to-right subsequence match rewarding consecutive characters
+/// and word starts and penalizing skipped characters.
+fn subsequence_score(query: &str, candidate: &str) -> Option<u32> {
+    let mut query_chars = query.chars().peekable();
+    let mut score = SUBSEQUENCE_SCORE as i64;
+    let mut previous_match: Option<usize> = None;
+
+    for (index, (byte_index, character)) in candidate.char_indices().enumerate() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        if !character.to_lowercase().eq(wanted.to_lowercase()) {
+            continue;
+        }
+
+        query_chars.next();
+        match previous_match {
+            Some(previous) if previous + 1 == index => score += 15,
+            Some(previous) => score -= (index - previous - 1).min(10) as i64,
+            None => score -= index.min(20) as i64,
+        }
+        if is_word_boundary(candidate, byte_index) {
+            score += 10;
+        }
+        previous_match = Some(index);
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    Some(score.clamp(1, (SUBSTRING_SCORE - 1) as i64) as u32)
+}
+
+/// Start of the string, after a separator, or a lower-to-upper case change.
+fn is_word_boundary(candidate: &str, byte_index: usize) -> bool {
+    if !candidate.is_char_boundary(byte_index) {
+        return false;
+    }
+    let Some(previous) = candidate[..byte_index].chars().next_back() else {
+        return true;
+    };
+    let current = candidate[byte_index..].chars().next().unwrap_or_default();
+    matches!(previous, '_' | ':' | '/' | '.' | '-' | ' ')
+        || (previous.is_lowercase() && current.is_uppercase())
+}
+
+fn kind_bonus(kind: &GraphNodeKind) -> u32 {
+    match kind {
+        GraphNodeKind::Struct
+        | GraphNodeKind::Enum
+        | GraphNodeKind::Union
+        | GraphNodeKind::Trait
+        | GraphNodeKind::TypeAlias
+        | GraphNodeKind::TauriCommand => 40,
+        GraphNodeKind::Function
+        | GraphNodeKind::Method
+        | GraphNodeKind::Macro
+        | GraphNodeKind::Const
+        | GraphNodeKind::Static => 30,
+        GraphNodeKind::Module
+        | GraphNodeKind::Crate
+        | GraphNodeKind::ExternalCrate
+        | GraphNodeKind::Task
+        | GraphNodeKind::SyncPrimitive => 20,
+        GraphNodeKind::File => 10,
+        GraphNodeKind::Workspace | GraphNodeKind::Impl => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_score, SymbolSearchFieldDto, SymbolSearchIndex};
+    use crate::graph::{
+        EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto, GraphEdgeKind, GraphNodeDto,
+        GraphNodeKind, GraphSnapshotDto, SourceRangeDto, GRAPH_SCHEMA_VERSION,
+    };
+
+    fn node(id: &str, kind: GraphNodeKind, label: &str, path: &str) -> GraphNodeDto {
+        GraphNodeDto {
+            id: id.to_string(),
+            kind,
+            label: label.to_string(),
+            source_range: Some(SourceRangeDto {
+                path: path.to_string(),
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 1,
+            }),
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn edge(source_id: &str, target_id: &str, provenance: EdgeProvenanceDto) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: format!("{source_id}->{target_id}"),
+            kind: GraphEdgeKind::Defines,
+            source_id: source_id.to_string(),
+            target_id: target_id.to_string(),
+            provenance,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    fn snapshot() -> GraphSnapshotDto {
+        GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            generated_at: "0".to_string(),
+            source_root: "/repo".to_string(),
+            nodes: vec![
+                node(
+                    "file:rules",
+                    GraphNodeKind::File,
+                    "src/graph/rules.rs",
+                    "src/graph/rules.rs",
+                ),
+                node(
+                    "module:rules",
+                    GraphNodeKind::Module,
+                    "graph::rules",
+                    "src/graph/rules.rs",
+                ),
+                node(
+                    "struct:rule_set",
+                    GraphNodeKind::Struct,
+                    "RuleSet",
+                    "src/graph/rules.rs",
+                ),
+                node(
+                    "function:rule_diagnostics",
+                    GraphNodeKind::Function,
+                    "rule_diagnostics",
+                    "src/graph/rules.rs",
+                ),
+                node(
+                    "struct:ra_rule_set",
+                    GraphNodeKind::Struct,
+                    "RuleSet",
+                    "src/graph/rules.rs",
+                ),
+                node(
+                    "module:import:crate::graph::rules::RuleSet",
+                    GraphNodeKind::Module,
+                    "crate::graph::rules::RuleSet",
+                    "src/main.rs",
+                ),
+            ],
+            edges: vec![
+                edge("file:rules", "module:rules", EdgeProvenanceDto::Normalized),
+                edge("module:rules", "struct:rule_set", EdgeProvenanceDto::Syn),
+                edge(
+                    "module:rules",
+                    "function:rule_diagnostics",
+                    EdgeProvenanceDto::Syn,
+                ),
+                edge(
+                    "file:rules",
+                    "struct:ra_rule_set",
+                    EdgeProvenanceDto::RustAnalyzer,
+                ),
+            ],
+            diagnostics: Vec::new(),
+            configuration: None,
+        }
+    }
+
+    #[test]
+    fn ranks_exact_then_prefix_then_subsequence_matches() {
+        let index = SymbolSearchIndex::from_snapshot(&snapshot());
+
+        let exact = index.search("ruleset", 10);
+        assert_eq!(exact[0].node_id, "struct:rule_set");
+        assert_eq!(exact[0].qualified_name, "graph::rules::RuleSet");
+        assert_eq!(exact[0].matched_field, SymbolSearchFieldDto::Label);
+
+        let fuzzy = index.search("rdiag", 10);
+        assert_eq!(fuzzy[0].node_id, "function:rule_diagnostics");
+
+        let by_module = index.search("graph::rules", 10);
+        assert_eq!(by_module[0].node_id, "module:rules");
+
+        assert!(index.search("zzz", 10).is_empty());
+        assert!(fuzzy_score("rsd", "RuleSet") < fuzzy_score("rs", "RuleSet"));
+    }
+
+    #[test]
+    fn skips_import_targets_and_duplicate_rust_analyzer_symbols() {
+        let index = SymbolSearchIndex::from_snapshot(&snapshot());
+
+        assert_eq!(index.entries.len(), 4);
+        assert!(index
+            .search("RuleSet", 10)
+            .iter()
+            .all(|symbol| symbol.node_id != "struct:ra_rule_set"
+                && !symbol.node_id.starts_with("module:import:")));
+    }
+}
@@ -0,0 +1,348 @@
+//! Enum state machines: state assignments recorded on functions and
+//! methods, the transitions they imply, and Mermaid/DOT state diagrams.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GraphSnapshotDto, SourceRangeDto};
+
+/// One assignment or struct-literal construction of a state enum variant
+/// into a field, recorded on the function or method that performs it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateAssignmentDto {
+    pub enum_id: String,
+    pub enum_name: String,
+    pub field: String,
+    pub variant: String,
+    /// Variants an enclosing `match` arm or `if` condition required the
+    /// field to hold; empty when the prior state is not narrowed.
+    pub guard: Vec<String>,
+    pub construction: bool,
+    pub range: SourceRangeDto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateMachineReportDto {
+    /// Sorted by enum name.
+    pub machines: Vec<StateMachineDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateMachineDto {
+    pub enum_id: String,
+    pub enum_name: String,
+    /// Field names the enum is stored in.
+    pub fields: Vec<String>,
+    /// Variants in order of first appearance.
+    pub states: Vec<String>,
+    /// In source order.
+    pub transitions: Vec<StateTransitionDto>,
+    pub mermaid: String,
+    pub dot: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTransitionDto {
+    pub kind: StateTransitionKindDto,
+    /// Prior state of a guarded transition; absent for initial and
+    /// unguarded ones.
+    pub from: Option<String>,
+    pub to: String,
+    pub via_node_id: String,
+    pub via_label: String,
+    pub range: SourceRangeDto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateTransitionKindDto {
+    /// A struct literal starts the field in this state.
+    Initial,
+    /// An enclosing `match` or `if` shows the prior state.
+    Guarded,
+    /// An assignment from any prior state.
+    Unguarded,
+}
+
+/// One state machine per enum with recorded assignments.
+pub fn state_machines(snapshot: &GraphSnapshotDto) -> StateMachineReportDto {
+    let mut assignments = BTreeMap::<&str, Vec<(&str, &str, &StateAssignmentDto)>>::new();
+    for node in &snapshot.nodes {
+        for assignment in &node.state_assignments {
+            assignments
+                .entry(assignment.enum_id.as_str())
+                .or_default()
+                .push((node.id.as_str(), node.label.as_str(), assignment));
+        }
+    }
+
+    let mut machines = assignments
+        .into_iter()
+        .map(|(enum_id, mut assignments)| {
+            assignments.sort_by(|left, right| {
+                (
+                    &left.2.range.path,
+                    left.2.range.start_line,
+                    left.2.range.start_column,
+                )
+                    .cmp(&(
+                        &right.2.range.path,
+                        right.2.range.start_line,
+                        right.2.range.start_column,
+                    ))
+            });
+            let transitions = assignments
+                .iter()
+                .flat_map(|(node_id, node_label, assignment)| {
+                    let transition = |kind, from: Option<&String>| StateTransitionDto {
+                        kind,
+                        from: from.cloned(),
+                        to: assignment.variant.clone(),
+                        via_node_id: node_id.to_string(),
+                        via_label: node_label.to_string(),
+                        range: assignment.range.clone(),
+                    };
+                    if assignment.construction {
+                        vec![transition(StateTransitionKindDto::Initial, None)]
+                    } else if assignment.guard.is_empty() {
+                        vec![transition(StateTransitionKindDto::Unguarded, None)]
+                    } else {
+                        assignment
+                            .guard
+                            .iter()
+                            .map(|from| transition(StateTransitionKindDto::Guarded, Some(from)))
+                            .collect()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut states = Vec::new();
+            for transition in &transitions {
+                for state in transition.from.iter().chain([&transition.to]) {
+                    if !states.contains(state) {
+                        states.push(state.clone());
+                    }
+                }
+            }
+            let mut machine = StateMachineDto {
+                enum_id: enum_id.to_string(),
+                enum_name: assignments[0].2.enum_name.clone(),
+                fields: assignments
+                    .iter()
+                    .map(|(_, _, assignment)| assignment.field.clone())
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+                states,
+                transitions,
+                mermaid: String::new(),
+                dot: String::new(),
+            };
+            machine.mermaid = machine.to_mermaid();
+            machine.dot = machine.to_dot();
+            machine
+        })
+        .collect::<Vec<_>>();
+    machines.sort_by(|left, right| left.enum_name.cmp(&right.enum_name));
+
+    StateMachineReportDto { machines }
+}
+
+impl StateMachineDto {
+    /// `stateDiagram-v2` source. Unguarded transitions start at an `(any)`
+    /// pseudo-state.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = "stateDiagram-v2\n".to_string();
+        if self.has_unguarded() {
+            mermaid.push_str("    state \"(any)\" as any_state\n");
+        }
+        for (from, to, label) in self.diagram_edges() {
+            let from = match from {
+                DiagramSource::Initial => "[*]",
+                DiagramSource::Any => "any_state",
+                DiagramSource::State(state) => state,
+            };
+            let _ = writeln!(mermaid, "    {from} --> {to} : {label}");
+        }
+        mermaid
+    }
+
+    /// Graphviz `digraph` source with one node per state.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n    rankdir=LR;\n", self.enum_name);
+        if self
+            .transitions
+            .iter()
+            .any(|transition| transition.kind == StateTransitionKindDto::Initial)
+        {
+            dot.push_str("    \"__initial\" [shape=point];\n");
+        }
+        if self.has_unguarded() {
+            dot.push_str("    \"__any\" [label=\"(any)\", shape=plaintext];\n");
+        }
+        for state in &self.states {
+            let _ = writeln!(dot, "    \"{state}\";");
+        }
+        for (from, to, label) in self.diagram_edges() {
+            let from = match from {
+                DiagramSource::Initial => "__initial",
+                DiagramSource::Any => "__any",
+                DiagramSource::State(state) => state,
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{from}\" -> \"{to}\" [label=\"{}\"];",
+                label.replace('"', "\\\"")
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn has_unguarded(&self) -> bool {
+        self.transitions
+            .iter()
+            .any(|transition| transition.kind == StateTransitionKindDto::Unguarded)
+    }
+
+    /// Distinct `(from, to, via)` edges in transition order.
+    fn diagram_edges(&self) -> Vec<(DiagramSource<'_>, &str, &str)> {
+        let mut edges = Vec::new();
+        for transition in &self.transitions {
+            let from = match (transition.kind, &transition.from) {
+                (StateTransitionKindDto::Initial, _) => DiagramSource::Initial,
+                (_, Some(from)) => DiagramSource::State(from),
+                (_, None) => DiagramSource::Any,
+            };
+            let edge = (from, transition.to.as_str(), transition.via_label.as_str());
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+        edges
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiagramSource<'a> {
+    Initial,
+    Any,
+    State(&'a str),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{state_machines, StateAssignmentDto, StateTransitionKindDto};
+    use crate::graph::{
+        stable_node_id, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn method(label: &str, assignments: Vec<StateAssignmentDto>) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(GraphNodeKind::Method, &["fixture", label]),
+            kind: GraphNodeKind::Method,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: assignments,
+            cfg: None,
+        }
+    }
+
+    fn assignment(
+        line: u32,
+        variant: &str,
+        guard: &[&str],
+        construction: bool,
+    ) -> StateAssignmentDto {
+        StateAssignmentDto {
+            enum_id: "enum:phase".to_string(),
+            enum_name: "Phase".to_string(),
+            field: "phase".to_string(),
+            variant: variant.to_string(),
+            guard: guard.iter().map(ToString::to_string).collect(),
+            construction,
+            range: SourceRangeDto {
+                path: "src/status.rs".to_string(),
+                start_line: line,
+                start_column: 1,
+                end_line: line,
+                end_column: 40,
+            },
+        }
+    }
+
+    #[test]
+    fn infers_transitions_and_renders_state_diagrams() {
+        let snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes: vec![
+                method("default", vec![assignment(3, "Idle", &[], true)]),
+                method(
+                    "begin",
+                    vec![
+                        assignment(10, "Busy", &["Ready", "Idle"], false),
+                        assignment(12, "Busy", &["Idle"], false),
+                    ],
+                ),
+                method("shutdown", vec![assignment(20, "Stopped", &[], false)]),
+            ],
+            edges: Vec::new(),
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        let report = state_machines(&snapshot);
+
+        let machine = &report.machines[0];
+        assert_eq!(machine.fields, vec!["phase"]);
+        assert_eq!(machine.states, vec!["Idle", "Ready", "Busy", "Stopped"]);
+        assert_eq!(
+            machine
+                .transitions
+                .iter()
+                .map(|transition| transition.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                StateTransitionKindDto::Initial,
+                StateTransitionKindDto::Guarded,
+                StateTransitionKindDto::Guarded,
+                StateTransitionKindDto::Guarded,
+                StateTransitionKindDto::Unguarded,
+            ]
+        );
+        assert_eq!(
+            machine.mermaid,
+            concat!(
+                "stateDiagram-v2\n",
+                "    state \"(any)\" as any_state\n",
+                "    [*] --> Idle : default\n",
+                "    Ready --> Busy : begin\n",
+                "    Idle --> Busy : begin\n",
+                "    any_state --> Stopped : shutdown\n",
+            )
+        );
+        assert!(machine
+            .dot
+            .contains("    \"__initial\" -> \"Idle\" [label=\"default\"];\n"));
+        assert!(machine
+            .dot
+            .contains("    \"__any\" -> \"Stopped\" [label=\"shutdown\"];\n"));
+    }
+}
@@ -0,0 +1,300 @@
+//! Unsafe code inventory: per-item unsafe sites, rollups, and review listing.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::metrics::NodeMetricsDto;
+use super::ownership::NodeOwnership;
+use super::{GraphNodeKind, GraphSnapshotDto, SourceRangeDto};
+
+/// One `unsafe` keyword owned by a graph node: the node's own `unsafe fn`,
+/// `unsafe impl`, or `unsafe trait` declaration, or an `unsafe { }` block in
+/// a function body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeSiteDto {
+    pub kind: UnsafeSiteKindDto,
+    /// From the `unsafe` keyword to the end of the declaration or block.
+    pub range: SourceRangeDto,
+    /// Whether the comments directly above, or on the line of, the site
+    /// contain `SAFETY:`. A `# Safety` doc section counts for `unsafe fn`
+    /// and `unsafe trait` declarations.
+    pub has_safety_comment: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsafeSiteKindDto {
+    Function,
+    Impl,
+    Trait,
+    Block,
+}
+
+/// Unsafe site counts summed over a file, module, or crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeRollupDto {
+    pub unsafe_functions: u32,
+    pub unsafe_impls: u32,
+    pub unsafe_traits: u32,
+    pub unsafe_blocks: u32,
+    /// Sites of any kind without a safety comment.
+    pub undocumented: u32,
+}
+
+impl UnsafeRollupDto {
+    fn add(&mut self, site: &UnsafeSiteDto) {
+        match site.kind {
+            UnsafeSiteKindDto::Function => self.unsafe_functions += 1,
+            UnsafeSiteKindDto::Impl => self.unsafe_impls += 1,
+            UnsafeSiteKindDto::Trait => self.unsafe_traits += 1,
+            UnsafeSiteKindDto::Block => self.unsafe_blocks += 1,
+        }
+        if !site.has_safety_comment {
+            self.undocumented += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeInventoryDto {
+    pub totals: UnsafeRollupDto,
+    /// Sorted by file path and position.
+    pub sites: Vec<UnsafeInventorySiteDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeInventorySiteDto {
+    pub node_id: String,
+    pub node_kind: GraphNodeKind,
+    pub node_label: String,
+    /// Crate-relative module path of the owning node.
+    pub module_path: Option<String>,
+    pub crate_id: Option<String>,
+    pub site: UnsafeSiteDto,
+}
+
+/// Sums unsafe sites into the owning file, module, and crate node metrics.
+pub fn attach_unsafe_rollups(snapshot: &mut GraphSnapshotDto) {
+    let ownership = NodeOwnership::from_snapshot(snapshot);
+    let mut rollups = BTreeMap::<String, UnsafeRollupDto>::new();
+
+    for node in &snapshot.nodes {
+        if node.unsafe_sites.is_empty() {
+            continue;
+        }
+        let owners = [
+            ownership.owning_file_id(&node.id, None),
+            ownership.owning_module_id(&node.id, None),
+            ownership.owning_crate_id(&node.id, None),
+        ];
+        for owner_id in owners.into_iter().flatten() {
+            let rollup = rollups.entry(owner_id.to_string()).or_default();
+            for site in &node.unsafe_sites {
+                rollup.add(site);
+            }
+        }
+    }
+
+    for node in &mut snapshot.nodes {
+        if let Some(rollup) = rollups.remove(&node.id) {
+            node.metrics
+                .get_or_insert_with(NodeMetricsDto::default)
+                .unsafe_rollup = Some(rollup);
+        }
+    }
+}
+
+/// Every unsafe site in the snapshot with its owning node, for review.
+pub fn unsafe_inventory(snapshot: &GraphSnapshotDto) -> UnsafeInventoryDto {
+    let ownership = NodeOwnership::from_snapshot(snapshot);
+    let mut totals = UnsafeRollupDto::default();
+    let mut sites = Vec::new();
+
+    for node in &snapshot.nodes {
+        for site in &node.unsafe_sites {
+            totals.add(site);
+            sites.push(UnsafeInventorySiteDto {
+                node_id: node.id.clone(),
+                node_kind: node.kind.clone(),
+                node_label: node.label.clone(),
+                module_path: ownership.module_path(&node.id).map(str::to_string),
+                crate_id: ownership
+                    .owning_crate_id(&node.id, None)
+                    .map(str::to_string),
+                site: site.clone(),
+            });
+        }
+    }
+    sites.sort_by(|left, right| {
+        let position = |entry: &UnsafeInventorySiteDto| {
+            (
+                entry.site.range.path.clone(),
+                entry.site.range.start_line,
+                entry.site.range.start_column,
+            )
+        };
+        position(left)
+            .cmp(&position(right))
+            .then_with(|| left.node_id.cmp(&right.node_id))
+    });
+
+    UnsafeInventoryDto { totals, sites }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        attach_unsafe_rollups, unsafe_inventory, UnsafeRollupDto, UnsafeSiteDto, UnsafeSiteKindDto,
+    };
+    use crate::graph::{
+        stable_edge_id, stable_node_id, EdgeConfidenceDto, EdgeProvenanceDto, GraphEdgeDto,
+        GraphEdgeKind, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, SourceRangeDto,
+        GRAPH_SCHEMA_VERSION,
+    };
+
+    fn node(kind: GraphNodeKind, label: &str, unsafe_sites: Vec<UnsafeSiteDto>) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &["fixture", label]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: Vec::new(),
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites,
+            error_flow: None,
+            wire_contract: None,
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn site(kind: UnsafeSiteKindDto, line: u32, has_safety_comment: bool) -> UnsafeSiteDto {
+        UnsafeSiteDto {
+            kind,
+            range: SourceRangeDto {
+                path: "src/raw.rs".to_string(),
+                start_line: line,
+                start_column: 5,
+                end_line: line,
+                end_column: 20,
+            },
+            has_safety_comment,
+        }
+    }
+
+    fn edge(
+        kind: GraphEdgeKind,
+        source: &GraphNodeDto,
+        target: &GraphNodeDto,
+        provenance: EdgeProvenanceDto,
+    ) -> GraphEdgeDto {
+        GraphEdgeDto {
+            id: stable_edge_id(kind.clone(), &source.id, &target.id),
+            kind,
+            source_id: source.id.clone(),
+            target_id: target.id.clone(),
+            provenance,
+            confidence: EdgeConfidenceDto::Exact,
+            evidence: None,
+            dependency: None,
+            cfg: None,
+        }
+    }
+
+    #[test]
+    fn rolls_up_unsafe_sites_and_lists_them_in_source_order() {
+        let krate = node(GraphNodeKind::Crate, "fixture", Vec::new());
+        let file = node(GraphNodeKind::File, "src/raw.rs", Vec::new());
+        let module = node(GraphNodeKind::Module, "raw", Vec::new());
+        let read = node(
+            GraphNodeKind::Function,
+            "read",
+            vec![
+                site(UnsafeSiteKindDto::Function, 3, true),
+                site(UnsafeSiteKindDto::Block, 5, false),
+            ],
+        );
+        let send = node(
+            GraphNodeKind::Impl,
+            "impl Send for Handle",
+            vec![site(UnsafeSiteKindDto::Impl, 1, true)],
+        );
+        let mut snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            edges: vec![
+                edge(
+                    GraphEdgeKind::Contains,
+                    &krate,
+                    &file,
+                    EdgeProvenanceDto::Normalized,
+                ),
+                edge(
+                    GraphEdgeKind::Defines,
+                    &file,
+                    &module,
+                    EdgeProvenanceDto::Normalized,
+                ),
+                edge(GraphEdgeKind::Defines, &file, &read, EdgeProvenanceDto::Syn),
+                edge(GraphEdgeKind::Defines, &file, &send, EdgeProvenanceDto::Syn),
+            ],
+            nodes: vec![krate.clone(), file.clone(), module.clone(), read, send],
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        attach_unsafe_rollups(&mut snapshot);
+
+        let expected = UnsafeRollupDto {
+            unsafe_functions: 1,
+            unsafe_impls: 1,
+            unsafe_traits: 0,
+            unsafe_blocks: 1,
+            undocumented: 1,
+        };
+        for owner in [&krate, &file, &module] {
+            let rollup = snapshot
+                .nodes
+                .iter()
+                .find(|node| node.id == owner.id)
+                .and_then(|node| node.metrics.as_ref()?.unsafe_rollup.clone());
+            assert_eq!(rollup.as_ref(), Some(&expected), "{}", owner.label);
+        }
+
+        let inventory = unsafe_inventory(&snapshot);
+        assert_eq!(inventory.totals, expected);
+        let listed = inventory
+            .sites
+            .iter()
+            .map(|entry| {
+                (
+                    entry.node_label.as_str(),
+                    entry.site.range.start_line,
+                    entry.module_path.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            vec![
+                ("impl Send for Handle", 1, Some("raw")),
+                ("read", 3, Some("raw")),
+                ("read", 5, Some("raw")),
+            ]
+        );
+        assert!(inventory
+            .sites
+            .iter()
+            .all(|entry| entry.crate_id.as_deref() == Some(krate.id.as_str())));
+    }
+}
@@ -0,0 +1,659 @@
+//! Serde wire contracts: the JSON shape of `Serialize`/`Deserialize` types,
+//! a per-type catalog, and generated JSON Schema.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::ownership::NodeOwnership;
+use super::GraphSnapshotDto;
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON shape of a struct or enum deriving `Serialize` or `Deserialize`,
+/// after serde attributes are applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireContractDto {
+    /// Type name after a container `#[serde(rename)]`.
+    pub wire_name: String,
+    pub serialize: bool,
+    pub deserialize: bool,
+    pub deny_unknown_fields: bool,
+    pub shape: WireShapeDto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum WireShapeDto {
+    /// Named fields, serialized as a JSON object.
+    Object {
+        fields: Vec<WireFieldDto>,
+    },
+    /// Several unnamed fields, serialized as a JSON array.
+    Tuple {
+        items: Vec<WireTypeDto>,
+    },
+    /// One unnamed field or `#[serde(transparent)]`, serialized as the field.
+    Newtype {
+        item: WireTypeDto,
+    },
+    Unit,
+    Enum {
+        tagging: EnumTaggingDto,
+        variants: Vec<WireVariantDto>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireFieldDto {
+    pub name: String,
+    /// Key after `rename` or the container's `rename_all`.
+    pub wire_name: String,
+    pub ty: WireTypeDto,
+    /// False for `Option` fields and fields with `default`,
+    /// `skip_serializing_if`, or a one-sided skip.
+    pub required: bool,
+    pub flatten: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireVariantDto {
+    pub name: String,
+    pub wire_name: String,
+    /// `Object`, `Tuple`, `Newtype`, or `Unit`.
+    pub shape: WireShapeDto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum EnumTaggingDto {
+    /// `{ "Variant": content }`, or `"Variant"` for unit variants.
+    External,
+    /// `#[serde(tag = "..")]`: the tag is a key inside the variant object.
+    Internal {
+        tag: String,
+    },
+    /// `#[serde(tag = "..", content = "..")]`.
+    Adjacent {
+        tag: String,
+        content: String,
+    },
+    Untagged,
+}
+
+/// JSON value a Rust field type serializes to. Types serde renders through
+/// `with`, `serialize_with`, or a generic parameter are `Any`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum WireTypeDto {
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Null,
+    Any,
+    Optional {
+        item: Box<WireTypeDto>,
+    },
+    Array {
+        items: Box<WireTypeDto>,
+    },
+    /// String-keyed map such as `BTreeMap<String, V>`.
+    Map {
+        values: Box<WireTypeDto>,
+    },
+    Tuple {
+        items: Vec<WireTypeDto>,
+    },
+    /// Another type by its Rust name, such as `GraphNodeDto`.
+    Named {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireContractCatalogDto {
+    /// Sorted by definition key.
+    pub contracts: Vec<WireContractEntryDto>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireContractEntryDto {
+    pub node_id: String,
+    pub type_name: String,
+    pub module_path: Option<String>,
+    pub crate_id: Option<String>,
+    /// Key under `$defs` in the schema bundle: the type name, or the
+    /// module-qualified name when several contracts share it.
+    pub definition_key: String,
+    pub contract: WireContractDto,
+    /// Standalone JSON Schema with every referenced definition inlined
+    /// under `$defs`.
+    pub schema: Value,
+}
+
+/// Catalog of every wire contract in the snapshot with its JSON Schema.
+pub fn wire_contract_catalog(snapshot: &GraphSnapshotDto) -> WireContractCatalogDto {
+    let definitions = SchemaDefinitions::from_snapshot(snapshot);
+    let contracts = definitions
+        .entries
+        .iter()
+        .map(|entry| {
+            let mut schema = Map::new();
+            schema.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+            schema.insert("title".to_string(), json!(entry.contract.wire_name));
+            if let Value::Object(body) = &definitions.schemas[&entry.key] {
+                schema.extend(body.clone());
+            }
+            let referenced = definitions.referenced_from(&entry.key);
+            if !referenced.is_empty() {
+                schema.insert(
+                    "$defs".to_string(),
+                    Value::Object(
+                        referenced
+                            .into_iter()
+                            .map(|key| (key.to_string(), definitions.schemas[key].clone()))
+                            .collect(),
+                    ),
+                );
+            }
+            WireContractEntryDto {
+                node_id: entry.node_id.clone(),
+                type_name: entry.type_name.clone(),
+                module_path: entry.module_path.clone(),
+                crate_id: entry.crate_id.clone(),
+                definition_key: entry.key.clone(),
+                contract: entry.contract.clone(),
+                schema: Value::Object(schema),
+            }
+        })
+        .collect();
+
+    WireContractCatalogDto { contracts }
+}
+
+/// One JSON Schema document with every wire contract under `$defs`.
+pub fn schema_bundle(snapshot: &GraphSnapshotDto) -> Value {
+    let definitions = SchemaDefinitions::from_snapshot(snapshot);
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "$defs": Value::Object(definitions.schemas.into_iter().collect()),
+    })
+}
+
+struct DefinitionEntry {
+    key: String,
+    node_id: String,
+    type_name: String,
+    module_path: Option<String>,
+    crate_id: Option<String>,
+    contract: WireContractDto,
+}
+
+struct SchemaDefinitions {
+    entries: Vec<DefinitionEntry>,
+    schemas: BTreeMap<String, Value>,
+    references: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SchemaDefinitions {
+    fn from_snapshot(snapshot: &GraphSnapshotDto) -> Self {
+        let ownership = NodeOwnership::from_snapshot(snapshot);
+        let contract_nodes = snapshot
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node, node.wire_contract.as_ref()?)))
+            .collect::<Vec<_>>();
+        let mut name_counts = BTreeMap::<&str, usize>::new();
+        for (node, _) in &contract_nodes {
+            *name_counts.entry(node.label.as_str()).or_default() += 1;
+        }
+
+        let mut keys_by_name = BTreeMap::<String, String>::new();
+        let mut entries = contract_nodes
+            .into_iter()
+            .map(|(node, contract)| {
+                let module_path = ownership.module_path(&node.id).map(str::to_string);
+                let key = match (name_counts[node.label.as_str()], module_path.as_deref()) {
+                    (1, _) => node.label.clone(),
+                    (_, Some(module_path)) if !module_path.is_empty() => {
+                        format!("{module_path}::{}", node.label)
+                    }
+                    _ => node.id.clone(),
+                };
+                if name_counts[node.label.as_str()] == 1 {
+                    keys_by_name.insert(node.label.clone(), key.clone());
+                }
+                DefinitionEntry {
+                    key,
+                    node_id: node.id.clone(),
+                    type_name: node.label.clone(),
+                    module_path,
+                    crate_id: ownership.crate_id(&node.id).map(str::to_string),
+                    contract: contract.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|left, right| left.key.cmp(&right.key));
+
+        let mut schemas = BTreeMap::new();
+        let mut references = BTreeMap::new();
+        for entry in &entries {
+            let mut writer = SchemaWriter {
+                keys_by_name: &keys_by_name,
+                references: BTreeSet::new(),
+            };
+            let schema = writer.contract_schema(&entry.contract);
+            schemas.insert(entry.key.clone(), schema);
+            references.insert(entry.key.clone(), writer.references);
+        }
+
+        Self {
+            entries,
+            schemas,
+            references,
+        }
+    }
+
+    /// Definitions reachable from `key` through `$ref`, including `key`
+    /// itself only when it is recursive.
+    fn referenced_from(&self, key: &str) -> BTreeSet<&str> {
+        let mut reached = BTreeSet::new();
+        let mut pending = self
+            .references
+            .get(key)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        while let Some(next) = pending.pop() {
+            if reached.insert(next) {
+                pending.extend(
+                    self.references
+                        .get(next)
+                        .into_iter()
+                        .flatten()
+                        .map(String::as_str),
+                );
+            }
+        }
+        reached
+    }
+}
+
+/// Renders contracts as JSON Schema, pointing `Named` types at their
+/// `$defs` entry and leaving unknown or ambiguous names unconstrained.
+struct SchemaWriter<'a> {
+    keys_by_name: &'a BTreeMap<String, String>,
+    references: BTreeSet<String>,
+}
+
+impl SchemaWriter<'_> {
+    fn contract_schema(&mut self, contract: &WireContractDto) -> Value {
+        self.shape_schema(&contract.shape, contract.deny_unknown_fields)
+    }
+
+    fn shape_schema(&mut self, shape: &WireShapeDto, deny_unknown_fields: bool) -> Value {
+        match shape {
+            WireShapeDto::Object { fields } => {
+                self.object_schema(fields, deny_unknown_fields, Vec::new())
+            }
+            WireShapeDto::Tuple { items } => {
+                let items = items
+                    .iter()
+                    .map(|item| self.type_schema(item))
+                    .collect::<Vec<_>>();
+                json!({
+                    "type": "array",
+                    "prefixItems": items,
+                    "minItems": items.len(),
+                    "maxItems": items.len(),
+                })
+            }
+            WireShapeDto::Newtype { item } => self.type_schema(item),
+            WireShapeDto::Unit => json!({ "type": "null" }),
+            WireShapeDto::Enum { tagging, variants } => self.enum_schema(tagging, variants),
+        }
+    }
+
+    /// Object with `fields`, plus `extra` properties such as an internal
+    /// tag. Flattened fields merge through `allOf`, or `additionalProperties`
+    /// for flattened maps.
+    fn object_schema(
+        &mut self,
+        fields: &[WireFieldDto],
+        deny_unknown_fields: bool,
+        extra: Vec<(String, Value)>,
+    ) -> Value {
+        let mut properties = Map::new();
+        let mut required = extra
+            .iter()
+            .map(|(name, _)| json!(name))
+            .collect::<Vec<_>>();
+        properties.extend(extra);
+        let mut flattened = Vec::new();
+        let mut additional = None;
+        for field in fields {
+            if field.flatten {
+                match &field.ty {
+                    WireTypeDto::Map { values } => additional = Some(self.type_schema(values)),
+                    ty => flattened.push(self.type_schema(ty)),
+                }
+                continue;
+            }
+            properties.insert(field.wire_name.clone(), self.type_schema(&field.ty));
+            if field.required {
+                required.push(json!(field.wire_name));
+            }
+        }
+
+        let mut object = Map::new();
+        object.insert("type".to_string(), json!("object"));
+        object.insert("properties".to_string(), Value::Object(properties));
+        if !required.is_empty() {
+            object.insert("required".to_string(), Value::Array(required));
+        }
+        match additional {
+            Some(values) => {
+                object.insert("additionalProperties".to_string(), values);
+            }
+            None if deny_unknown_fields && flattened.is_empty() => {
+                object.insert("additionalProperties".to_string(), json!(false));
+            }
+            None => {}
+        }
+        if flattened.is_empty() {
+            Value::Object(object)
+        } else {
+            flattened.insert(0, Value::Object(object));
+            json!({ "allOf": flattened })
+        }
+    }
+
+    fn enum_schema(&mut self, tagging: &EnumTaggingDto, variants: &[WireVariantDto]) -> Value {
+        let all_unit = variants
+            .iter()
+            .all(|variant| variant.shape == WireShapeDto::Unit);
+        if all_unit && matches!(tagging, EnumTaggingDto::External) {
+            let names = variants
+                .iter()
+                .map(|variant| json!(variant.wire_name))
+                .collect::<Vec<_>>();
+            return json!({ "type": "string", "enum": names });
+        }
+
+        let alternatives = variants
+            .iter()
+            .map(|variant| self.variant_schema(tagging, variant))
+            .collect::<Vec<_>>();
+        json!({ "oneOf": alternatives })
+    }
+
+    fn variant_schema(&mut self, tagging: &EnumTaggingDto, variant: &WireVariantDto) -> Value {
+        let name = &variant.wire_name;
+        match tagging {
+            EnumTaggingDto::External => match &variant.shape {
+                WireShapeDto::Unit => json!({ "const": name }),
+                shape => {
+                    let content = self.shape_schema(shape, false);
+                    json!({
+                        "type": "object",
+                        "properties": { name.as_str(): content },
+                        "required": [name],
+                        "additionalProperties": false,
+                    })
+                }
+            },
+            EnumTaggingDto::Internal { tag } => {
+                let tag_property = vec![(tag.clone(), json!({ "const": name }))];
+                match &variant.shape {
+                    WireShapeDto::Object { fields } => {
+                        self.object_schema(fields, false, tag_property)
+                    }
+                    WireShapeDto::Newtype { item } => {
+                        let tag_object = self.object_schema(&[], false, tag_property);
+                        let content = self.type_schema(item);
+                        json!({ "allOf": [tag_object, content] })
+                    }
+                    _ => self.object_schema(&[], false, tag_property),
+                }
+            }
+            EnumTaggingDto::Adjacent { tag, content } => {
+                let mut properties = vec![(tag.clone(), json!({ "const": name }))];
+                if variant.shape != WireShapeDto::Unit {
+                    properties.push((content.clone(), self.shape_schema(&variant.shape, false)));
+                }
+                self.object_schema(&[], true, properties)
+            }
+            EnumTaggingDto::Untagged => self.shape_schema(&variant.shape, false),
+        }
+    }
+
+    fn type_schema(&mut self, ty: &WireTypeDto) -> Value {
+        match ty {
+            WireTypeDto::Boolean => json!({ "type": "boolean" }),
+            WireTypeDto::Integer => json!({ "type": "integer" }),
+            WireTypeDto::Number => json!({ "type": "number" }),
+            WireTypeDto::String => json!({ "type": "string" }),
+            WireTypeDto::Null => json!({ "type": "null" }),
+            WireTypeDto::Any => json!({}),
+            WireTypeDto::Optional { item } => {
+                json!({ "anyOf": [self.type_schema(item), { "type": "null" }] })
+            }
+            WireTypeDto::Array { items } => {
+                json!({ "type": "array", "items": self.type_schema(items) })
+            }
+            WireTypeDto::Map { values } => {
+                json!({ "type": "object", "additionalProperties": self.type_schema(values) })
+            }
+            WireTypeDto::Tuple { items } => {
+                let items = items
+                    .iter()
+                    .map(|item| self.type_schema(item))
+                    .collect::<Vec<_>>();
+                json!({
+                    "type": "array",
+                    "prefixItems": items,
+                    "minItems": items.len(),
+                    "maxItems": items.len(),
+                })
+            }
+            WireTypeDto::Named { name } => match self.keys_by_name.get(name) {
+                Some(key) => {
+                    self.references.insert(key.clone());
+                    json!({ "$ref": format!("#/$defs/{key}") })
+                }
+                None => json!({}),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        schema_bundle, wire_contract_catalog, EnumTaggingDto, WireContractDto, WireFieldDto,
+        WireShapeDto, WireTypeDto, WireVariantDto,
+    };
+    use crate::graph::{
+        stable_node_id, GraphNodeDto, GraphNodeKind, GraphSnapshotDto, GRAPH_SCHEMA_VERSION,
+    };
+    use serde_json::json;
+
+    fn node(kind: GraphNodeKind, label: &str, contract: WireContractDto) -> GraphNodeDto {
+        GraphNodeDto {
+            id: stable_node_id(kind.clone(), &["fixture", label]),
+            kind,
+            label: label.to_string(),
+            source_range: None,
+            metrics: None,
+            visibility: None,
+            attributes: Vec::new(),
+            derives: vec!["Serialize".to_string()],
+            signature: None,
+            public_paths: Vec::new(),
+            unsafe_sites: Vec::new(),
+            error_flow: None,
+            wire_contract: Some(contract),
+            state_assignments: Vec::new(),
+            cfg: None,
+        }
+    }
+
+    fn field(name: &str, wire_name: &str, ty: WireTypeDto, required: bool) -> WireFieldDto {
+        WireFieldDto {
+            name: name.to_string(),
+            wire_name: wire_name.to_string(),
+            ty,
+            required,
+            flatten: false,
+        }
+    }
+
+    #[test]
+    fn generates_standalone_schemas_and_a_bundle() {
+        let snapshot_node = node(
+            GraphNodeKind::Struct,
+            "SnapshotDto",
+            WireContractDto {
+                wire_name: "SnapshotDto".to_string(),
+                serialize: true,
+                deserialize: true,
+                deny_unknown_fields: true,
+                shape: WireShapeDto::Object {
+                    fields: vec![
+                        field(
+                            "schema_version",
+                            "schemaVersion",
+                            WireTypeDto::Integer,
+                            true,
+                        ),
+                        field(
+                            "kind",
+                            "kind",
+                            WireTypeDto::Optional {
+                                item: Box::new(WireTypeDto::Named {
+                                    name: "KindDto".to_string(),
+                                }),
+                            },
+                            false,
+                        ),
+                        field(
+                            "source",
+                            "source",
+                            WireTypeDto::Named {
+                                name: "PathBuf".to_string(),
+                            },
+                            true,
+                        ),
+                    ],
+                },
+            },
+        );
+        let kind_node = node(
+            GraphNodeKind::Enum,
+            "KindDto",
+            WireContractDto {
+                wire_name: "KindDto".to_string(),
+                serialize: true,
+                deserialize: false,
+                deny_unknown_fields: false,
+                shape: WireShapeDto::Enum {
+                    tagging: EnumTaggingDto::Internal {
+                        tag: "kind".to_string(),
+                    },
+                    variants: vec![
+                        WireVariantDto {
+                            name: "Empty".to_string(),
+                            wire_name: "empty".to_string(),
+                            shape: WireShapeDto::Unit,
+                        },
+                        WireVariantDto {
+                            name: "Sized".to_string(),
+                            wire_name: "sized".to_string(),
+                            shape: WireShapeDto::Object {
+                                fields: vec![field("size", "size", WireTypeDto::Number, true)],
+                            },
+                        },
+                    ],
+                },
+            },
+        );
+        let snapshot = GraphSnapshotDto {
+            schema_version: GRAPH_SCHEMA_VERSION,
+            source_root: "/fixture".to_string(),
+            generated_at: "test".to_string(),
+            nodes: vec![snapshot_node, kind_node],
+            edges: Vec::new(),
+            diagnostics: Vec::new(),
+            configuration: None,
+        };
+
+        let catalog = wire_contract_catalog(&snapshot);
+
+        let keys = catalog
+            .contracts
+            .iter()
+            .map(|entry| entry.definition_key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["KindDto", "SnapshotDto"]);
+        let kind_schema = json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "kind": { "const": "empty" } },
+                    "required": ["kind"],
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "const": "sized" },
+                        "size": { "type": "number" },
+                    },
+                    "required": ["kind", "size"],
+                },
+            ],
+        });
+        assert_eq!(
+            catalog.contracts[1].schema,
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "SnapshotDto",
+                "type": "object",
+                "properties": {
+                    "schemaVersion": { "type": "integer" },
+                    "kind": { "anyOf": [{ "$ref": "#/$defs/KindDto" }, { "type": "null" }] },
+                    "source": {},
+                },
+                "required": ["schemaVersion", "source"],
+                "additionalProperties": false,
+                "$defs": { "KindDto": kind_schema.clone() },
+            })
+        );
+
+        let bundle = schema_bundle(&snapshot);
+        assert_eq!(bundle["$defs"]["KindDto"], kind_schema);
+        assert_eq!(
+            bundle["$defs"]["SnapshotDto"]["properties"]["schemaVersion"],
+            json!({ "type": "integer" })
+        );
+    }
+}
@@ -3,12 +3,17 @@
 mod analyzer;
 mod app_lifecycle;
 mod app_setup;
+mod cli;
 mod commands;
 mod config;
 mod graph;
 mod source;
 
 fn main() {
+    if let Some(exit_code) = cli::run_from_args(std::env::args().skip(1)) {
+        std::process::exit(exit_code);
+    }
+
     if let Err(error) = app_setup::run_app() {
         eprintln!("failed to start Whip Docs: {error}");
         std::process::exit(1);
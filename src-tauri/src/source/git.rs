@@ -0,0 +1,351 @@
+//! Read-only git access for the validated source repository.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{SourcePathError, ValidatedRepoPath};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum GitError {
+    #[error("git revision must be a non-empty name that does not start with '-': '{0}'")]
+    InvalidRevision(String),
+    #[error("git revision '{0}' does not name a commit")]
+    UnknownRevision(String),
+    #[error("failed to run git {command}: {message}")]
+    Command { command: String, message: String },
+    #[error("failed to export revision '{revision}': {message}")]
+    Export { revision: String, message: String },
+    #[error(transparent)]
+    SourcePath(#[from] SourcePathError),
+}
+
+/// Runs `git <args>` in the source root and returns trimmed stdout.
+pub fn git_output(source_root: &ValidatedRepoPath, args: &[&str]) -> Result<String, GitError> {
+    let command = args.join(" ");
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(source_root.as_path())
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|error| GitError::Command {
+            command: command.clone(),
+            message: error.to_string(),
+        })?;
+    if !output.status.success() {
+        return Err(GitError::Command {
+            command,
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Full commit hash for a revision such as `v1.2.0`, `main~3`, or a hash.
+pub fn resolve_commit(source_root: &ValidatedRepoPath, revision: &str) -> Result<String, GitError> {
+    if revision.trim().is_empty() || revision.starts_with('-') {
+        return Err(GitError::InvalidRevision(revision.to_string()));
+    }
+    git_output(
+        source_root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{revision}^{{commit}}"),
+        ],
+    )
+    .map_err(|_| GitError::UnknownRevision(revision.to_string()))
+}
+
+/// A non-merge commit that touched the source root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitCommit {
+    pub hash: String,
+    /// Committer time in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub author_name: String,
+    pub author_email: String,
+    pub files: Vec<GitFileChange>,
+}
+
+/// Lines changed in one file by one commit. Binary files count as zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitFileChange {
+    /// Relative to the source root.
+    pub path: String,
+    pub added: u32,
+    pub removed: u32,
+}
+
+/// Non-merge commits touching the source root, newest first, optionally
+/// limited to the last `since_days` days. Renames are reported as a delete
+/// and an add so every path is one that existed at that commit.
+pub fn commit_history(
+    source_root: &ValidatedRepoPath,
+    since_days: Option<u32>,
+) -> Result<Vec<GitCommit>, GitError> {
+    let since = since_days.map(|days| format!("--since={days}.days.ago"));
+    let mut args = vec![
+        "-c",
+        "core.quotePath=off",
+        "log",
+        "--relative",
+        "--no-merges",
+        "--no-renames",
+        "--numstat",
+        "--format=%x1e%H%x1f%ct%x1f%an%x1f%ae",
+    ];
+    args.extend(since.as_deref());
+    args.extend(["--", "."]);
+    let output = git_output(source_root, &args)?;
+
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut header = lines.next()?.split('\x1f');
+            let hash = header.next()?.to_string();
+            let timestamp = header.next()?.parse().ok()?;
+            let author_name = header.next()?.to_string();
+            let author_email = header.next()?.to_string();
+            let files = lines
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, '\t');
+                    let added = fields.next()?;
+                    let removed = fields.next()?;
+                    Some(GitFileChange {
+                        added: added.parse().unwrap_or_default(),
+                        removed: removed.parse().unwrap_or_default(),
+                        path: fields.next()?.to_string(),
+                    })
+                })
+                .collect();
+            Some(GitCommit {
+                hash,
+                timestamp,
+                author_name,
+                author_email,
+                files,
+            })
+        })
+        .collect())
+}
+
+/// The repository at one commit, exported with `git archive` into a
+/// temporary directory that is removed on drop. The source root keeps its
+/// position inside the repository, so workspace manifests above it still
+/// resolve.
+#[derive(Debug)]
+pub struct RevisionCheckout {
+    export_root: PathBuf,
+    source_root: ValidatedRepoPath,
+}
+
+impl RevisionCheckout {
+    pub fn export(source_root: &ValidatedRepoPath, revision: &str) -> Result<Self, GitError> {
+        let commit = resolve_commit(source_root, revision)?;
+        let toplevel = git_output(source_root, &["rev-parse", "--show-toplevel"])?;
+        let prefix = git_output(source_root, &["rev-parse", "--show-prefix"])?;
+        let export_root = std::env::temp_dir().join(format!(
+            "whip-docs-revision-{}-{}-{}",
+            &commit[..commit.len().min(12)],
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_nanos())
+                .unwrap_or_default()
+        ));
+        let export_error = |message: String| GitError::Export {
+            revision: revision.to_string(),
+            message,
+        };
+        fs::create_dir_all(&export_root).map_err(|error| export_error(error.to_string()))?;
+
+        let exported = extract_archive(Path::new(&toplevel), &commit, &export_root)
+            .map_err(export_error)
+            .and_then(|()| {
+                ValidatedRepoPath::parse_existing_cargo_repo(
+                    export_root.join(prefix.trim_end_matches('/')),
+                )
+                .map_err(GitError::from)
+            });
+        match exported {
+            Ok(source_root) => Ok(Self {
+                export_root,
+                source_root,
+            }),
+            Err(error) => {
+                let _ = fs::remove_dir_all(&export_root);
+                Err(error)
+            }
+        }
+    }
+
+    pub fn source_root(&self) -> &ValidatedRepoPath {
+        &self.source_root
+    }
+}
+
+impl Drop for RevisionCheckout {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.export_root);
+    }
+}
+
+/// Pipes `git archive` of the whole repository into `tar -x` so nothing in
+/// the repository, its index, or its worktree list changes. Run from a
+/// subdirectory, `git archive` would only include that subdirectory.
+fn extract_archive(repo: &Path, commit: &str, destination: &Path) -> Result<(), String> {
+    let mut archive = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["archive", "--format=tar", commit])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run git archive: {error}"))?;
+    let archive_stdout = archive
+        .stdout
+        .take()
+        .ok_or_else(|| "git archive produced no output".to_string())?;
+    let unpack = Command::new("tar")
+        .arg("-x")
+        .arg("-C")
+        .arg(destination)
+        .stdin(archive_stdout)
+        .output()
+        .map_err(|error| format!("failed to run tar: {error}"))?;
+    let archive = archive
+        .wait_with_output()
+        .map_err(|error| format!("git archive failed: {error}"))?;
+
+    if !archive.status.success() {
+        return Err(String::from_utf8_lossy(&archive.stderr).trim().to_string());
+    }
+    if !unpack.status.success() {
+        return Err(String::from_utf8_lossy(&unpack.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit_history, resolve_commit, GitError, RevisionCheckout};
+    use crate::source::ValidatedRepoPath;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "whip-docs-git-{name}-{}-{timestamp}",
+            std::process::id()
+        ))
+    }
+
+    fn git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args([
+                "-c",
+                "user.name=Fixture",
+                "-c",
+                "user.email=fixture@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn exports_a_revision_without_touching_the_worktree() {
+        let repo = unique_temp_dir("export");
+        fs::create_dir_all(repo.join("crates/app/src")).expect("create fixture");
+        fs::write(
+            repo.join("crates/app/Cargo.toml"),
+            "[package]\nname = \"app\"\n",
+        )
+        .expect("write manifest");
+        fs::write(repo.join("crates/app/src/lib.rs"), "pub fn first() {}\n").expect("write lib");
+        git(&repo, &["init", "--quiet"]);
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "first"]);
+        fs::write(repo.join("crates/app/src/lib.rs"), "pub fn second() {}\n").expect("edit lib");
+
+        let source_root = ValidatedRepoPath::parse_existing_cargo_repo(repo.join("crates/app"))
+            .expect("valid source root");
+        let checkout = RevisionCheckout::export(&source_root, "HEAD").expect("export HEAD");
+        let export_root = checkout.source_root().as_path().to_path_buf();
+
+        assert!(export_root.ends_with("crates/app"));
+        assert_eq!(
+            fs::read_to_string(export_root.join("src/lib.rs")).expect("read exported lib"),
+            "pub fn first() {}\n"
+        );
+        assert_eq!(
+            resolve_commit(&source_root, "--help"),
+            Err(GitError::InvalidRevision("--help".to_string()))
+        );
+        assert_eq!(
+            resolve_commit(&source_root, "missing-tag"),
+            Err(GitError::UnknownRevision("missing-tag".to_string()))
+        );
+
+        drop(checkout);
+        assert!(!export_root.exists());
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+
+    #[test]
+    fn reads_commits_touching_the_source_root_with_relative_paths() {
+        let repo = unique_temp_dir("history");
+        fs::create_dir_all(repo.join("crates/app/src")).expect("create fixture");
+        fs::write(
+            repo.join("crates/app/Cargo.toml"),
+            "[package]\nname = \"app\"\n",
+        )
+        .expect("write manifest");
+        fs::write(repo.join("crates/app/src/lib.rs"), "pub fn first() {}\n").expect("write lib");
+        git(&repo, &["init", "--quiet"]);
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "first"]);
+        fs::write(repo.join("README.md"), "outside\n").expect("write readme");
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "--quiet", "-m", "outside"]);
+        fs::write(
+            repo.join("crates/app/src/lib.rs"),
+            "pub fn second() {}\npub fn third() {}\n",
+        )
+        .expect("edit lib");
+        git(&repo, &["commit", "--quiet", "-am", "second"]);
+
+        let source_root = ValidatedRepoPath::parse_existing_cargo_repo(repo.join("crates/app"))
+            .expect("valid source root");
+        let commits = commit_history(&source_root, Some(7)).expect("read history");
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].author_email, "fixture@example.com");
+        assert_eq!(
+            commits[0]
+                .files
+                .iter()
+                .map(|change| (change.path.as_str(), change.added, change.removed))
+                .collect::<Vec<_>>(),
+            vec![("src/lib.rs", 2, 1)]
+        );
+        assert_eq!(commits[1].files.len(), 2);
+
+        fs::remove_dir_all(repo).expect("cleanup fixture repo");
+    }
+}
@@ -1,5 +1,7 @@
 //! Validated source repository paths and snippet services.
 
+pub mod git;
+
 use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -45,6 +45,7 @@ export type AnalysisStatusDto = {
 export type GraphNodeKind =
   | 'workspace'
   | 'crate'
+  | 'external_crate'
   | 'module'
   | 'file'
   | 'struct'
@@ -53,7 +54,14 @@ export type GraphNodeKind =
   | 'impl'
   | 'function'
   | 'method'
-  | 'tauri_command';
+  | 'macro'
+  | 'const'
+  | 'static'
+  | 'type_alias'
+  | 'union'
+  | 'tauri_command'
+  | 'task'
+  | 'sync_primitive';
 
 export type GraphEdgeKind =
   | 'contains'
@@ -63,7 +71,13 @@ export type GraphEdgeKind =
   | 'calls'
   | 'implements'
   | 'references'
-  | 'exposes_command';
+  | 'exposes_command'
+  | 'depends_on'
+  | 'invokes_macro'
+  | 'spawns'
+  | 'sends'
+  | 'receives'
+  | 'locks';
 
 export type SourceRangeDto = {
   path: string;
@@ -78,6 +92,291 @@ export type GraphNodeDto = {
   kind: GraphNodeKind;
   label: string;
   sourceRange: SourceRangeDto | null;
+  metrics: NodeMetricsDto | null;
+  visibility: NodeVisibilityDto | null;
+  attributes: string[];
+  derives: string[];
+  signature: string | null;
+  publicPaths: string[];
+  unsafeSites: UnsafeSiteDto[];
+  errorFlow: ErrorFlowFactsDto | null;
+  wireContract: WireContractDto | null;
+  stateAssignments: StateAssignmentDto[];
+  cfg: string | null;
+};
+
+export type NodeVisibilityDto = 'public' | 'crate' | 'super' | 'restricted' | 'private';
+
+export type ErrorConversionKindDto = 'question' | 'map_err' | 'from_impl' | 'from_attribute';
+
+export type ErrorDetailDto = 'preserved' | 'stringified' | 'discarded';
+
+export type TrySiteDto = {
+  range: SourceRangeDto;
+  callee: string | null;
+};
+
+export type ErrorConversionDto = {
+  kind: ErrorConversionKindDto;
+  sourceType: string | null;
+  targetType: string | null;
+  callee: string | null;
+  detail: ErrorDetailDto;
+  range: SourceRangeDto;
+};
+
+export type ErrorFlowFactsDto = {
+  errorType: string | null;
+  trySites: TrySiteDto[];
+  conversions: ErrorConversionDto[];
+};
+
+export type ErrorTypeUsageDto = {
+  errorType: string;
+  returnedBy: number;
+  trySites: number;
+};
+
+export type ErrorFlowSiteDto = {
+  nodeId: string;
+  nodeLabel: string;
+  kind: ErrorConversionKindDto;
+  detail: ErrorDetailDto;
+  range: SourceRangeDto;
+};
+
+export type ErrorFlowRelationDto = {
+  sourceType: string | null;
+  targetType: string;
+  lossy: number;
+  sites: ErrorFlowSiteDto[];
+};
+
+export type ErrorFlowReportDto = {
+  errorTypes: ErrorTypeUsageDto[];
+  relations: ErrorFlowRelationDto[];
+  lossySites: ErrorFlowSiteDto[];
+};
+
+export type WireTypeDto =
+  | { kind: 'boolean' }
+  | { kind: 'integer' }
+  | { kind: 'number' }
+  | { kind: 'string' }
+  | { kind: 'null' }
+  | { kind: 'any' }
+  | { kind: 'optional'; item: WireTypeDto }
+  | { kind: 'array'; items: WireTypeDto }
+  | { kind: 'map'; values: WireTypeDto }
+  | { kind: 'tuple'; items: WireTypeDto[] }
+  | { kind: 'named'; name: string };
+
+export type WireFieldDto = {
+  name: string;
+  wireName: string;
+  ty: WireTypeDto;
+  required: boolean;
+  flatten: boolean;
+};
+
+export type EnumTaggingDto =
+  | { kind: 'external' }
+  | { kind: 'internal'; tag: string }
+  | { kind: 'adjacent'; tag: string; content: string }
+  | { kind: 'untagged' };
+
+export type WireVariantDto = {
+  name: string;
+  wireName: string;
+  shape: WireShapeDto;
+};
+
+export type WireShapeDto =
+  | { kind: 'object'; fields: WireFieldDto[] }
+  | { kind: 'tuple'; items: WireTypeDto[] }
+  | { kind: 'newtype'; item: WireTypeDto }
+  | { kind: 'unit' }
+  | { kind: 'enum'; tagging: EnumTaggingDto; variants: WireVariantDto[] };
+
+export type WireContractDto = {
+  wireName: string;
+  serialize: boolean;
+  deserialize: boolean;
+  denyUnknownFields: boolean;
+  shape: WireShapeDto;
+};
+
+export type WireContractEntryDto = {
+  nodeId: string;
+  typeName: string;
+  modulePath: string | null;
+  crateId: string | null;
+  definitionKey: string;
+  contract: WireContractDto;
+  schema: Record<string, unknown>;
+};
+
+export type WireContractCatalogDto = {
+  contracts: WireContractEntryDto[];
+};
+
+export type StateAssignmentDto = {
+  enumId: string;
+  enumName: string;
+  field: string;
+  variant: string;
+  guard: string[];
+  construction: boolean;
+  range: SourceRangeDto;
+};
+
+export type StateTransitionKindDto = 'initial' | 'guarded' | 'unguarded';
+
+export type StateTransitionDto = {
+  kind: StateTransitionKindDto;
+  from: string | null;
+  to: string;
+  viaNodeId: string;
+  viaLabel: string;
+  range: SourceRangeDto;
+};
+
+export type StateMachineDto = {
+  enumId: string;
+  enumName: string;
+  fields: string[];
+  states: string[];
+  transitions: StateTransitionDto[];
+  mermaid: string;
+  dot: string;
+};
+
+export type StateMachineReportDto = {
+  machines: StateMachineDto[];
+};
+
+export type UnsafeSiteKindDto = 'function' | 'impl' | 'trait' | 'block';
+
+export type UnsafeSiteDto = {
+  kind: UnsafeSiteKindDto;
+  range: SourceRangeDto;
+  hasSafetyComment: boolean;
+};
+
+export type UnsafeRollupDto = {
+  unsafeFunctions: number;
+  unsafeImpls: number;
+  unsafeTraits: number;
+  unsafeBlocks: number;
+  undocumented: number;
+};
+
+export type UnsafeInventorySiteDto = {
+  nodeId: string;
+  nodeKind: GraphNodeKind;
+  nodeLabel: string;
+  modulePath: string | null;
+  crateId: string | null;
+  site: UnsafeSiteDto;
+};
+
+export type UnsafeInventoryDto = {
+  totals: UnsafeRollupDto;
+  sites: UnsafeInventorySiteDto[];
+};
+
+export type CouplingMetricsDto = {
+  afferentCoupling: number;
+  efferentCoupling: number;
+  instability: number;
+  abstractTypes: number;
+  concreteTypes: number;
+  abstractness: number;
+  distanceFromMainSequence: number;
+};
+
+export type FunctionComplexityDto = {
+  linesOfCode: number;
+  cyclomaticComplexity: number;
+  maxNestingDepth: number;
+  parameterCount: number;
+  questionMarkCount: number;
+  unwrapCount: number;
+  expectCount: number;
+  awaitCount: number;
+};
+
+export type ComplexityRollupDto = {
+  functionCount: number;
+  linesOfCode: number;
+  totalCyclomaticComplexity: number;
+  maxCyclomaticComplexity: number;
+  maxNestingDepth: number;
+  questionMarkCount: number;
+  unwrapCount: number;
+  expectCount: number;
+  awaitCount: number;
+};
+
+export type FunctionComplexityMetric =
+  | 'lines_of_code'
+  | 'cyclomatic_complexity'
+  | 'max_nesting_depth'
+  | 'parameter_count'
+  | 'question_mark_count'
+  | 'unwrap_count'
+  | 'expect_count'
+  | 'await_count';
+
+export type ComplexityOffenderDto = {
+  nodeId: string;
+  kind: GraphNodeKind;
+  label: string;
+  sourceRange: SourceRangeDto | null;
+  complexity: FunctionComplexityDto;
+};
+
+export type CoverageMetricsDto = {
+  coveredLines: number;
+  totalLines: number;
+  ratio: number;
+};
+
+export type CoverageFormatDto = 'lcov' | 'cobertura' | 'llvm_cov_json';
+
+export type CoverageImportDto = {
+  reportPath: string;
+  format: CoverageFormatDto;
+  matchedFiles: number;
+  unmatchedFiles: string[];
+  totals: CoverageMetricsDto;
+};
+
+export type UncoveredFunctionDto = {
+  nodeId: string;
+  kind: GraphNodeKind;
+  label: string;
+  publicPaths: string[];
+  range: SourceRangeDto;
+  totalLines: number;
+};
+
+export type UncoveredFunctionsReportDto = {
+  functions: UncoveredFunctionDto[];
+};
+
+export type NodeMetricsDto = {
+  coupling: CouplingMetricsDto | null;
+  complexity: FunctionComplexityDto | null;
+  complexityRollup: ComplexityRollupDto | null;
+  unsafeRollup: UnsafeRollupDto | null;
+  coverage: CoverageMetricsDto | null;
+};
+
+export type GraphMetricsReportDto = {
+  sourceRoot: string;
+  generatedAt: string;
+  metrics: Record<string, NodeMetricsDto>;
 };
 
 export type GraphEdgeDto = {
@@ -87,12 +386,30 @@ export type GraphEdgeDto = {
   targetId: string;
   provenance: 'rust_analyzer' | 'syn' | 'normalized';
   confidence: 'exact' | 'inferred' | 'partial';
+  evidence: SourceRangeDto | null;
+  dependency: CrateDependencyDto | null;
+  cfg: string | null;
+};
+
+export type DependencyKindDto = 'normal' | 'dev' | 'build';
+
+export type CrateDependencyDto = {
+  kinds: DependencyKindDto[];
+  versionReq: string;
+  resolvedVersion: string;
+  optional: boolean;
+  defaultFeatures: boolean;
+  requestedFeatures: string[];
+  enabledFeatures: string[];
+  rename: string | null;
+  targets: string[];
 };
 
 export type GraphDiagnosticDto = {
   code: string;
   message: string;
   sourcePath: string | null;
+  evidence: SourceRangeDto | null;
 };
 
 export type GraphSnapshotDto = {
@@ -102,12 +419,104 @@ export type GraphSnapshotDto = {
   nodes: GraphNodeDto[];
   edges: GraphEdgeDto[];
   diagnostics: GraphDiagnosticDto[];
+  configuration: ActiveBuildConfigurationDto | null;
+};
+
+export type BuildConfigurationDto = {
+  features?: string[];
+  allFeatures?: boolean;
+  noDefaultFeatures?: boolean;
+  targetOs?: string | null;
+  targetFamily?: string | null;
+  targetArch?: string | null;
+  targetPointerWidth?: string | null;
+  targetEndian?: string | null;
+  targetEnv?: string | null;
+  test?: boolean;
+  release?: boolean;
+  cfgs?: string[];
+  pruneInactive?: boolean;
+};
+
+export type ActiveBuildConfigurationDto = {
+  featuresByCrate: Record<string, string[]>;
+  targetOs: string;
+  targetFamily: string;
+  targetArch: string;
+  targetPointerWidth: string;
+  targetEndian: string;
+  targetEnv: string;
+  test: boolean;
+  debugAssertions: boolean;
+  cfgs: string[];
+  pruneInactive: boolean;
 };
 
 export type DirectoryGraphNodeKind = 'repo' | 'directory' | 'file';
 
 export type DirectoryGraphEdgeKind = 'tree';
 
+export type ChurnWindowDto = {
+  sinceDays: number | null;
+};
+
+export type CoChangeOptionsDto = {
+  window?: ChurnWindowDto;
+  minSupport?: number;
+  minConfidence?: number;
+  maxFilesPerCommit?: number;
+};
+
+export type LastCommitDto = {
+  hash: string;
+  authorName: string;
+  timestamp: number;
+};
+
+export type FileChurnDto = {
+  commitCount: number;
+  authorCount: number;
+  linesAdded: number;
+  linesRemoved: number;
+  lastCommit: LastCommitDto;
+};
+
+export type HotspotMeasureDto = 'cyclomatic_complexity' | 'lines_of_code';
+
+export type HotspotDto = {
+  path: string;
+  churn: FileChurnDto;
+  measure: HotspotMeasureDto;
+  measureValue: number;
+  score: number;
+};
+
+export type HotspotReportDto = {
+  window: ChurnWindowDto;
+  hotspots: HotspotDto[];
+};
+
+export type CodeOwnershipDto = {
+  owners: string[];
+  pattern: string;
+  sourcePath: string;
+  line: number;
+};
+
+export type CrossOwnerEdgeDto = {
+  edgeId: string;
+  kind: FileRelationEdgeKind;
+  fromPath: string;
+  toPath: string;
+  fromOwners: string[];
+  toOwners: string[];
+  weight: number;
+};
+
+export type CrossOwnerReportDto = {
+  edges: CrossOwnerEdgeDto[];
+};
+
 export type DirectoryGraphNodeDto = {
   id: string;
   kind: DirectoryGraphNodeKind;
@@ -116,6 +525,7 @@ export type DirectoryGraphNodeDto = {
   parentId: string | null;
   childIds: string[];
   expanded: boolean;
+  churn: FileChurnDto | null;
 };
 
 export type DirectoryGraphEdgeDto = {
@@ -156,7 +566,8 @@ export type FileRelationEdgeKind =
   | 'copies_data'
   | 'tests'
   | 'configures'
-  | 'implements_contract';
+  | 'implements_contract'
+  | 'co_changes';
 
 export type FileRelationDirectionDto = 'directed' | 'undirected';
 
@@ -172,7 +583,8 @@ export type FileRelationEvidenceKind =
   | 'copy'
   | 'test_coverage'
   | 'configuration'
-  | 'contract_implementation';
+  | 'contract_implementation'
+  | 'co_change';
 
 export type FileRelationAccessDto = 'read' | 'write' | 'borrow' | 'mutable_borrow' | 'copy';
 
@@ -184,6 +596,8 @@ export type FileRelationNodeDto = {
   parentId: string | null;
   childIds: string[];
   language: SourceLanguageDto | null;
+  churn: FileChurnDto | null;
+  ownership: CodeOwnershipDto | null;
 };
 
 export type FileRelationEvidenceDto = {
@@ -194,6 +608,13 @@ export type FileRelationEvidenceDto = {
   targetLabel: string | null;
   access: FileRelationAccessDto | null;
   analyzer: string;
+  reexportHops: FileRelationReexportHopDto[];
+  commit: string | null;
+};
+
+export type FileRelationReexportHopDto = {
+  exportPath: string;
+  sourceRange: SourceRangeDto;
 };
 
 export type FileRelationEdgeDto = {
@@ -234,6 +655,141 @@ export type SourceSnippetDto = {
   text: string;
 };
 
+export type DependencyCycleGranularity = 'file' | 'module' | 'crate';
+
+export type DependencyCycleEdgeDto = {
+  fromMemberId: string;
+  toMemberId: string;
+  edgeId: string;
+  kind: GraphEdgeKind;
+  evidence: SourceRangeDto | null;
+};
+
+export type DependencyCycleDto = {
+  id: string;
+  granularity: DependencyCycleGranularity;
+  memberIds: string[];
+  memberLabels: string[];
+  cyclePath: string[];
+  closingEdges: DependencyCycleEdgeDto[];
+};
+
+export type DependencyCycleReportDto = {
+  granularity: DependencyCycleGranularity;
+  cycles: DependencyCycleDto[];
+};
+
+export type DeadCodeConfidenceDto = 'low' | 'medium' | 'high';
+
+export type DeadCodeItemDto = {
+  nodeId: string;
+  kind: GraphNodeKind;
+  label: string;
+  sourceRange: SourceRangeDto | null;
+  confidence: DeadCodeConfidenceDto;
+  reason: string;
+};
+
+export type DeadCodeReportDto = {
+  rootCount: number;
+  items: DeadCodeItemDto[];
+};
+
+export type GraphQueryDirectionDto = 'outgoing' | 'incoming' | 'both';
+
+export type GraphNodeFilterDto = {
+  kinds?: GraphNodeKind[];
+  pathGlob?: string | null;
+  label?: string | null;
+  publicApiOnly?: boolean;
+};
+
+export type GraphQueryDto =
+  | {
+      operation: 'neighborhood';
+      nodeId: string;
+      hops: number;
+      edgeKinds?: GraphEdgeKind[];
+      direction?: GraphQueryDirectionDto;
+    }
+  | {
+      operation: 'shortest_paths';
+      fromNodeId: string;
+      toNodeId: string;
+      edgeKinds?: GraphEdgeKind[];
+      direction?: GraphQueryDirectionDto;
+    }
+  | { operation: 'filter'; filter: GraphNodeFilterDto }
+  | {
+      operation: 'subgraph';
+      rootNodeIds: string[];
+      depth: number;
+      edgeKinds?: GraphEdgeKind[];
+      direction?: GraphQueryDirectionDto;
+      filter?: GraphNodeFilterDto;
+    };
+
+export type GraphQueryResultDto = {
+  snapshot: GraphSnapshotDto;
+  paths: string[][];
+};
+
+export type SymbolSearchFieldDto = 'label' | 'qualified_name' | 'path';
+
+export type SymbolSearchMatchDto = {
+  nodeId: string;
+  kind: GraphNodeKind;
+  label: string;
+  qualifiedName: string;
+  sourceRange: SourceRangeDto | null;
+  matchedField: SymbolSearchFieldDto;
+  score: number;
+};
+
+export type PublicApiItemDto = {
+  path: string;
+  nodeId: string;
+  kind: GraphNodeKind;
+  signature: string | null;
+  sourceRange: SourceRangeDto | null;
+};
+
+export type CratePublicApiDto = {
+  crateId: string;
+  crateName: string;
+  items: PublicApiItemDto[];
+};
+
+export type PublicApiReportDto = {
+  crates: CratePublicApiDto[];
+};
+
+export type PublicApiChangeKindDto = 'added' | 'removed' | 'signature_changed';
+
+export type SemverImpactDto = 'patch' | 'minor' | 'major';
+
+export type PublicApiChangeDto = {
+  path: string;
+  kind: GraphNodeKind;
+  change: PublicApiChangeKindDto;
+  baseSignature: string | null;
+  headSignature: string | null;
+  impact: SemverImpactDto;
+};
+
+export type CrateApiDiffDto = {
+  crateName: string;
+  impact: SemverImpactDto;
+  changes: PublicApiChangeDto[];
+};
+
+export type PublicApiDiffDto = {
+  baseRevision: string;
+  headRevision: string;
+  impact: SemverImpactDto;
+  crates: CrateApiDiffDto[];
+};
+
 export type CommandErrorDto = {
   code: string;
   message: string;
@@ -257,16 +813,32 @@ export class TauriArchitectureBackend {
     return invokeTauri<AnalysisStatusDto>('get_analysis_status');
   }
 
-  async analyzeSourceRepo(): Promise<GraphSnapshotDto> {
-    return invokeTauri<GraphSnapshotDto>('analyze_source_repo');
+  async analyzeSourceRepo(configuration?: BuildConfigurationDto): Promise<GraphSnapshotDto> {
+    return invokeTauri<GraphSnapshotDto>('analyze_source_repo', {
+      configuration: configuration ?? null,
+    });
   }
 
-  async loadDirectoryGraph(path: string): Promise<DirectoryGraphSnapshotDto> {
-    return invokeTauri<DirectoryGraphSnapshotDto>('load_directory_graph', { path });
+  async loadDirectoryGraph(
+    path: string,
+    churnWindow?: ChurnWindowDto,
+  ): Promise<DirectoryGraphSnapshotDto> {
+    return invokeTauri<DirectoryGraphSnapshotDto>('load_directory_graph', {
+      path,
+      churnWindow: churnWindow ?? null,
+    });
   }
 
-  async loadFileRelationGraph(path: string): Promise<FileRelationGraphSnapshotDto> {
-    return invokeTauri<FileRelationGraphSnapshotDto>('load_file_relation_graph', { path });
+  async loadFileRelationGraph(
+    path: string,
+    churnWindow?: ChurnWindowDto,
+    coChange?: CoChangeOptionsDto,
+  ): Promise<FileRelationGraphSnapshotDto> {
+    return invokeTauri<FileRelationGraphSnapshotDto>('load_file_relation_graph', {
+      path,
+      churnWindow: churnWindow ?? null,
+      coChange: coChange ?? null,
+    });
   }
 
   async getGraphSnapshot(): Promise<GraphSnapshotDto | null> {
@@ -277,6 +849,86 @@ export class TauriArchitectureBackend {
     return invokeTauri<SourceSnippetDto>('get_source_snippet', { nodeId });
   }
 
+  async getDependencyCycles(
+    granularity: DependencyCycleGranularity,
+  ): Promise<DependencyCycleReportDto> {
+    return invokeTauri<DependencyCycleReportDto>('get_dependency_cycles', { granularity });
+  }
+
+  async getGraphMetrics(): Promise<GraphMetricsReportDto> {
+    return invokeTauri<GraphMetricsReportDto>('get_graph_metrics');
+  }
+
+  async getComplexityOffenders(
+    metric: FunctionComplexityMetric,
+    limit: number,
+  ): Promise<ComplexityOffenderDto[]> {
+    return invokeTauri<ComplexityOffenderDto[]>('get_complexity_offenders', { metric, limit });
+  }
+
+  async getDeadCode(): Promise<DeadCodeReportDto> {
+    return invokeTauri<DeadCodeReportDto>('get_dead_code');
+  }
+
+  async queryGraph(query: GraphQueryDto): Promise<GraphQueryResultDto> {
+    return invokeTauri<GraphQueryResultDto>('query_graph', { query });
+  }
+
+  async searchSymbols(query: string, limit?: number): Promise<SymbolSearchMatchDto[]> {
+    return invokeTauri<SymbolSearchMatchDto[]>('search_symbols', { query, limit: limit ?? null });
+  }
+
+  async getPublicApi(): Promise<PublicApiReportDto> {
+    return invokeTauri<PublicApiReportDto>('get_public_api');
+  }
+
+  async getPublicApiDiff(baseRevision: string, headRevision?: string): Promise<PublicApiDiffDto> {
+    return invokeTauri<PublicApiDiffDto>('get_public_api_diff', {
+      baseRevision,
+      headRevision: headRevision ?? null,
+    });
+  }
+
+  async getUnsafeInventory(): Promise<UnsafeInventoryDto> {
+    return invokeTauri<UnsafeInventoryDto>('get_unsafe_inventory');
+  }
+
+  async getConcurrencyTopology(): Promise<GraphSnapshotDto> {
+    return invokeTauri<GraphSnapshotDto>('get_concurrency_topology');
+  }
+
+  async getErrorFlow(): Promise<ErrorFlowReportDto> {
+    return invokeTauri<ErrorFlowReportDto>('get_error_flow');
+  }
+
+  async getWireContracts(): Promise<WireContractCatalogDto> {
+    return invokeTauri<WireContractCatalogDto>('get_wire_contracts');
+  }
+
+  async getSchemaBundle(): Promise<Record<string, unknown>> {
+    return invokeTauri<Record<string, unknown>>('get_schema_bundle');
+  }
+
+  async getStateMachines(): Promise<StateMachineReportDto> {
+    return invokeTauri<StateMachineReportDto>('get_state_machines');
+  }
+
+  async importCoverage(reportPath: string): Promise<CoverageImportDto> {
+    return invokeTauri<CoverageImportDto>('import_coverage', { reportPath });
+  }
+
+  async getUncoveredPublicFunctions(): Promise<UncoveredFunctionsReportDto> {
+    return invokeTauri<UncoveredFunctionsReportDto>('get_uncovered_public_functions');
+  }
+
+  async getHotspots(window: ChurnWindowDto, limit?: number): Promise<HotspotReportDto> {
+    return invokeTauri<HotspotReportDto>('get_hotspots', { window, limit: limit ?? null });
+  }
+
+  async getCrossOwnerDependencies(): Promise<CrossOwnerReportDto> {
+    return invokeTauri<CrossOwnerReportDto>('get_cross_owner_dependencies');
+  }
+
   async setSourceRepoPath(path: string): Promise<AppConfigDto> {
     return invokeTauri<AppConfigDto>('set_source_repo_path', { path });
   }
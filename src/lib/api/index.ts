@@ -1,15 +1,49 @@
 export type {
+  ActiveBuildConfigurationDto,
   AppConfigDto,
   AppStatusDto,
   AnalysisStatusDto,
   AnalyzerDiagnosticDto,
   AnalyzerLifecyclePhase,
+  BuildConfigurationDto,
+  ChurnWindowDto,
+  CoChangeOptionsDto,
+  CodeOwnershipDto,
   CommandErrorDto,
+  ComplexityOffenderDto,
+  ComplexityRollupDto,
+  CouplingMetricsDto,
+  CoverageFormatDto,
+  CoverageImportDto,
+  CoverageMetricsDto,
+  CrateApiDiffDto,
+  CrateDependencyDto,
+  CratePublicApiDto,
+  CrossOwnerEdgeDto,
+  CrossOwnerReportDto,
+  DeadCodeConfidenceDto,
+  DeadCodeItemDto,
+  DeadCodeReportDto,
+  DependencyCycleDto,
+  DependencyCycleEdgeDto,
+  DependencyCycleGranularity,
+  DependencyCycleReportDto,
+  DependencyKindDto,
   DirectoryGraphEdgeDto,
   DirectoryGraphEdgeKind,
   DirectoryGraphNodeDto,
   DirectoryGraphNodeKind,
   DirectoryGraphSnapshotDto,
+  EnumTaggingDto,
+  ErrorConversionDto,
+  ErrorConversionKindDto,
+  ErrorDetailDto,
+  ErrorFlowFactsDto,
+  ErrorFlowRelationDto,
+  ErrorFlowReportDto,
+  ErrorFlowSiteDto,
+  ErrorTypeUsageDto,
+  FileChurnDto,
   FileRelationAccessDto,
   FileRelationDirectionDto,
   FileRelationEdgeDto,
@@ -19,15 +53,57 @@ export type {
   FileRelationGraphSnapshotDto,
   FileRelationNodeDto,
   FileRelationNodeKind,
+  FileRelationReexportHopDto,
+  FunctionComplexityDto,
+  FunctionComplexityMetric,
   GraphDiagnosticDto,
   GraphEdgeDto,
   GraphEdgeKind,
+  GraphMetricsReportDto,
   GraphNodeDto,
+  GraphNodeFilterDto,
   GraphNodeKind,
+  GraphQueryDirectionDto,
+  GraphQueryDto,
+  GraphQueryResultDto,
   AnalyzerRunDto,
   GraphSnapshotDto,
+  HotspotDto,
+  HotspotMeasureDto,
+  HotspotReportDto,
+  LastCommitDto,
+  NodeMetricsDto,
+  NodeVisibilityDto,
+  PublicApiChangeDto,
+  PublicApiChangeKindDto,
+  PublicApiDiffDto,
+  PublicApiItemDto,
+  PublicApiReportDto,
+  SemverImpactDto,
   SourceLanguageDto,
   SourceRangeDto,
   SourceSnippetDto,
   SourceRepoStatusDto,
+  StateAssignmentDto,
+  StateMachineDto,
+  StateMachineReportDto,
+  StateTransitionDto,
+  StateTransitionKindDto,
+  SymbolSearchFieldDto,
+  SymbolSearchMatchDto,
+  TrySiteDto,
+  UncoveredFunctionDto,
+  UncoveredFunctionsReportDto,
+  UnsafeInventoryDto,
+  UnsafeInventorySiteDto,
+  UnsafeRollupDto,
+  UnsafeSiteDto,
+  UnsafeSiteKindDto,
+  WireContractCatalogDto,
+  WireContractDto,
+  WireContractEntryDto,
+  WireFieldDto,
+  WireShapeDto,
+  WireTypeDto,
+  WireVariantDto,
 } from '../../backends/TauriArchitectureBackend';
@@ -33,6 +33,7 @@ test('directorySnapshotToRenderGraph preserves backend graph identity', () => {
         parentId: null,
         childIds: ['dir:src'],
         expanded: true,
+        churn: null,
       },
       {
         id: 'dir:src',
@@ -42,6 +43,7 @@ test('directorySnapshotToRenderGraph preserves backend graph identity', () => {
         parentId: 'repo:.',
         childIds: [],
         expanded: false,
+        churn: null,
       },
     ],
     edges: [
@@ -101,6 +103,8 @@ test('fileRelationSnapshotToRenderGraph preserves cross-file relation metadata',
         parentId: null,
         childIds: ['dir:src'],
         language: null,
+        churn: null,
+        ownership: null,
       },
       {
         id: 'dir:src',
@@ -110,6 +114,8 @@ test('fileRelationSnapshotToRenderGraph preserves cross-file relation metadata',
         parentId: 'repo:.',
         childIds: ['file:src/main.rs', 'file:src/lib.rs'],
         language: null,
+        churn: null,
+        ownership: null,
       },
       {
         id: 'file:src/main.rs',
@@ -119,6 +125,8 @@ test('fileRelationSnapshotToRenderGraph preserves cross-file relation metadata',
         parentId: 'dir:src',
         childIds: [],
         language: 'rust',
+        churn: null,
+        ownership: null,
       },
       {
         id: 'file:src/lib.rs',
@@ -128,6 +136,8 @@ test('fileRelationSnapshotToRenderGraph preserves cross-file relation metadata',
         parentId: 'dir:src',
         childIds: [],
         language: 'rust',
+        churn: null,
+        ownership: null,
       },
     ],
     edges: [
@@ -156,6 +166,8 @@ test('fileRelationSnapshotToRenderGraph preserves cross-file relation metadata',
             targetLabel: 'lib',
             access: null,
             analyzer: 'syn',
+            reexportHops: [],
+            commit: null,
           },
         ],
       },
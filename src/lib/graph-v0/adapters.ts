@@ -63,6 +63,7 @@ function relationDetailsForEdgeKind(kind: FileRelationEdgeKind): readonly FileRe
     case 'imports':
       return ['imports'];
     case 'calls':
+    case 'co_changes':
       return ['calls'];
     case 'references_type':
     case 'passes_data':
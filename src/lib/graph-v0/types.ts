@@ -13,7 +13,8 @@ export type GraphEdgeKind =
   | 'copies_data'
   | 'tests'
   | 'configures'
-  | 'implements_contract';
+  | 'implements_contract'
+  | 'co_changes';
 
 export type GraphEdgeDirection = 'directed' | 'undirected';
 
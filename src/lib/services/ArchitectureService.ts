@@ -1,11 +1,33 @@
 import type {
   AppConfigDto,
   AnalysisStatusDto,
+  BuildConfigurationDto,
+  ChurnWindowDto,
+  CoChangeOptionsDto,
   CommandErrorDto,
+  ComplexityOffenderDto,
+  CoverageImportDto,
+  CrossOwnerReportDto,
+  DeadCodeReportDto,
+  DependencyCycleGranularity,
+  DependencyCycleReportDto,
   DirectoryGraphSnapshotDto,
+  ErrorFlowReportDto,
   FileRelationGraphSnapshotDto,
+  FunctionComplexityMetric,
+  GraphMetricsReportDto,
+  GraphQueryDto,
+  GraphQueryResultDto,
   GraphSnapshotDto,
+  HotspotReportDto,
+  PublicApiDiffDto,
+  PublicApiReportDto,
   SourceSnippetDto,
+  StateMachineReportDto,
+  SymbolSearchMatchDto,
+  UncoveredFunctionsReportDto,
+  UnsafeInventoryDto,
+  WireContractCatalogDto,
 } from '../../backends/TauriArchitectureBackend';
 import { TauriArchitectureBackend } from '../../backends/TauriArchitectureBackend.ts';
 
@@ -24,16 +46,23 @@ export class ArchitectureService {
     return this.backend.getAnalysisStatus();
   }
 
-  analyzeSourceRepo(): Promise<GraphSnapshotDto> {
-    return this.backend.analyzeSourceRepo();
+  analyzeSourceRepo(configuration?: BuildConfigurationDto): Promise<GraphSnapshotDto> {
+    return this.backend.analyzeSourceRepo(configuration);
   }
 
-  loadDirectoryGraph(path: string): Promise<DirectoryGraphSnapshotDto> {
-    return this.backend.loadDirectoryGraph(path.trim());
+  loadDirectoryGraph(
+    path: string,
+    churnWindow?: ChurnWindowDto,
+  ): Promise<DirectoryGraphSnapshotDto> {
+    return this.backend.loadDirectoryGraph(path.trim(), churnWindow);
   }
 
-  loadFileRelationGraph(path: string): Promise<FileRelationGraphSnapshotDto> {
-    return this.backend.loadFileRelationGraph(path.trim());
+  loadFileRelationGraph(
+    path: string,
+    churnWindow?: ChurnWindowDto,
+    coChange?: CoChangeOptionsDto,
+  ): Promise<FileRelationGraphSnapshotDto> {
+    return this.backend.loadFileRelationGraph(path.trim(), churnWindow, coChange);
   }
 
   getGraphSnapshot(): Promise<GraphSnapshotDto | null> {
@@ -44,6 +73,83 @@ export class ArchitectureService {
     return this.backend.getSourceSnippet(nodeId);
   }
 
+  getDependencyCycles(granularity: DependencyCycleGranularity): Promise<DependencyCycleReportDto> {
+    return this.backend.getDependencyCycles(granularity);
+  }
+
+  getGraphMetrics(): Promise<GraphMetricsReportDto> {
+    return this.backend.getGraphMetrics();
+  }
+
+  getComplexityOffenders(
+    metric: FunctionComplexityMetric,
+    limit: number,
+  ): Promise<ComplexityOffenderDto[]> {
+    return this.backend.getComplexityOffenders(metric, limit);
+  }
+
+  getDeadCode(): Promise<DeadCodeReportDto> {
+    return this.backend.getDeadCode();
+  }
+
+  queryGraph(query: GraphQueryDto): Promise<GraphQueryResultDto> {
+    return this.backend.queryGraph(query);
+  }
+
+  searchSymbols(query: string, limit?: number): Promise<SymbolSearchMatchDto[]> {
+    const trimmed = query.trim();
+    return trimmed ? this.backend.searchSymbols(trimmed, limit) : Promise.resolve([]);
+  }
+
+  getPublicApi(): Promise<PublicApiReportDto> {
+    return this.backend.getPublicApi();
+  }
+
+  getPublicApiDiff(baseRevision: string, headRevision?: string): Promise<PublicApiDiffDto> {
+    const head = headRevision?.trim();
+    return this.backend.getPublicApiDiff(baseRevision.trim(), head ? head : undefined);
+  }
+
+  getUnsafeInventory(): Promise<UnsafeInventoryDto> {
+    return this.backend.getUnsafeInventory();
+  }
+
+  getConcurrencyTopology(): Promise<GraphSnapshotDto> {
+    return this.backend.getConcurrencyTopology();
+  }
+
+  getErrorFlow(): Promise<ErrorFlowReportDto> {
+    return this.backend.getErrorFlow();
+  }
+
+  getWireContracts(): Promise<WireContractCatalogDto> {
+    return this.backend.getWireContracts();
+  }
+
+  getSchemaBundle(): Promise<Record<string, unknown>> {
+    return this.backend.getSchemaBundle();
+  }
+
+  getStateMachines(): Promise<StateMachineReportDto> {
+    return this.backend.getStateMachines();
+  }
+
+  importCoverage(reportPath: string): Promise<CoverageImportDto> {
+    return this.backend.importCoverage(reportPath.trim());
+  }
+
+  getUncoveredPublicFunctions(): Promise<UncoveredFunctionsReportDto> {
+    return this.backend.getUncoveredPublicFunctions();
+  }
+
+  getHotspots(window: ChurnWindowDto, limit?: number): Promise<HotspotReportDto> {
+    return this.backend.getHotspots(window, limit);
+  }
+
+  getCrossOwnerDependencies(): Promise<CrossOwnerReportDto> {
+    return this.backend.getCrossOwnerDependencies();
+  }
+
   setSourceRepoPath(path: string): Promise<AppConfigDto> {
     return this.backend.setSourceRepoPath(path.trim());
   }
@@ -21,12 +21,34 @@ const nodes: GraphNodeDto[] = [
       endLine: 1,
       endColumn: 1,
     },
+    metrics: null,
+    visibility: null,
+    attributes: [],
+    derives: [],
+    signature: null,
+    publicPaths: [],
+    unsafeSites: [],
+    errorFlow: null,
+    wireContract: null,
+    stateAssignments: [],
+    cfg: null,
   },
   {
     id: 'struct:widget',
     kind: 'struct',
     label: 'Widget',
     sourceRange: null,
+    metrics: null,
+    visibility: null,
+    attributes: [],
+    derives: [],
+    signature: null,
+    publicPaths: [],
+    unsafeSites: [],
+    errorFlow: null,
+    wireContract: null,
+    stateAssignments: [],
+    cfg: null,
   },
   {
     id: 'file:src/lib.rs',
@@ -39,6 +61,17 @@ const nodes: GraphNodeDto[] = [
       endLine: 1,
       endColumn: 1,
     },
+    metrics: null,
+    visibility: null,
+    attributes: [],
+    derives: [],
+    signature: null,
+    publicPaths: [],
+    unsafeSites: [],
+    errorFlow: null,
+    wireContract: null,
+    stateAssignments: [],
+    cfg: null,
   },
   {
     id: 'file:src/domain.rs',
@@ -51,6 +84,17 @@ const nodes: GraphNodeDto[] = [
       endLine: 1,
       endColumn: 1,
     },
+    metrics: null,
+    visibility: null,
+    attributes: [],
+    derives: [],
+    signature: null,
+    publicPaths: [],
+    unsafeSites: [],
+    errorFlow: null,
+    wireContract: null,
+    stateAssignments: [],
+    cfg: null,
   },
 ];
 
@@ -62,6 +106,9 @@ const edges: GraphEdgeDto[] = [
     targetId: 'function:entry',
     provenance: 'syn',
     confidence: 'exact',
+    evidence: null,
+    dependency: null,
+    cfg: null,
   },
   {
     id: 'missing:endpoint',
@@ -70,6 +117,9 @@ const edges: GraphEdgeDto[] = [
     targetId: 'function:missing',
     provenance: 'syn',
     confidence: 'partial',
+    evidence: null,
+    dependency: null,
+    cfg: null,
   },
 ];
 
@@ -89,6 +139,18 @@ test('filterGraphNodes filters by kind and limit', () => {
   );
 });
 
+test('filterGraphNodes keeps only public API nodes when requested', () => {
+  const publicNodes = nodes.map((node) =>
+    node.id === 'struct:widget' ? { ...node, publicPaths: ['fixture::Widget'] } : node,
+  );
+  assert.deepEqual(
+    filterGraphNodes(publicNodes, { query: '', kinds: [], limit: 10, publicApiOnly: true }).map(
+      (node) => node.id,
+    ),
+    ['struct:widget'],
+  );
+});
+
 test('graphNodeKinds returns stable sorted kinds', () => {
   assert.deepEqual(graphNodeKinds(nodes), ['file', 'function', 'struct']);
 });
@@ -8,6 +8,8 @@ export type GraphNodeFilter = {
   query: string;
   kinds: GraphNodeKind[];
   limit: number;
+  /** Keeps only nodes with at least one public path. */
+  publicApiOnly?: boolean;
 };
 
 export function filterGraphNodes(
@@ -20,13 +22,14 @@ export function filterGraphNodes(
   return nodes
     .filter((node) => {
       const matchesKind = allowedKinds.size === 0 || allowedKinds.has(node.kind);
+      const matchesVisibility = !filter.publicApiOnly || node.publicPaths.length > 0;
       const matchesQuery =
         query.length === 0 ||
         node.label.toLowerCase().includes(query) ||
         node.kind.toLowerCase().includes(query) ||
         node.sourceRange?.path.toLowerCase().includes(query);
 
-      return matchesKind && matchesQuery;
+      return matchesKind && matchesVisibility && matchesQuery;
     })
     .slice(0, filter.limit);
 }
@@ -390,6 +393,7 @@ function nodeBaseRadius(kind: GraphNodeKind): number {
     case 'workspace':
     case 'crate':
       return 42;
+    case 'external_crate':
     case 'file':
       return 34;
     case 'module':